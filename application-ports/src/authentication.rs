@@ -12,7 +12,41 @@ pub trait AuthenticationPort {
         &'a mut self,
         csrf_token: CsrfToken,
         client_callback_token: ClientCallbackToken,
-    ) -> impl Future<Output = Result<UserId, AuthenticationError>> + Send + 'a;
+    ) -> impl Future<Output = Result<ConfirmedAuthenticationDto, AuthenticationError>> + Send + 'a;
+    /// Unlinks the school account from the Discord user, archiving the user
+    /// and revoking the OAuth token
+    fn unverify<'a>(
+        &'a mut self,
+        user_id: UserId,
+    ) -> impl Future<Output = Result<(), AuthenticationError>> + Send + 'a;
+    /// Verifies the user without the OAuth flow, on behalf of an admin
+    fn force_verify<'a>(
+        &'a mut self,
+        request: ForceVerifyDto,
+    ) -> impl Future<Output = Result<(), AuthenticationError>> + Send + 'a;
+}
+
+#[derive(Debug)]
+pub struct ConfirmedAuthenticationDto {
+    pub user_id: UserId,
+    /// The account previously verified with the same email, now archived
+    pub replaced_user: Option<ReplacedUserDto>,
+}
+
+#[derive(Debug)]
+pub struct ReplacedUserDto {
+    pub user_id: UserId,
+    pub archived_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug)]
+pub struct ForceVerifyDto {
+    pub user_id: UserId,
+    pub name: String,
+    pub email: String,
+    pub class_id: String,
+    pub verified_by: UserId,
+    pub reason: String,
 }
 
 #[derive(Debug, Error)]
@@ -23,4 +57,14 @@ pub enum AuthenticationError {
     TemporaryUnavailable,
     #[error("User authentication request was already confirmed")]
     AuthenticationRequestAlreadyConfirmed,
+    #[error("User authentication request has expired")]
+    AuthenticationRequestExpired,
+    #[error("Authenticated user was not found")]
+    AuthenticatedUserNotFound,
+    #[error("User is already verified")]
+    UserAlreadyVerified,
+    #[error("Email is already used by another user")]
+    EmailAlreadyUsed,
+    #[error("Class ID is not valid")]
+    InvalidClassId,
 }
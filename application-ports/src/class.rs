@@ -0,0 +1,54 @@
+use domain_shared::discord::RoleId;
+use std::future::Future;
+use thiserror::Error;
+
+pub trait ClassPort {
+    /// Lists the class catalog including the retired classes, ordered by the class ID
+    fn list_classes(&mut self) -> impl Future<Output = Result<Vec<ClassDto>, ClassError>> + Send;
+    /// Adds the class to the catalog, a retired class with the same ID is reactivated
+    fn add_class(
+        &mut self,
+        class: AddClassDto,
+    ) -> impl Future<Output = Result<ClassDto, ClassError>> + Send;
+    /// Retires the class, its role is removed from the students on their next role sync
+    fn retire_class(
+        &mut self,
+        class_id: &str,
+    ) -> impl Future<Output = Result<(), ClassError>> + Send;
+    /// Renames the class together with its Discord role
+    fn rename_class(
+        &mut self,
+        class_id: &str,
+        display_name: &str,
+    ) -> impl Future<Output = Result<(), ClassError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum ClassError {
+    #[error("Class ID is not valid")]
+    InvalidClassId,
+    #[error("Display name of the class is not valid")]
+    InvalidClassDetails,
+    #[error("Class already exists")]
+    ClassAlreadyExists,
+    #[error("Class not found")]
+    ClassNotFound,
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
+
+#[derive(Debug)]
+pub struct AddClassDto {
+    pub class_id: String,
+    pub display_name: Option<String>,
+    pub role_id: Option<RoleId>,
+}
+
+pub struct ClassDto {
+    pub class_id: String,
+    pub display_name: String,
+    pub role_id: Option<RoleId>,
+    pub year: u8,
+    pub field: Option<String>,
+    pub active: bool,
+}
@@ -0,0 +1,58 @@
+use domain_shared::discord::UserId;
+use std::future::Future;
+use thiserror::Error;
+
+pub trait ClassRolloverPort {
+    /// Takes the classes of all students and enqueues a user info sync for every verified user,
+    /// optionally moving the class roles one year up. A recently started rollover is only
+    /// started again when forced.
+    fn start_rollover(
+        &mut self,
+        shift_roles: bool,
+        force: bool,
+    ) -> impl Future<Output = Result<ClassRolloverStartedDto, ClassRolloverError>> + Send;
+    /// Compares the classes taken at the start of the last rollover with the current ones
+    fn get_rollover_report(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<ClassRolloverReportDto>, ClassRolloverError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum ClassRolloverError {
+    #[error("Rollover was already started at {0}")]
+    RecentlyStarted(chrono::DateTime<chrono::Utc>),
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
+
+#[derive(Debug)]
+pub struct ClassRolloverStartedDto {
+    pub students: u64,
+    pub users_enqueued: u64,
+    pub roles_moved: u64,
+}
+
+pub struct ClassRolloverReportDto {
+    pub taken_at: chrono::DateTime<chrono::Utc>,
+    pub students: u64,
+    pub advanced: u64,
+    pub graduated: u64,
+    pub class_changes: Vec<ClassChangeCountDto>,
+    /// Students whose class did not advance as expected
+    pub flagged: Vec<FlaggedClassChangeDto>,
+}
+
+pub struct ClassChangeCountDto {
+    pub previous_class_id: String,
+    pub current_class_id: Option<String>,
+    pub count: u64,
+}
+
+pub struct FlaggedClassChangeDto {
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub previous_class_id: String,
+    pub current_class_id: Option<String>,
+    pub expected_class_id: Option<String>,
+    pub not_advanced: bool,
+}
@@ -1,6 +1,14 @@
 pub mod authentication;
+pub mod class;
+pub mod class_rollover;
 pub mod information_channel;
+pub mod nickname;
 pub mod periodic_scheduling_handler;
+pub mod personal_data;
+pub mod retention_handler;
+pub mod role_log;
+pub mod role_reconciliation;
+pub mod role_sync_exemption;
 pub mod role_sync_job_handler;
 pub mod user;
 pub mod user_info_sync_job_handler;
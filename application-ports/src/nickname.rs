@@ -0,0 +1,30 @@
+use domain_shared::discord::UserId;
+use std::future::Future;
+use thiserror::Error;
+
+pub trait NicknamePort {
+    /// Sets whether the user opted out of the nickname policy, applied by the next role sync
+    fn set_nickname_opt_out(
+        &mut self,
+        user_id: UserId,
+        opt_out: bool,
+    ) -> impl Future<Output = Result<(), NicknameError>> + Send;
+    /// Overrides the preference of the user, `None` follows the preference of the user again
+    fn set_nickname_override(
+        &mut self,
+        user_id: UserId,
+        admin_override: Option<bool>,
+    ) -> impl Future<Output = Result<(), NicknameError>> + Send;
+}
+
+pub struct NicknameSettingsDto {
+    pub opt_out: bool,
+    pub admin_override: Option<bool>,
+    pub applied_nickname: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum NicknameError {
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
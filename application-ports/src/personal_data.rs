@@ -0,0 +1,55 @@
+use crate::nickname::NicknameSettingsDto;
+use crate::role_log::RoleChangeDto;
+use crate::role_sync_exemption::RoleSyncExemptionDto;
+use crate::user::{ArchivedUserInfoDto, AuthenticatedUserInfoDto};
+use domain_shared::discord::UserId;
+use std::future::Future;
+use thiserror::Error;
+
+pub trait PersonalDataPort {
+    /// Collects everything stored about the user, including the archived identities
+    /// sharing the email of the user
+    fn export_personal_data(
+        &mut self,
+        user_id: UserId,
+    ) -> impl Future<Output = Result<PersonalDataExportDto, PersonalDataError>> + Send;
+    /// Removes everything stored about the user, revoking the OAuth tokens and removing the roles
+    fn erase_personal_data(
+        &mut self,
+        user_id: UserId,
+    ) -> impl Future<Output = Result<PersonalDataErasureDto, PersonalDataError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum PersonalDataError {
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
+
+pub struct PersonalDataExportDto {
+    pub user_id: UserId,
+    pub authenticated_user: Option<AuthenticatedUserInfoDto>,
+    /// The tokens themselves are credentials and are never exported
+    pub oauth_token_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub archived_users: Vec<ArchivedUserInfoDto>,
+    pub authentication_requests: Vec<AuthenticationRequestInfoDto>,
+    pub role_changes: Vec<RoleChangeDto>,
+    pub nickname_settings: Option<NicknameSettingsDto>,
+    pub role_sync_exemption: Option<RoleSyncExemptionDto>,
+}
+
+pub struct AuthenticationRequestInfoDto {
+    pub requested_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub confirmed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug)]
+pub struct PersonalDataErasureDto {
+    pub authenticated_user_removed: bool,
+    pub archived_users_removed: u64,
+    pub authentication_requests_removed: u64,
+    pub role_changes_removed: u64,
+    pub nickname_settings_removed: bool,
+    pub role_sync_exemption_removed: bool,
+}
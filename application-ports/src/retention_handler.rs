@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[async_trait]
+pub trait RetentionHandlerPort {
+    async fn tick(&self) -> Result<(), RetentionHandlerError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RetentionHandlerError {
+    #[error("Service temporarily unavailable")]
+    TemporarilyUnavailable,
+}
@@ -0,0 +1,32 @@
+use domain_shared::discord::{RoleId, UserId};
+use std::future::Future;
+use thiserror::Error;
+
+pub trait RoleLogPort {
+    /// Finds the role changes applied to the user, the most recent first
+    fn get_role_log(
+        &mut self,
+        user_id: UserId,
+        offset: u64,
+        limit: u64,
+    ) -> impl Future<Output = Result<RoleLogPageDto, RoleLogError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum RoleLogError {
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
+
+pub struct RoleLogPageDto {
+    pub entries: Vec<RoleChangeDto>,
+    pub total: u64,
+}
+
+pub struct RoleChangeDto {
+    pub assigned: Vec<RoleId>,
+    pub removed: Vec<RoleId>,
+    pub reason: String,
+    pub low_priority: bool,
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,40 @@
+use domain_shared::discord::{RoleId, UserId};
+use std::future::Future;
+use thiserror::Error;
+
+pub trait RoleReconciliationPort {
+    /// Computes the role diff of every guild member as the role sync would, without applying it
+    fn reconcile_roles_dry_run(
+        &mut self,
+    ) -> impl Future<Output = Result<RoleReconciliationReportDto, RoleReconciliationError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum RoleReconciliationError {
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
+
+pub struct RoleReconciliationReportDto {
+    pub members: u64,
+    /// Active classes without a role, the role sync creates their roles on its next run
+    pub classes_without_role: Vec<String>,
+    pub role_changes: Vec<RoleChangeCountDto>,
+    /// Members whose roles would change, ordered by the user ID
+    pub member_diffs: Vec<MemberRoleDiffDto>,
+}
+
+pub struct RoleChangeCountDto {
+    pub role_id: RoleId,
+    pub role_name: Option<String>,
+    pub assigned: u64,
+    pub removed: u64,
+}
+
+pub struct MemberRoleDiffDto {
+    pub user_id: UserId,
+    /// Name of the verified user, unverified members have none
+    pub name: Option<String>,
+    pub assigned: Vec<RoleId>,
+    pub removed: Vec<RoleId>,
+}
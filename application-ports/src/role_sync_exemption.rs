@@ -0,0 +1,47 @@
+use domain_shared::discord::{RoleId, UserId};
+use std::future::Future;
+use thiserror::Error;
+
+pub trait RoleSyncExemptionPort {
+    /// Exempts the target from the role sync, replacing its previous exemption
+    fn add_exemption(
+        &mut self,
+        target: ExemptionTargetDto,
+        reason: String,
+        created_by: UserId,
+    ) -> impl Future<Output = Result<(), RoleSyncExemptionError>> + Send;
+    /// Removes the exemption of the target, an exempted user gets their roles synced again
+    fn remove_exemption(
+        &mut self,
+        target: ExemptionTargetDto,
+    ) -> impl Future<Output = Result<(), RoleSyncExemptionError>> + Send;
+    fn list_exemptions(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<RoleSyncExemptionDto>, RoleSyncExemptionError>> + Send;
+    /// Finds the exemptions applying to the guild member, either directly or by their roles
+    fn find_user_exemptions(
+        &mut self,
+        user_id: UserId,
+    ) -> impl Future<Output = Result<Vec<RoleSyncExemptionDto>, RoleSyncExemptionError>> + Send;
+}
+
+#[derive(Debug, Error)]
+pub enum RoleSyncExemptionError {
+    #[error("Exemption not found")]
+    ExemptionNotFound,
+    #[error("Service is temporarily unavailable")]
+    TemporaryUnavailable,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ExemptionTargetDto {
+    User(UserId),
+    Role(RoleId),
+}
+
+pub struct RoleSyncExemptionDto {
+    pub target: ExemptionTargetDto,
+    pub reason: String,
+    pub created_by: UserId,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
@@ -1,4 +1,5 @@
 use chrono::Duration;
+use domain_shared::authentication::UserKind;
 use domain_shared::discord::UserId;
 use std::future::Future;
 use thiserror::Error;
@@ -12,10 +13,27 @@ pub trait UserPort {
         &mut self,
         user_id: UserId,
     ) -> impl Future<Output = Result<(), UserError>> + Send;
+    /// Syncs the roles of a member who joined the guild
+    fn sync_joined_member_roles(
+        &mut self,
+        user_id: UserId,
+    ) -> impl Future<Output = Result<(), UserError>> + Send;
     fn refresh_user_info(
         &mut self,
         user_id: UserId,
     ) -> impl Future<Output = Result<Duration, UserError>> + Send;
+    /// Lists the archived identities matching the query, the most recently archived first
+    fn get_user_history(
+        &mut self,
+        query: UserHistoryQuery,
+    ) -> impl Future<Output = Result<Vec<ArchivedUserInfoDto>, UserError>> + Send;
+    /// Searches verified users by their name, email or class, ignoring case and accents
+    fn search_users(
+        &mut self,
+        query: &str,
+        offset: u64,
+        limit: u64,
+    ) -> impl Future<Output = Result<UserSearchPageDto, UserError>> + Send;
 }
 
 #[derive(Debug, Error)]
@@ -27,9 +45,39 @@ pub enum UserError {
 }
 
 pub struct AuthenticatedUserInfoDto {
+    pub user_id: UserId,
+    pub name: String,
+    pub email: String,
+    pub kind: UserKind,
+    pub class_id: Option<String>,
+    pub graduation_year: Option<i32>,
+    pub manual_verification: Option<ManualVerificationDto>,
+    pub authenticated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ManualVerificationDto {
+    pub verified_by: UserId,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum UserHistoryQuery {
+    UserId(UserId),
+    Email(String),
+}
+
+pub struct ArchivedUserInfoDto {
     pub user_id: UserId,
     pub name: String,
     pub email: String,
     pub class_id: Option<String>,
     pub authenticated_at: chrono::DateTime<chrono::Utc>,
+    pub archived_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct UserSearchPageDto {
+    pub users: Vec<AuthenticatedUserInfoDto>,
+    /// The number of all matching users, not only the ones on the page
+    pub total: u64,
 }
@@ -1,23 +1,32 @@
-use application_ports::authentication::{AuthenticationError, AuthenticationPort};
+use application_ports::authentication::{
+    AuthenticationError, AuthenticationPort, ConfirmedAuthenticationDto, ForceVerifyDto,
+    ReplacedUserDto,
+};
+use chrono::Duration;
 use domain::authentication::archived_authenticated_user::{
     ArchivedAuthenticatedUserRepository, ArchivedAuthenticatedUserRepositoryError,
-    create_archived_authenticated_user_from_user,
+    create_archived_authenticated_user_from_user, create_archived_authenticated_user_without_token,
 };
 use domain::authentication::authenticated_user::{
-    AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
-    create_user_from_successful_authentication,
+    AuthenticatedUserRepository, AuthenticatedUserRepositoryError, ManualVerification,
+    create_manually_verified_user, create_user_from_successful_authentication,
 };
+use domain::authentication::group_role_rule::{GroupRoleRule, find_rule_group_ids};
 use domain::authentication::user_authentication_request::{
     UserAuthenticationRequestRepository, UserAuthenticationRequestRepositoryError,
     create_user_authentication_request,
 };
+use domain::authentication::user_kind::find_user_kind;
+use domain::class::catalog::{ClassRepository, ClassRepositoryError};
+use domain::class::class_id::ClassId;
 use domain::jobs::role_sync_job::{
     RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError, request_role_sync,
 };
+use domain::jobs::sync_trigger::SyncTrigger;
 use domain::jobs::user_info_sync_job::{
     UserInfoSyncRequestedRepository, UserInfoSyncRequestedRepositoryError, request_user_info_sync,
 };
-use domain::ports::oauth::{OAuthError, OAuthPort};
+use domain::ports::oauth::{AuthenticationLinkDto, OAuthError, OAuthPort};
 use domain_shared::authentication::{AuthenticationLink, ClientCallbackToken, CsrfToken};
 use domain_shared::discord::UserId;
 use tracing::{Span, error, info, instrument, warn};
@@ -25,6 +34,7 @@ use tracing::{Span, error, info, instrument, warn};
 pub struct AuthenticationService<
     TArchivedAuthenticatedUserRepository,
     TAuthenticatedUserRepository,
+    TClassRepository,
     TRoleSyncRequestedRepository,
     TUserAuthenticationRequestRepository,
     TUserInfoSyncRequestedRepository,
@@ -32,15 +42,20 @@ pub struct AuthenticationService<
 > {
     pub archived_authenticated_user_repository: TArchivedAuthenticatedUserRepository,
     pub authenticated_user_repository: TAuthenticatedUserRepository,
+    pub class_repository: TClassRepository,
     pub role_sync_requested_repository: TRoleSyncRequestedRepository,
     pub user_authentication_request_repository: TUserAuthenticationRequestRepository,
     pub user_info_sync_requested_repository: TUserInfoSyncRequestedRepository,
     pub oauth_port: TOAuthAdapter,
+    pub staff_groups: Vec<String>,
+    pub group_role_rules: Vec<GroupRoleRule>,
+    pub authentication_request_ttl: Duration,
 }
 
 impl<
     TArchivedAuthenticatedUserRepository,
     TAuthenticatedUserRepository,
+    TClassRepository,
     TRoleSyncRequestedRepository,
     TUserAuthenticationRequestRepository,
     TUserInfoSyncRequestedRepository,
@@ -49,6 +64,7 @@ impl<
     for AuthenticationService<
         TArchivedAuthenticatedUserRepository,
         TAuthenticatedUserRepository,
+        TClassRepository,
         TRoleSyncRequestedRepository,
         TUserAuthenticationRequestRepository,
         TUserInfoSyncRequestedRepository,
@@ -57,6 +73,7 @@ impl<
 where
     TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
     TUserAuthenticationRequestRepository: UserAuthenticationRequestRepository + Send + Sync,
     TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
@@ -67,9 +84,20 @@ where
         &mut self,
         user_id: UserId,
     ) -> Result<AuthenticationLink, AuthenticationError> {
-        let (link, csrf_token) = self.oauth_port.create_authentication_link().await;
+        let AuthenticationLinkDto {
+            link,
+            csrf_token,
+            pkce_verifier,
+            nonce,
+        } = self.oauth_port.create_authentication_link().await;
 
-        let request = create_user_authentication_request(csrf_token, user_id);
+        let request = create_user_authentication_request(
+            csrf_token,
+            pkce_verifier,
+            nonce,
+            user_id,
+            self.authentication_request_ttl,
+        );
 
         self.user_authentication_request_repository
             .save(&request)
@@ -86,7 +114,7 @@ where
         &mut self,
         csrf_token: CsrfToken,
         client_callback_token: ClientCallbackToken,
-    ) -> Result<UserId, AuthenticationError> {
+    ) -> Result<ConfirmedAuthenticationDto, AuthenticationError> {
         let mut request = match self
             .user_authentication_request_repository
             .find_by_csrf_token(&csrf_token)
@@ -111,12 +139,25 @@ where
             return Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed);
         }
 
+        if request.is_expired() {
+            warn!(
+                csrf_token = csrf_token.0,
+                expires_at = %request.expires_at(),
+                "The user tried to authenticate with an expired CSRF token",
+            );
+            return Err(AuthenticationError::AuthenticationRequestExpired);
+        }
+
         let user_id = request.user_id();
         Span::current().record("user_id", user_id.0);
 
         let oauth_token = self
             .oauth_port
-            .exchange_code_after_callback(client_callback_token)
+            .exchange_code_after_callback(
+                client_callback_token,
+                request.pkce_verifier().cloned(),
+                request.nonce().cloned(),
+            )
             .await
             .map_err(|err| match err {
                 OAuthError::OAuthUnavailable => AuthenticationError::TemporaryUnavailable,
@@ -143,6 +184,7 @@ where
                 }
             })?;
 
+        let mut replaced_user = None;
         if let Some(user) = self
             .authenticated_user_repository
             .find_by_email(&user_info.email)
@@ -163,6 +205,12 @@ where
                 .remove(user.user_id())
                 .await
                 .map_err(map_user_repo_err)?;
+            if user.user_id() != user_id {
+                replaced_user = Some(ReplacedUserDto {
+                    user_id: archived_user.user_id(),
+                    archived_at: archived_user.archived_at(),
+                });
+            }
 
             info!(
                 user_id = user.user_id().0,
@@ -170,7 +218,8 @@ where
                 "Updating user's info due to a new user authenticating with the same email",
             );
 
-            let user_info_request = request_user_info_sync(user.user_id());
+            let user_info_request =
+                request_user_info_sync(user.user_id(), SyncTrigger::EmailReused);
             self.user_info_sync_requested_repository
                 .save(&user_info_request)
                 .await
@@ -178,12 +227,18 @@ where
         }
 
         request.confirm();
-        let user = create_user_from_successful_authentication(
+        let kind = find_user_kind(&user_info.groups, &self.staff_groups);
+        let mut user = create_user_from_successful_authentication(
             &request,
             user_info.name,
             user_info.email,
+            kind,
             oauth_token,
         );
+        user.update_group_ids(find_rule_group_ids(
+            &user_info.groups,
+            &self.group_role_rules,
+        ));
 
         self.authenticated_user_repository
             .save(&user)
@@ -199,7 +254,7 @@ where
             "Assigning student roles by OAuth2 Azure AD authentication asynchronously",
         );
 
-        let role_sync_request = request_role_sync(user.user_id());
+        let role_sync_request = request_role_sync(user.user_id(), SyncTrigger::Verification);
         self.role_sync_requested_repository
             .save(&role_sync_request)
             .await
@@ -207,7 +262,140 @@ where
 
         info!(user_id = user_id.0, "User successfully authenticated");
 
-        Ok(user.user_id())
+        Ok(ConfirmedAuthenticationDto {
+            user_id: user.user_id(),
+            replaced_user,
+        })
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn unverify(&mut self, user_id: UserId) -> Result<(), AuthenticationError> {
+        let user = self
+            .authenticated_user_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_user_repo_err)?
+            .ok_or(AuthenticationError::AuthenticatedUserNotFound)?;
+
+        let archived_user = create_archived_authenticated_user_without_token(&user);
+        self.archived_authenticated_user_repository
+            .save(&archived_user)
+            .await
+            .map_err(map_archived_user_repo_err)?;
+        self.authenticated_user_repository
+            .remove(user_id)
+            .await
+            .map_err(map_user_repo_err)?;
+
+        // The user is already unlinked, a failed revocation must not prevent the roles removal
+        let revocation = match user.oauth_token() {
+            Some(oauth_token) => self.oauth_port.revoke_token(oauth_token).await,
+            None => Ok(()), // Manually verified users have no token to revoke
+        };
+        match revocation {
+            Ok(()) => {}
+            Err(OAuthError::TokenExpired) => {
+                info!(
+                    user_id = user_id.0,
+                    "User's OAuth token was already expired or revoked",
+                );
+            }
+            Err(OAuthError::OAuthUnavailable) => {
+                warn!(
+                    user_id = user_id.0,
+                    "Failed to revoke user's OAuth token, it is not stored but stays valid until it expires",
+                );
+            }
+        }
+
+        info!(
+            user_id = user_id.0,
+            "Removing student roles of the unverified user asynchronously",
+        );
+
+        let role_sync_request = request_role_sync(user_id, SyncTrigger::Unverification);
+        self.role_sync_requested_repository
+            .save(&role_sync_request)
+            .await
+            .map_err(map_role_sync_req_repo_err)?;
+
+        info!(user_id = user_id.0, "User successfully unverified");
+
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn force_verify(&mut self, request: ForceVerifyDto) -> Result<(), AuthenticationError> {
+        let ForceVerifyDto {
+            user_id,
+            name,
+            email,
+            class_id,
+            verified_by,
+            reason,
+        } = request;
+
+        let class_id = ClassId::parse(&class_id).ok_or(AuthenticationError::InvalidClassId)?;
+        let is_active_class = self
+            .class_repository
+            .find_by_class_id(&class_id)
+            .await
+            .map_err(map_class_repo_err)?
+            .is_some_and(|class| class.is_active());
+        if !is_active_class {
+            return Err(AuthenticationError::InvalidClassId);
+        }
+
+        if self
+            .authenticated_user_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_user_repo_err)?
+            .is_some()
+        {
+            return Err(AuthenticationError::UserAlreadyVerified);
+        }
+
+        if let Some(user) = self
+            .authenticated_user_repository
+            .find_by_email(&email)
+            .await
+            .map_err(map_user_repo_err)?
+        {
+            warn!(
+                user_id = user_id.0,
+                other_user_id = user.user_id().0,
+                email = user.email(),
+                "Admin tried to manually verify a user with an already used email",
+            );
+            return Err(AuthenticationError::EmailAlreadyUsed);
+        }
+
+        let manual_verification = ManualVerification {
+            verified_by,
+            reason,
+        };
+        let user =
+            create_manually_verified_user(user_id, name, email, class_id, manual_verification);
+
+        self.authenticated_user_repository
+            .save(&user)
+            .await
+            .map_err(map_user_repo_err)?;
+
+        let role_sync_request = request_role_sync(user_id, SyncTrigger::ManualVerification);
+        self.role_sync_requested_repository
+            .save(&role_sync_request)
+            .await
+            .map_err(map_role_sync_req_repo_err)?;
+
+        info!(
+            user_id = user_id.0,
+            verified_by = verified_by.0,
+            "User successfully verified manually",
+        );
+
+        Ok(())
     }
 }
 
@@ -220,6 +408,13 @@ fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> AuthenticationErr
     }
 }
 
+#[instrument(level = "trace", skip_all)]
+fn map_class_repo_err(err: ClassRepositoryError) -> AuthenticationError {
+    match err {
+        ClassRepositoryError::ServiceUnavailable => AuthenticationError::TemporaryUnavailable,
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 fn map_archived_user_repo_err(
     err: ArchivedAuthenticatedUserRepositoryError,
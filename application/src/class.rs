@@ -0,0 +1,161 @@
+use application_ports::class::{AddClassDto, ClassDto, ClassError, ClassPort};
+use domain::class::catalog::{Class, ClassRepository, ClassRepositoryError, create_class};
+use domain::class::class_id::ClassId;
+use domain::ports::discord::{DiscordError, DiscordPort};
+use tracing::{info, instrument, warn};
+
+pub struct ClassService<TClassRepository, TDiscordPort> {
+    pub class_repository: TClassRepository,
+    pub discord_port: TDiscordPort,
+}
+
+impl<TClassRepository, TDiscordPort> ClassService<TClassRepository, TDiscordPort>
+where
+    TClassRepository: ClassRepository + Send + Sync,
+    TDiscordPort: DiscordPort + Send + Sync,
+{
+    #[instrument(level = "debug", skip(self))]
+    async fn find_class(&self, class_id: &str) -> Result<Class, ClassError> {
+        let class_id = ClassId::parse(class_id).ok_or(ClassError::InvalidClassId)?;
+
+        self.class_repository
+            .find_by_class_id(&class_id)
+            .await
+            .map_err(map_class_repo_err)?
+            .ok_or(ClassError::ClassNotFound)
+    }
+}
+
+impl<TClassRepository, TDiscordPort> ClassPort for ClassService<TClassRepository, TDiscordPort>
+where
+    TClassRepository: ClassRepository + Send + Sync,
+    TDiscordPort: DiscordPort + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn list_classes(&mut self) -> Result<Vec<ClassDto>, ClassError> {
+        let classes = self
+            .class_repository
+            .find_all()
+            .await
+            .map_err(map_class_repo_err)?;
+
+        Ok(classes.iter().map(class_to_dto).collect())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn add_class(&mut self, class: AddClassDto) -> Result<ClassDto, ClassError> {
+        let AddClassDto {
+            class_id,
+            display_name,
+            role_id,
+        } = class;
+
+        let class_id = ClassId::parse(&class_id).ok_or(ClassError::InvalidClassId)?;
+        let display_name = display_name
+            .map(|display_name| display_name.trim().to_string())
+            .filter(|display_name| !display_name.is_empty())
+            .unwrap_or_else(|| class_id.to_string().to_uppercase());
+
+        let class = match self
+            .class_repository
+            .find_by_class_id(&class_id)
+            .await
+            .map_err(map_class_repo_err)?
+        {
+            Some(class) if class.is_active() => return Err(ClassError::ClassAlreadyExists),
+            Some(mut class) => {
+                info!(class_id = %class_id, "Reactivating retired class");
+                class.reactivate(display_name, role_id);
+                class
+            }
+            None => {
+                info!(class_id = %class_id, "Adding class to the catalog");
+                create_class(class_id, display_name, role_id)
+            }
+        };
+
+        self.class_repository
+            .save(&class)
+            .await
+            .map_err(map_class_repo_err)?;
+
+        Ok(class_to_dto(&class))
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn retire_class(&mut self, class_id: &str) -> Result<(), ClassError> {
+        let mut class = self.find_class(class_id).await?;
+        if !class.is_active() {
+            return Ok(());
+        }
+
+        class.retire();
+        self.class_repository
+            .save(&class)
+            .await
+            .map_err(map_class_repo_err)?;
+
+        info!(class_id = %class.class_id(), "Class retired");
+
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn rename_class(&mut self, class_id: &str, display_name: &str) -> Result<(), ClassError> {
+        let mut class = self.find_class(class_id).await?;
+        let display_name = display_name.trim();
+        if display_name.is_empty() {
+            return Err(ClassError::InvalidClassDetails);
+        }
+
+        class.rename(display_name.to_string());
+        self.class_repository
+            .save(&class)
+            .await
+            .map_err(map_class_repo_err)?;
+
+        // Keep the role name in sync, so the role is found by the name again if the ID is lost
+        if let Some(role_id) = class.role_id() {
+            self.discord_port
+                .rename_role(role_id, display_name, "Class renamed")
+                .await
+                .map_err(map_discord_err)?;
+        }
+
+        info!(class_id = %class.class_id(), display_name, "Class renamed",);
+
+        Ok(())
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn class_to_dto(class: &Class) -> ClassDto {
+    ClassDto {
+        class_id: class.class_id().to_string(),
+        display_name: class.display_name().to_string(),
+        role_id: class.role_id(),
+        year: class.class_id().year(),
+        field: class
+            .class_id()
+            .field()
+            .map(|field| field.prefix().to_string()),
+        active: class.is_active(),
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_class_repo_err(err: ClassRepositoryError) -> ClassError {
+    match err {
+        ClassRepositoryError::ServiceUnavailable => ClassError::TemporaryUnavailable,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_discord_err(err: DiscordError) -> ClassError {
+    match err {
+        DiscordError::DiscordUnavailable => {
+            warn!("Failed to rename the class role");
+            ClassError::TemporaryUnavailable
+        }
+    }
+}
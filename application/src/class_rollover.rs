@@ -0,0 +1,294 @@
+use application_ports::class_rollover::{
+    ClassChangeCountDto, ClassRolloverError, ClassRolloverPort, ClassRolloverReportDto,
+    ClassRolloverStartedDto, FlaggedClassChangeDto,
+};
+use chrono::Utc;
+use domain::authentication::authenticated_user::{
+    AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
+};
+use domain::class::catalog::{ClassRepository, ClassRepositoryError};
+use domain::class::rollover::{
+    ClassChangeKind, ClassRolloverRepository, ClassRolloverRepositoryError, classify_class_change,
+    create_class_rollover_entries, find_recent_rollover, plan_class_role_shift,
+};
+use domain::jobs::sync_trigger::SyncTrigger;
+use domain::jobs::user_info_sync_job::{
+    UserInfoSyncRequestedRepository, UserInfoSyncRequestedRepositoryError, request_user_info_sync,
+};
+use domain::ports::discord::{DiscordError, DiscordPort};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, instrument, warn};
+
+pub struct ClassRolloverService<
+    TAuthenticatedUserRepository,
+    TClassRepository,
+    TClassRolloverRepository,
+    TUserInfoSyncRequestedRepository,
+    TDiscordPort,
+> {
+    pub authenticated_user_repository: TAuthenticatedUserRepository,
+    pub class_repository: TClassRepository,
+    pub class_rollover_repository: TClassRolloverRepository,
+    pub user_info_sync_requested_repository: TUserInfoSyncRequestedRepository,
+    pub discord_port: TDiscordPort,
+}
+
+impl<
+    TAuthenticatedUserRepository,
+    TClassRepository,
+    TClassRolloverRepository,
+    TUserInfoSyncRequestedRepository,
+    TDiscordPort,
+>
+    ClassRolloverService<
+        TAuthenticatedUserRepository,
+        TClassRepository,
+        TClassRolloverRepository,
+        TUserInfoSyncRequestedRepository,
+        TDiscordPort,
+    >
+where
+    TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
+    TClassRolloverRepository: ClassRolloverRepository + Send + Sync,
+    TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
+    TDiscordPort: DiscordPort + Send + Sync,
+{
+    /// Moves the class roles one year up and renames them after their new classes
+    #[instrument(level = "debug", skip(self))]
+    async fn shift_class_roles(&self) -> Result<u64, ClassRolloverError> {
+        let classes = self
+            .class_repository
+            .find_all()
+            .await
+            .map_err(map_class_repo_err)?;
+        let shifted_roles = plan_class_role_shift(&classes);
+        let mut classes = classes
+            .into_iter()
+            .map(|class| (class.class_id().clone(), class))
+            .collect::<HashMap<_, _>>();
+
+        let mut roles_moved = 0;
+        for (class_id, role_id) in shifted_roles {
+            let Some(class) = classes.get_mut(&class_id) else {
+                continue;
+            };
+
+            class.move_role(role_id);
+            self.class_repository
+                .save(class)
+                .await
+                .map_err(map_class_repo_err)?;
+
+            if let Some(role_id) = role_id {
+                self.discord_port
+                    .rename_role(role_id, class.display_name(), "School-year rollover")
+                    .await
+                    .map_err(map_discord_err)?;
+                roles_moved += 1;
+            }
+        }
+
+        Ok(roles_moved)
+    }
+}
+
+impl<
+    TAuthenticatedUserRepository,
+    TClassRepository,
+    TClassRolloverRepository,
+    TUserInfoSyncRequestedRepository,
+    TDiscordPort,
+> ClassRolloverPort
+    for ClassRolloverService<
+        TAuthenticatedUserRepository,
+        TClassRepository,
+        TClassRolloverRepository,
+        TUserInfoSyncRequestedRepository,
+        TDiscordPort,
+    >
+where
+    TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
+    TClassRolloverRepository: ClassRolloverRepository + Send + Sync,
+    TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
+    TDiscordPort: DiscordPort + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn start_rollover(
+        &mut self,
+        shift_roles: bool,
+        force: bool,
+    ) -> Result<ClassRolloverStartedDto, ClassRolloverError> {
+        let previous_entries = self
+            .class_rollover_repository
+            .find_all()
+            .await
+            .map_err(map_class_rollover_repo_err)?;
+        if let Some(started_at) = find_recent_rollover(&previous_entries, Utc::now()) {
+            if !force {
+                return Err(ClassRolloverError::RecentlyStarted(started_at));
+            }
+            warn!(
+                started_at = %started_at,
+                "Forcing a school-year rollover although one was started recently",
+            );
+        }
+
+        let users = self
+            .authenticated_user_repository
+            .find_all()
+            .await
+            .map_err(map_user_repo_err)?;
+
+        let entries = create_class_rollover_entries(&users);
+        self.class_rollover_repository
+            .replace_all(&entries)
+            .await
+            .map_err(map_class_rollover_repo_err)?;
+
+        let roles_moved = if shift_roles {
+            self.shift_class_roles().await?
+        } else {
+            0
+        };
+
+        for user in &users {
+            let request = request_user_info_sync(user.user_id(), SyncTrigger::ClassRollover);
+            self.user_info_sync_requested_repository
+                .save(&request)
+                .await
+                .map_err(map_user_info_sync_req_repo_err)?;
+        }
+
+        let started = ClassRolloverStartedDto {
+            students: entries.len() as u64,
+            users_enqueued: users.len() as u64,
+            roles_moved,
+        };
+        info!(started = ?started, "School-year rollover started");
+
+        Ok(started)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn get_rollover_report(
+        &mut self,
+    ) -> Result<Option<ClassRolloverReportDto>, ClassRolloverError> {
+        let entries = self
+            .class_rollover_repository
+            .find_all()
+            .await
+            .map_err(map_class_rollover_repo_err)?;
+        let Some(taken_at) = entries.iter().map(|entry| entry.taken_at).min() else {
+            return Ok(None);
+        };
+
+        let users = self
+            .authenticated_user_repository
+            .find_all()
+            .await
+            .map_err(map_user_repo_err)?
+            .into_iter()
+            .map(|user| (user.user_id(), user))
+            .collect::<HashMap<_, _>>();
+
+        let mut advanced = 0;
+        let mut graduated = 0;
+        let mut class_changes = BTreeMap::<(String, Option<String>), u64>::new();
+        let mut flagged = Vec::new();
+
+        for entry in &entries {
+            let user = users.get(&entry.user_id);
+            let current_class_id = user
+                .and_then(|user| user.class_id())
+                .map(|class_id| class_id.to_string());
+
+            match classify_class_change(entry, user) {
+                ClassChangeKind::Advanced => advanced += 1,
+                ClassChangeKind::Graduated => graduated += 1,
+                kind @ (ClassChangeKind::NotAdvanced | ClassChangeKind::Unexpected) => {
+                    flagged.push(FlaggedClassChangeDto {
+                        user_id: entry.user_id,
+                        name: user.map(|user| user.name().to_string()),
+                        previous_class_id: entry.class_id.to_string(),
+                        current_class_id: current_class_id.clone(),
+                        expected_class_id: entry
+                            .class_id
+                            .next_year()
+                            .map(|class_id| class_id.to_string()),
+                        not_advanced: kind == ClassChangeKind::NotAdvanced,
+                    });
+                }
+            }
+
+            *class_changes
+                .entry((entry.class_id.to_string(), current_class_id))
+                .or_default() += 1;
+        }
+
+        Ok(Some(ClassRolloverReportDto {
+            taken_at,
+            students: entries.len() as u64,
+            advanced,
+            graduated,
+            class_changes: class_changes
+                .into_iter()
+                .map(
+                    |((previous_class_id, current_class_id), count)| ClassChangeCountDto {
+                        previous_class_id,
+                        current_class_id,
+                        count,
+                    },
+                )
+                .collect(),
+            flagged,
+        }))
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> ClassRolloverError {
+    match err {
+        AuthenticatedUserRepositoryError::ServiceUnavailable => {
+            ClassRolloverError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_class_repo_err(err: ClassRepositoryError) -> ClassRolloverError {
+    match err {
+        ClassRepositoryError::ServiceUnavailable => ClassRolloverError::TemporaryUnavailable,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_class_rollover_repo_err(err: ClassRolloverRepositoryError) -> ClassRolloverError {
+    match err {
+        ClassRolloverRepositoryError::ServiceUnavailable => {
+            ClassRolloverError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_user_info_sync_req_repo_err(
+    err: UserInfoSyncRequestedRepositoryError,
+) -> ClassRolloverError {
+    match err {
+        UserInfoSyncRequestedRepositoryError::ServiceUnavailable => {
+            ClassRolloverError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_discord_err(err: DiscordError) -> ClassRolloverError {
+    match err {
+        DiscordError::DiscordUnavailable => {
+            warn!("Failed to rename a class role during the rollover");
+            ClassRolloverError::TemporaryUnavailable
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use application_ports::nickname::{NicknameError, NicknamePort};
+use domain::jobs::role_sync_job::{
+    RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError, request_role_sync,
+};
+use domain::jobs::sync_trigger::SyncTrigger;
+use domain::nickname::{
+    NicknameSettings, NicknameSettingsRepository, NicknameSettingsRepositoryError,
+};
+use domain_shared::discord::UserId;
+use tracing::{error, info, instrument};
+
+pub struct NicknameService<TNicknameSettingsRepository, TRoleSyncRequestedRepository> {
+    pub nickname_settings_repository: TNicknameSettingsRepository,
+    pub role_sync_requested_repository: TRoleSyncRequestedRepository,
+}
+
+impl<TNicknameSettingsRepository, TRoleSyncRequestedRepository>
+    NicknameService<TNicknameSettingsRepository, TRoleSyncRequestedRepository>
+where
+    TNicknameSettingsRepository: NicknameSettingsRepository + Send + Sync,
+    TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+{
+    /// Updates the settings of the user and enqueues a role sync which applies them
+    #[instrument(level = "debug", skip(self, update))]
+    async fn update_settings(
+        &self,
+        user_id: UserId,
+        update: impl FnOnce(&mut NicknameSettings) + Send,
+    ) -> Result<(), NicknameError> {
+        let mut settings = self
+            .nickname_settings_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_nickname_settings_repo_err)?
+            .unwrap_or_else(|| NicknameSettings::new(user_id));
+        update(&mut settings);
+
+        self.nickname_settings_repository
+            .save(&settings)
+            .await
+            .map_err(map_nickname_settings_repo_err)?;
+
+        let role_sync_request = request_role_sync(user_id, SyncTrigger::NicknameSettingsChange);
+        self.role_sync_requested_repository
+            .save(&role_sync_request)
+            .await
+            .map_err(map_role_sync_req_repo_err)?;
+
+        info!(
+            user_id = user_id.0,
+            settings = ?settings,
+            "Nickname settings updated",
+        );
+
+        Ok(())
+    }
+}
+
+impl<TNicknameSettingsRepository, TRoleSyncRequestedRepository> NicknamePort
+    for NicknameService<TNicknameSettingsRepository, TRoleSyncRequestedRepository>
+where
+    TNicknameSettingsRepository: NicknameSettingsRepository + Send + Sync,
+    TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn set_nickname_opt_out(
+        &mut self,
+        user_id: UserId,
+        opt_out: bool,
+    ) -> Result<(), NicknameError> {
+        self.update_settings(user_id, |settings| settings.opt_out = opt_out)
+            .await
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn set_nickname_override(
+        &mut self,
+        user_id: UserId,
+        admin_override: Option<bool>,
+    ) -> Result<(), NicknameError> {
+        self.update_settings(user_id, |settings| settings.admin_override = admin_override)
+            .await
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_nickname_settings_repo_err(err: NicknameSettingsRepositoryError) -> NicknameError {
+    match err {
+        NicknameSettingsRepositoryError::ServiceUnavailable => {
+            error!("NicknameSettingsRepositoryError::ServiceUnavailable");
+            NicknameError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_sync_req_repo_err(err: RoleSyncRequestedRepositoryError) -> NicknameError {
+    match err {
+        RoleSyncRequestedRepositoryError::ServiceUnavailable => {
+            error!("RoleSyncRequestedRepositoryError::ServiceUnavailable");
+            NicknameError::TemporaryUnavailable
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use crate::role_log::role_change_to_dto;
+use crate::role_sync_exemption::exemption_to_dto;
+use crate::user::{archived_user_to_info_dto, user_to_info_dto};
+use application_ports::nickname::NicknameSettingsDto;
+use application_ports::personal_data::{
+    AuthenticationRequestInfoDto, PersonalDataErasureDto, PersonalDataError, PersonalDataExportDto,
+    PersonalDataPort,
+};
+use domain::authentication::archived_authenticated_user::{
+    ArchivedAuthenticatedUser, ArchivedAuthenticatedUserRepository,
+    ArchivedAuthenticatedUserRepositoryError,
+};
+use domain::authentication::authenticated_user::{
+    AuthenticatedUser, AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
+};
+use domain::authentication::user_authentication_request::{
+    UserAuthenticationRequestRepository, UserAuthenticationRequestRepositoryError,
+};
+use domain::jobs::role_sync_job::{
+    RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError, request_role_sync,
+};
+use domain::jobs::sync_trigger::SyncTrigger;
+use domain::nickname::{NicknameSettingsRepository, NicknameSettingsRepositoryError};
+use domain::ports::discord::{DiscordError, DiscordPort, NicknameUpdate};
+use domain::ports::oauth::{OAuthError, OAuthPort, OAuthToken};
+use domain::role_change_log::{RoleChangeLogRepository, RoleChangeLogRepositoryError};
+use domain::role_sync_exemption::{
+    ExemptionTarget, RoleSyncExemptionRepository, RoleSyncExemptionRepositoryError,
+};
+use domain_shared::discord::UserId;
+use tracing::{info, instrument, warn};
+
+pub struct PersonalDataService<
+    TArchivedAuthenticatedUserRepository,
+    TAuthenticatedUserRepository,
+    TNicknameSettingsRepository,
+    TRoleChangeLogRepository,
+    TRoleSyncExemptionRepository,
+    TRoleSyncRequestedRepository,
+    TUserAuthenticationRequestRepository,
+    TDiscordPort,
+    TOAuthAdapter,
+> {
+    pub archived_authenticated_user_repository: TArchivedAuthenticatedUserRepository,
+    pub authenticated_user_repository: TAuthenticatedUserRepository,
+    pub nickname_settings_repository: TNicknameSettingsRepository,
+    pub role_change_log_repository: TRoleChangeLogRepository,
+    pub role_sync_exemption_repository: TRoleSyncExemptionRepository,
+    pub role_sync_requested_repository: TRoleSyncRequestedRepository,
+    pub user_authentication_request_repository: TUserAuthenticationRequestRepository,
+    pub discord_port: TDiscordPort,
+    pub oauth_port: TOAuthAdapter,
+}
+
+impl<
+    TArchivedAuthenticatedUserRepository,
+    TAuthenticatedUserRepository,
+    TNicknameSettingsRepository,
+    TRoleChangeLogRepository,
+    TRoleSyncExemptionRepository,
+    TRoleSyncRequestedRepository,
+    TUserAuthenticationRequestRepository,
+    TDiscordPort,
+    TOAuthAdapter,
+>
+    PersonalDataService<
+        TArchivedAuthenticatedUserRepository,
+        TAuthenticatedUserRepository,
+        TNicknameSettingsRepository,
+        TRoleChangeLogRepository,
+        TRoleSyncExemptionRepository,
+        TRoleSyncRequestedRepository,
+        TUserAuthenticationRequestRepository,
+        TDiscordPort,
+        TOAuthAdapter,
+    >
+where
+    TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
+    TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TNicknameSettingsRepository: NicknameSettingsRepository + Send + Sync,
+    TRoleChangeLogRepository: RoleChangeLogRepository + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
+    TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+    TUserAuthenticationRequestRepository: UserAuthenticationRequestRepository + Send + Sync,
+    TDiscordPort: DiscordPort + Send + Sync,
+    TOAuthAdapter: OAuthPort + Send + Sync,
+{
+    /// Finds the archived identities of the Discord user and the ones sharing the email
+    /// of the currently verified school account
+    #[instrument(level = "debug", skip(self, user))]
+    async fn find_archived_users(
+        &self,
+        user_id: UserId,
+        user: Option<&AuthenticatedUser>,
+    ) -> Result<Vec<ArchivedAuthenticatedUser>, PersonalDataError> {
+        let mut archived_users = self
+            .archived_authenticated_user_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_archived_user_repo_err)?;
+
+        if let Some(user) = user {
+            let archived_users_by_email = self
+                .archived_authenticated_user_repository
+                .find_by_email(user.email())
+                .await
+                .map_err(map_archived_user_repo_err)?;
+            for archived_user in archived_users_by_email {
+                if !archived_users
+                    .iter()
+                    .any(|a| a.archived_user_id() == archived_user.archived_user_id())
+                {
+                    archived_users.push(archived_user);
+                }
+            }
+        }
+
+        archived_users.sort_by_key(|archived_user| std::cmp::Reverse(archived_user.archived_at()));
+        Ok(archived_users)
+    }
+
+    /// Revokes the token on a best-effort basis, the erasure must not fail on the provider
+    #[instrument(level = "debug", skip(self, oauth_token))]
+    async fn revoke_token(&self, user_id: UserId, oauth_token: Option<&OAuthToken>) {
+        let Some(oauth_token) = oauth_token else {
+            return;
+        };
+
+        match self.oauth_port.revoke_token(oauth_token).await {
+            Ok(()) => {}
+            Err(OAuthError::TokenExpired) => {
+                info!(
+                    user_id = user_id.0,
+                    "OAuth token was already expired or revoked",
+                );
+            }
+            Err(OAuthError::OAuthUnavailable) => {
+                warn!(
+                    user_id = user_id.0,
+                    "Failed to revoke OAuth token, it is discarded only",
+                );
+            }
+        }
+    }
+
+    /// Removes the nickname set by the bot on a best-effort basis, the erasure must not fail
+    /// on Discord
+    #[instrument(level = "debug", skip(self))]
+    async fn reset_nickname(&self, user_id: UserId) -> Result<(), PersonalDataError> {
+        let applied_nickname = self
+            .nickname_settings_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_nickname_settings_repo_err)?
+            .and_then(|settings| settings.applied_nickname);
+        let Some(applied_nickname) = applied_nickname else {
+            return Ok(());
+        };
+
+        match self
+            .discord_port
+            .reset_nickname(user_id, &applied_nickname, "Personal data erased")
+            .await
+        {
+            Ok(NicknameUpdate::Updated) => {
+                info!(user_id = user_id.0, "Nickname reset");
+            }
+            Ok(_) => {}
+            Err(DiscordError::DiscordUnavailable) => {
+                warn!(
+                    user_id = user_id.0,
+                    "Failed to reset nickname, it is kept on Discord",
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<
+    TArchivedAuthenticatedUserRepository,
+    TAuthenticatedUserRepository,
+    TNicknameSettingsRepository,
+    TRoleChangeLogRepository,
+    TRoleSyncExemptionRepository,
+    TRoleSyncRequestedRepository,
+    TUserAuthenticationRequestRepository,
+    TDiscordPort,
+    TOAuthAdapter,
+> PersonalDataPort
+    for PersonalDataService<
+        TArchivedAuthenticatedUserRepository,
+        TAuthenticatedUserRepository,
+        TNicknameSettingsRepository,
+        TRoleChangeLogRepository,
+        TRoleSyncExemptionRepository,
+        TRoleSyncRequestedRepository,
+        TUserAuthenticationRequestRepository,
+        TDiscordPort,
+        TOAuthAdapter,
+    >
+where
+    TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
+    TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TNicknameSettingsRepository: NicknameSettingsRepository + Send + Sync,
+    TRoleChangeLogRepository: RoleChangeLogRepository + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
+    TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+    TUserAuthenticationRequestRepository: UserAuthenticationRequestRepository + Send + Sync,
+    TDiscordPort: DiscordPort + Send + Sync,
+    TOAuthAdapter: OAuthPort + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn export_personal_data(
+        &mut self,
+        user_id: UserId,
+    ) -> Result<PersonalDataExportDto, PersonalDataError> {
+        let user = self
+            .authenticated_user_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_user_repo_err)?;
+        let archived_users = self.find_archived_users(user_id, user.as_ref()).await?;
+        let authentication_requests = self
+            .user_authentication_request_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_auth_req_repo_err)?;
+        let role_change_count = self
+            .role_change_log_repository
+            .count_by_user_id(user_id)
+            .await
+            .map_err(map_role_change_log_repo_err)?;
+        let role_changes = self
+            .role_change_log_repository
+            .find_by_user_id(user_id, 0, role_change_count)
+            .await
+            .map_err(map_role_change_log_repo_err)?;
+        let nickname_settings = self
+            .nickname_settings_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_nickname_settings_repo_err)?;
+        let role_sync_exemption = self
+            .role_sync_exemption_repository
+            .find_by_target(ExemptionTarget::User(user_id))
+            .await
+            .map_err(map_role_sync_exemption_repo_err)?;
+
+        info!(user_id = user_id.0, "Exporting personal data");
+
+        Ok(PersonalDataExportDto {
+            user_id,
+            authenticated_user: user.as_ref().map(user_to_info_dto),
+            oauth_token_expires_at: user
+                .as_ref()
+                .and_then(|user| user.oauth_token())
+                .map(|oauth_token| oauth_token.expires_at),
+            archived_users: archived_users
+                .iter()
+                .map(archived_user_to_info_dto)
+                .collect(),
+            authentication_requests: authentication_requests
+                .iter()
+                .map(|request| AuthenticationRequestInfoDto {
+                    requested_at: request.requested_at(),
+                    expires_at: request.expires_at(),
+                    confirmed_at: request.confirmed_at(),
+                })
+                .collect(),
+            role_changes: role_changes.into_iter().map(role_change_to_dto).collect(),
+            nickname_settings: nickname_settings.map(|settings| NicknameSettingsDto {
+                opt_out: settings.opt_out,
+                admin_override: settings.admin_override,
+                applied_nickname: settings.applied_nickname,
+            }),
+            role_sync_exemption: role_sync_exemption.as_ref().map(exemption_to_dto),
+        })
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn erase_personal_data(
+        &mut self,
+        user_id: UserId,
+    ) -> Result<PersonalDataErasureDto, PersonalDataError> {
+        let user = self
+            .authenticated_user_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_user_repo_err)?;
+        let archived_users = self.find_archived_users(user_id, user.as_ref()).await?;
+
+        if let Some(user) = &user {
+            self.authenticated_user_repository
+                .remove(user_id)
+                .await
+                .map_err(map_user_repo_err)?;
+            self.revoke_token(user_id, user.oauth_token()).await;
+        }
+
+        for archived_user in &archived_users {
+            self.archived_authenticated_user_repository
+                .remove(archived_user.archived_user_id())
+                .await
+                .map_err(map_archived_user_repo_err)?;
+            self.revoke_token(archived_user.user_id(), archived_user.oauth_token())
+                .await;
+        }
+
+        let authentication_requests_removed = self
+            .user_authentication_request_repository
+            .remove_by_user_id(user_id)
+            .await
+            .map_err(map_auth_req_repo_err)?;
+        let role_changes_removed = self
+            .role_change_log_repository
+            .remove_by_user_id(user_id)
+            .await
+            .map_err(map_role_change_log_repo_err)?;
+        // The settings hold the nickname set by the bot, which is personal data as well
+        self.reset_nickname(user_id).await?;
+        let nickname_settings_removed = self
+            .nickname_settings_repository
+            .remove_by_user_id(user_id)
+            .await
+            .map_err(map_nickname_settings_repo_err)?;
+        // The exemption reason is written by an admin, but it is about the user
+        let role_sync_exemption_removed = self
+            .role_sync_exemption_repository
+            .remove(ExemptionTarget::User(user_id))
+            .await
+            .map_err(map_role_sync_exemption_repo_err)?;
+
+        // The user is no longer verified, the role sync removes the student roles
+        let role_sync_request = request_role_sync(user_id, SyncTrigger::PersonalDataErasure);
+        self.role_sync_requested_repository
+            .save(&role_sync_request)
+            .await
+            .map_err(map_role_sync_req_repo_err)?;
+
+        let erasure = PersonalDataErasureDto {
+            authenticated_user_removed: user.is_some(),
+            archived_users_removed: archived_users.len() as u64,
+            authentication_requests_removed,
+            role_changes_removed,
+            nickname_settings_removed,
+            role_sync_exemption_removed,
+        };
+        info!(
+            user_id = user_id.0,
+            erasure = ?erasure,
+            "Personal data erased",
+        );
+
+        Ok(erasure)
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> PersonalDataError {
+    match err {
+        AuthenticatedUserRepositoryError::ServiceUnavailable => {
+            PersonalDataError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_archived_user_repo_err(err: ArchivedAuthenticatedUserRepositoryError) -> PersonalDataError {
+    match err {
+        ArchivedAuthenticatedUserRepositoryError::ServiceUnavailable => {
+            PersonalDataError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_auth_req_repo_err(err: UserAuthenticationRequestRepositoryError) -> PersonalDataError {
+    match err {
+        UserAuthenticationRequestRepositoryError::TemporaryUnavailable => {
+            PersonalDataError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_sync_req_repo_err(err: RoleSyncRequestedRepositoryError) -> PersonalDataError {
+    match err {
+        RoleSyncRequestedRepositoryError::ServiceUnavailable => {
+            PersonalDataError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_change_log_repo_err(err: RoleChangeLogRepositoryError) -> PersonalDataError {
+    match err {
+        RoleChangeLogRepositoryError::ServiceUnavailable => PersonalDataError::TemporaryUnavailable,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_nickname_settings_repo_err(err: NicknameSettingsRepositoryError) -> PersonalDataError {
+    match err {
+        NicknameSettingsRepositoryError::ServiceUnavailable => {
+            PersonalDataError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_sync_exemption_repo_err(err: RoleSyncExemptionRepositoryError) -> PersonalDataError {
+    match err {
+        RoleSyncExemptionRepositoryError::ServiceUnavailable => {
+            PersonalDataError::TemporaryUnavailable
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use application_ports::retention_handler::{RetentionHandlerError, RetentionHandlerPort};
+use async_trait::async_trait;
+use chrono::{Duration, TimeDelta, Utc};
+use domain::authentication::archived_authenticated_user::{
+    ArchivedAuthenticatedUserRepository, ArchivedAuthenticatedUserRepositoryError,
+};
+use domain::authentication::user_authentication_request::{
+    UserAuthenticationRequestRepository, UserAuthenticationRequestRepositoryError,
+};
+use tracing::{error, info, instrument};
+
+/// How long confirmed requests are kept, so the user gets a meaningful response
+/// when opening the authentication link again.
+const CONFIRMED_REQUEST_RETENTION: TimeDelta = Duration::days(1);
+
+#[derive(Clone, Debug)]
+pub struct RetentionConfig {
+    /// How long archived users are kept, they are kept forever when unset
+    pub archived_user_retention: Option<Duration>,
+    /// Only counts the affected archived users without changing them,
+    /// stale authentication requests are removed regardless
+    pub dry_run: bool,
+}
+
+pub struct RetentionHandler<
+    TArchivedAuthenticatedUserRepository,
+    TUserAuthenticationRequestRepository,
+> {
+    archived_authenticated_user_repository: TArchivedAuthenticatedUserRepository,
+    user_authentication_request_repository: TUserAuthenticationRequestRepository,
+    config: RetentionConfig,
+}
+
+impl<TArchivedAuthenticatedUserRepository, TUserAuthenticationRequestRepository>
+    RetentionHandler<TArchivedAuthenticatedUserRepository, TUserAuthenticationRequestRepository>
+where
+    TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
+    TUserAuthenticationRequestRepository: UserAuthenticationRequestRepository + Send + Sync,
+{
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(
+        archived_authenticated_user_repository: TArchivedAuthenticatedUserRepository,
+        user_authentication_request_repository: TUserAuthenticationRequestRepository,
+        config: RetentionConfig,
+    ) -> Self {
+        Self {
+            archived_authenticated_user_repository,
+            user_authentication_request_repository,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl<TArchivedAuthenticatedUserRepository, TUserAuthenticationRequestRepository>
+    RetentionHandlerPort
+    for RetentionHandler<TArchivedAuthenticatedUserRepository, TUserAuthenticationRequestRepository>
+where
+    TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
+    TUserAuthenticationRequestRepository: UserAuthenticationRequestRepository + Send + Sync,
+{
+    #[instrument(level = "debug", skip_all, fields(dry_run = self.config.dry_run))]
+    async fn tick(&self) -> Result<(), RetentionHandlerError> {
+        let now = Utc::now();
+        let dry_run = self.config.dry_run;
+
+        // Archived users are never authenticated again, their tokens are not needed. Unverified
+        // users are archived without them already, this catches the replaced accounts and older rows
+        let scrubbed_tokens = if dry_run {
+            self.archived_authenticated_user_repository
+                .count_with_oauth_token()
+                .await
+        } else {
+            self.archived_authenticated_user_repository
+                .remove_oauth_tokens()
+                .await
+        }
+        .map_err(map_archived_user_repo_err)?;
+
+        let removed_archived_users = match self.config.archived_user_retention {
+            Some(retention) if dry_run => {
+                self.archived_authenticated_user_repository
+                    .count_archived_before(now - retention)
+                    .await
+            }
+            Some(retention) => {
+                self.archived_authenticated_user_repository
+                    .remove_archived_before(now - retention)
+                    .await
+            }
+            None => Ok(0),
+        }
+        .map_err(map_archived_user_repo_err)?;
+
+        // Stale requests were purged before the archive policy existed, the dry run does not apply
+        let removed_authentication_requests = self
+            .user_authentication_request_repository
+            .remove_stale(now, now - CONFIRMED_REQUEST_RETENTION)
+            .await
+            .map_err(map_auth_req_repo_err)?;
+
+        if dry_run {
+            info!(
+                scrubbed_tokens,
+                removed_archived_users,
+                removed_authentication_requests,
+                "Dry run of the retention policy, archived users were not changed",
+            );
+        } else {
+            info!(
+                scrubbed_tokens,
+                removed_archived_users,
+                removed_authentication_requests,
+                "Successfully applied the retention policy",
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[instrument(level = "debug", skip_all)]
+fn map_archived_user_repo_err(
+    err: ArchivedAuthenticatedUserRepositoryError,
+) -> RetentionHandlerError {
+    match err {
+        ArchivedAuthenticatedUserRepositoryError::ServiceUnavailable => {
+            error!(
+                "Retention handler is temporarily unavailable: archived authenticated user repository is unavailable"
+            );
+            RetentionHandlerError::TemporarilyUnavailable
+        }
+    }
+}
+
+#[instrument(level = "debug", skip_all)]
+fn map_auth_req_repo_err(err: UserAuthenticationRequestRepositoryError) -> RetentionHandlerError {
+    match err {
+        UserAuthenticationRequestRepositoryError::TemporaryUnavailable => {
+            error!(
+                "Retention handler is temporarily unavailable: user authentication request repository is unavailable"
+            );
+            RetentionHandlerError::TemporarilyUnavailable
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use application_ports::role_log::{RoleChangeDto, RoleLogError, RoleLogPageDto, RoleLogPort};
+use domain::role_change_log::{
+    RoleChangeLogEntry, RoleChangeLogRepository, RoleChangeLogRepositoryError,
+};
+use domain_shared::discord::UserId;
+use tracing::{error, instrument};
+
+pub struct RoleLogService<TRoleChangeLogRepository> {
+    pub role_change_log_repository: TRoleChangeLogRepository,
+}
+
+impl<TRoleChangeLogRepository> RoleLogPort for RoleLogService<TRoleChangeLogRepository>
+where
+    TRoleChangeLogRepository: RoleChangeLogRepository + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn get_role_log(
+        &mut self,
+        user_id: UserId,
+        offset: u64,
+        limit: u64,
+    ) -> Result<RoleLogPageDto, RoleLogError> {
+        let (entries, total) = tokio::try_join!(
+            async {
+                self.role_change_log_repository
+                    .find_by_user_id(user_id, offset, limit)
+                    .await
+                    .map_err(map_role_change_log_repo_err)
+            },
+            async {
+                self.role_change_log_repository
+                    .count_by_user_id(user_id)
+                    .await
+                    .map_err(map_role_change_log_repo_err)
+            }
+        )?;
+
+        Ok(RoleLogPageDto {
+            entries: entries.into_iter().map(role_change_to_dto).collect(),
+            total,
+        })
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn role_change_to_dto(entry: RoleChangeLogEntry) -> RoleChangeDto {
+    RoleChangeDto {
+        assigned: entry.assigned,
+        removed: entry.removed,
+        reason: entry.reason,
+        low_priority: entry.low_priority,
+        changed_at: entry.changed_at,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_change_log_repo_err(err: RoleChangeLogRepositoryError) -> RoleLogError {
+    match err {
+        RoleChangeLogRepositoryError::ServiceUnavailable => {
+            error!("RoleChangeLogRepositoryError::ServiceUnavailable");
+            RoleLogError::TemporaryUnavailable
+        }
+    }
+}
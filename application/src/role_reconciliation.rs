@@ -0,0 +1,230 @@
+use crate::role_sync_job_handler::RoleSyncConfig;
+use application_ports::role_reconciliation::{
+    MemberRoleDiffDto, RoleChangeCountDto, RoleReconciliationError, RoleReconciliationPort,
+    RoleReconciliationReportDto,
+};
+use domain::authentication::authenticated_user::{
+    AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
+};
+use domain::class::catalog::{ClassRepository, ClassRepositoryError};
+use domain::ports::discord::{DiscordError, DiscordPort};
+use domain::role_sync_exemption::{
+    RoleSyncExemptionRepository, RoleSyncExemptionRepositoryError, find_member_exemptions,
+};
+use domain::roles::RolesDiffService;
+use domain_shared::discord::RoleId;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, instrument, warn};
+
+pub struct RoleReconciliationService<
+    TDiscordPort,
+    TAuthenticatedUserRepository,
+    TClassRepository,
+    TRoleSyncExemptionRepository,
+> {
+    pub discord_port: TDiscordPort,
+    pub authenticated_user_repository: TAuthenticatedUserRepository,
+    pub class_repository: TClassRepository,
+    pub role_sync_exemption_repository: TRoleSyncExemptionRepository,
+    pub config: RoleSyncConfig,
+}
+
+impl<TDiscordPort, TAuthenticatedUserRepository, TClassRepository, TRoleSyncExemptionRepository>
+    RoleReconciliationService<
+        TDiscordPort,
+        TAuthenticatedUserRepository,
+        TClassRepository,
+        TRoleSyncExemptionRepository,
+    >
+where
+    TDiscordPort: DiscordPort + Send + Sync,
+    TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
+{
+    /// Unlike the role sync, the roles of classes without one are not created, the classes are
+    /// reported instead
+    #[instrument(level = "debug", skip(self))]
+    async fn create_roles_diff_service(
+        &self,
+    ) -> Result<(RolesDiffService, Vec<String>), RoleReconciliationError> {
+        let classes = self
+            .class_repository
+            .find_all()
+            .await
+            .map_err(map_class_repo_err)?;
+
+        let mut class_id_to_role_id = Vec::new();
+        let mut retired_class_role_ids = Vec::new();
+        let mut classes_without_role = Vec::new();
+
+        for class in classes {
+            match (class.is_active(), class.role_id()) {
+                (true, Some(role_id)) => {
+                    class_id_to_role_id.push((class.class_id().clone(), role_id))
+                }
+                (true, None) => classes_without_role.push(class.class_id().to_string()),
+                (false, role_id) => retired_class_role_ids.extend(role_id),
+            }
+        }
+
+        let roles_diff_service = self
+            .config
+            .create_roles_diff_service(class_id_to_role_id, retired_class_role_ids);
+
+        Ok((roles_diff_service, classes_without_role))
+    }
+}
+
+impl<TDiscordPort, TAuthenticatedUserRepository, TClassRepository, TRoleSyncExemptionRepository>
+    RoleReconciliationPort
+    for RoleReconciliationService<
+        TDiscordPort,
+        TAuthenticatedUserRepository,
+        TClassRepository,
+        TRoleSyncExemptionRepository,
+    >
+where
+    TDiscordPort: DiscordPort + Send + Sync,
+    TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn reconcile_roles_dry_run(
+        &mut self,
+    ) -> Result<RoleReconciliationReportDto, RoleReconciliationError> {
+        let (roles_diff_service, classes_without_role) = self.create_roles_diff_service().await?;
+        let users = self
+            .authenticated_user_repository
+            .find_all()
+            .await
+            .map_err(map_user_repo_err)?
+            .into_iter()
+            .map(|user| (user.user_id(), user))
+            .collect::<HashMap<_, _>>();
+        let exemptions = self
+            .role_sync_exemption_repository
+            .find_all()
+            .await
+            .map_err(map_exemption_repo_err)?;
+
+        let mut members = 0;
+        let mut role_changes = BTreeMap::<RoleId, (u64, u64)>::new();
+        let mut member_diffs = Vec::new();
+
+        let mut offset = None;
+        while let Some(member_ids) = self
+            .discord_port
+            .find_all_members(offset)
+            .await
+            .map_err(map_discord_err)?
+        {
+            offset = member_ids.last().copied();
+
+            for user_id in member_ids {
+                let Some(assigned_roles) = self
+                    .discord_port
+                    .find_user_roles(user_id)
+                    .await
+                    .map_err(map_discord_err)?
+                else {
+                    continue; // The member left the guild in the meantime
+                };
+                members += 1;
+
+                let assigned_roles = assigned_roles.iter().map(|r| r.role_id).collect::<Vec<_>>();
+                if !find_member_exemptions(&exemptions, user_id, &assigned_roles).is_empty() {
+                    continue; // The role sync never changes the roles of exempt members
+                }
+                let user = users.get(&user_id);
+
+                let mut role_diff = roles_diff_service.diff_roles(user);
+                role_diff.optimize_by_already_assigned_roles(&assigned_roles);
+                if role_diff.to_assign().is_empty() && role_diff.to_remove().is_empty() {
+                    continue;
+                }
+
+                for role_id in role_diff.to_assign() {
+                    role_changes.entry(*role_id).or_default().0 += 1;
+                }
+                for role_id in role_diff.to_remove() {
+                    role_changes.entry(*role_id).or_default().1 += 1;
+                }
+                member_diffs.push(MemberRoleDiffDto {
+                    user_id,
+                    name: user.map(|user| user.name().to_string()),
+                    assigned: role_diff.to_assign().to_vec(),
+                    removed: role_diff.to_remove().to_vec(),
+                });
+            }
+        }
+
+        let mut role_change_counts = Vec::new();
+        for (role_id, (assigned, removed)) in role_changes {
+            let role_name = self
+                .discord_port
+                .find_role_name(role_id)
+                .await
+                .unwrap_or_else(|error| {
+                    warn!(error = ?error, role_id = role_id.0, "Failed to find the role name");
+                    None
+                });
+            role_change_counts.push(RoleChangeCountDto {
+                role_id,
+                role_name,
+                assigned,
+                removed,
+            });
+        }
+        member_diffs.sort_by_key(|diff| diff.user_id.0);
+
+        info!(
+            members,
+            members_with_changes = member_diffs.len(),
+            "Role reconciliation dry run finished",
+        );
+
+        Ok(RoleReconciliationReportDto {
+            members,
+            classes_without_role,
+            role_changes: role_change_counts,
+            member_diffs,
+        })
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_discord_err(err: DiscordError) -> RoleReconciliationError {
+    match err {
+        DiscordError::DiscordUnavailable => {
+            warn!("Failed to walk the guild members");
+            RoleReconciliationError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> RoleReconciliationError {
+    match err {
+        AuthenticatedUserRepositoryError::ServiceUnavailable => {
+            RoleReconciliationError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_class_repo_err(err: ClassRepositoryError) -> RoleReconciliationError {
+    match err {
+        ClassRepositoryError::ServiceUnavailable => RoleReconciliationError::TemporaryUnavailable,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_exemption_repo_err(err: RoleSyncExemptionRepositoryError) -> RoleReconciliationError {
+    match err {
+        RoleSyncExemptionRepositoryError::ServiceUnavailable => {
+            RoleReconciliationError::TemporaryUnavailable
+        }
+    }
+}
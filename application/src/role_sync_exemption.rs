@@ -0,0 +1,176 @@
+use application_ports::role_sync_exemption::{
+    ExemptionTargetDto, RoleSyncExemptionDto, RoleSyncExemptionError, RoleSyncExemptionPort,
+};
+use domain::jobs::role_sync_job::{
+    RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError, request_role_sync,
+};
+use domain::jobs::sync_trigger::SyncTrigger;
+use domain::ports::discord::{DiscordError, DiscordPort};
+use domain::role_sync_exemption::{
+    ExemptionTarget, RoleSyncExemption, RoleSyncExemptionRepository,
+    RoleSyncExemptionRepositoryError, create_role_sync_exemption, find_member_exemptions,
+};
+use domain_shared::discord::UserId;
+use tracing::{error, info, instrument};
+
+pub struct RoleSyncExemptionService<
+    TDiscordPort,
+    TRoleSyncExemptionRepository,
+    TRoleSyncRequestedRepository,
+> {
+    pub discord_port: TDiscordPort,
+    pub role_sync_exemption_repository: TRoleSyncExemptionRepository,
+    pub role_sync_requested_repository: TRoleSyncRequestedRepository,
+}
+
+impl<TDiscordPort, TRoleSyncExemptionRepository, TRoleSyncRequestedRepository> RoleSyncExemptionPort
+    for RoleSyncExemptionService<
+        TDiscordPort,
+        TRoleSyncExemptionRepository,
+        TRoleSyncRequestedRepository,
+    >
+where
+    TDiscordPort: DiscordPort + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
+    TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+{
+    #[instrument(level = "info", skip(self))]
+    async fn add_exemption(
+        &mut self,
+        target: ExemptionTargetDto,
+        reason: String,
+        created_by: UserId,
+    ) -> Result<(), RoleSyncExemptionError> {
+        let exemption =
+            create_role_sync_exemption(dto_to_exemption_target(target), reason, created_by);
+        self.role_sync_exemption_repository
+            .save(&exemption)
+            .await
+            .map_err(map_exemption_repo_err)?;
+
+        info!(exemption = ?exemption, "Role sync exemption added");
+
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn remove_exemption(
+        &mut self,
+        target: ExemptionTargetDto,
+    ) -> Result<(), RoleSyncExemptionError> {
+        let target = dto_to_exemption_target(target);
+        let removed = self
+            .role_sync_exemption_repository
+            .remove(target)
+            .await
+            .map_err(map_exemption_repo_err)?;
+        if !removed {
+            return Err(RoleSyncExemptionError::ExemptionNotFound);
+        }
+
+        // Members exempted by a role are synced again by the periodic role sync
+        if let ExemptionTarget::User(user_id) = target {
+            let role_sync_request = request_role_sync(user_id, SyncTrigger::ExemptionChange);
+            self.role_sync_requested_repository
+                .save(&role_sync_request)
+                .await
+                .map_err(map_role_sync_req_repo_err)?;
+        }
+
+        info!(target = ?target, "Role sync exemption removed");
+
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn list_exemptions(
+        &mut self,
+    ) -> Result<Vec<RoleSyncExemptionDto>, RoleSyncExemptionError> {
+        let exemptions = self
+            .role_sync_exemption_repository
+            .find_all()
+            .await
+            .map_err(map_exemption_repo_err)?;
+
+        Ok(exemptions.iter().map(exemption_to_dto).collect())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn find_user_exemptions(
+        &mut self,
+        user_id: UserId,
+    ) -> Result<Vec<RoleSyncExemptionDto>, RoleSyncExemptionError> {
+        let exemptions = self
+            .role_sync_exemption_repository
+            .find_all()
+            .await
+            .map_err(map_exemption_repo_err)?;
+        let assigned_roles = self
+            .discord_port
+            .find_user_roles(user_id)
+            .await
+            .map_err(map_discord_err)?
+            .unwrap_or_default()
+            .iter()
+            .map(|role| role.role_id)
+            .collect::<Vec<_>>();
+
+        Ok(
+            find_member_exemptions(&exemptions, user_id, &assigned_roles)
+                .into_iter()
+                .map(exemption_to_dto)
+                .collect(),
+        )
+    }
+}
+
+#[instrument(level = "trace")]
+fn dto_to_exemption_target(target: ExemptionTargetDto) -> ExemptionTarget {
+    match target {
+        ExemptionTargetDto::User(user_id) => ExemptionTarget::User(user_id),
+        ExemptionTargetDto::Role(role_id) => ExemptionTarget::Role(role_id),
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn exemption_to_dto(exemption: &RoleSyncExemption) -> RoleSyncExemptionDto {
+    RoleSyncExemptionDto {
+        target: match exemption.target {
+            ExemptionTarget::User(user_id) => ExemptionTargetDto::User(user_id),
+            ExemptionTarget::Role(role_id) => ExemptionTargetDto::Role(role_id),
+        },
+        reason: exemption.reason.clone(),
+        created_by: exemption.created_by,
+        created_at: exemption.created_at,
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_exemption_repo_err(err: RoleSyncExemptionRepositoryError) -> RoleSyncExemptionError {
+    match err {
+        RoleSyncExemptionRepositoryError::ServiceUnavailable => {
+            error!("RoleSyncExemptionRepositoryError::ServiceUnavailable");
+            RoleSyncExemptionError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_sync_req_repo_err(err: RoleSyncRequestedRepositoryError) -> RoleSyncExemptionError {
+    match err {
+        RoleSyncRequestedRepositoryError::ServiceUnavailable => {
+            error!("RoleSyncRequestedRepositoryError::ServiceUnavailable");
+            RoleSyncExemptionError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_discord_err(err: DiscordError) -> RoleSyncExemptionError {
+    match err {
+        DiscordError::DiscordUnavailable => {
+            error!("DiscordError::DiscordUnavailable");
+            RoleSyncExemptionError::TemporaryUnavailable
+        }
+    }
+}
@@ -1,66 +1,129 @@
 use application_ports::role_sync_job_handler::{RoleSyncJobHandlerError, RoleSyncJobHandlerPort};
 use chrono::{Duration, TimeDelta};
 use domain::authentication::authenticated_user::{
-    AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
+    AuthenticatedUser, AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
 };
-use domain::authentication::create_class_ids;
+use domain::authentication::group_role_rule::GroupRoleRule;
+use domain::class::catalog::{ClassRepository, ClassRepositoryError};
+use domain::class::class_id::{ClassId, FieldOfStudy};
 use domain::jobs::role_sync_job::{
     RoleSyncRequested, RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError,
 };
-use domain::ports::discord::{DiscordError, DiscordPort};
+use domain::nickname::{
+    NicknamePolicy, NicknameSettings, NicknameSettingsRepository, NicknameSettingsRepositoryError,
+};
+use domain::ports::discord::{DiscordError, DiscordPort, NicknameUpdate};
+use domain::role_change_log::{
+    RoleChangeLogRepository, RoleChangeLogRepositoryError, create_role_change_log_entry,
+};
+use domain::role_sync_exemption::{
+    RoleSyncExemptionRepository, RoleSyncExemptionRepositoryError, find_member_exemptions,
+};
 use domain::roles::RolesDiffService;
-use domain_shared::discord::RoleId;
+use domain_shared::discord::{RoleId, UserId};
 use tracing::{error, info, instrument};
 
+#[derive(Clone, Debug)]
+pub struct RoleSyncConfig {
+    pub everyone_roles: Vec<RoleId>,
+    pub additional_student_roles: Vec<RoleId>,
+    pub staff_roles: Vec<RoleId>,
+    pub alumni_role_id: Option<RoleId>,
+    pub unknown_class_role_id: RoleId,
+    /// Roles assigned to the students of all classes of the year
+    pub year_roles: Vec<(u8, RoleId)>,
+    /// Roles assigned to the students of the field of study, `None` is the main field of study
+    pub field_roles: Vec<(Option<FieldOfStudy>, RoleId)>,
+    pub group_role_rules: Vec<GroupRoleRule>,
+    /// Nicknames of the verified users, they are not changed when unset
+    pub nickname_policy: Option<NicknamePolicy>,
+}
+
+impl RoleSyncConfig {
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn create_roles_diff_service(
+        &self,
+        class_id_to_role_id: Vec<(ClassId, RoleId)>,
+        retired_class_role_ids: Vec<RoleId>,
+    ) -> RolesDiffService {
+        let RoleSyncConfig {
+            everyone_roles,
+            additional_student_roles,
+            staff_roles,
+            alumni_role_id,
+            unknown_class_role_id,
+            year_roles,
+            field_roles,
+            group_role_rules,
+            nickname_policy: _,
+        } = self.clone();
+
+        RolesDiffService {
+            everyone_roles,
+            additional_student_roles,
+            staff_roles,
+            alumni_role_id,
+            unknown_class_role_id,
+            class_id_to_role_id,
+            retired_class_role_ids,
+            year_to_role_id: year_roles,
+            field_to_role_id: field_roles,
+            group_role_rules,
+        }
+    }
+}
+
 pub struct RoleSyncJobHandler<
     TDiscordPort,
     TAuthenticatedUserRepository,
+    TClassRepository,
     TRoleSyncRequestedRepository,
+    TRoleChangeLogRepository,
+    TNicknameSettingsRepository,
+    TRoleSyncExemptionRepository,
 > {
-    discord_port: TDiscordPort,
-    authenticated_user_repository: TAuthenticatedUserRepository,
-    role_sync_requested_repository: TRoleSyncRequestedRepository,
-    everyone_roles: Vec<RoleId>,
-    additional_student_roles: Vec<RoleId>,
-    class_ids: Vec<String>,
-    roles_diff_service: Option<RolesDiffService>,
-    unknown_class_role_id: RoleId,
+    pub discord_port: TDiscordPort,
+    pub authenticated_user_repository: TAuthenticatedUserRepository,
+    pub class_repository: TClassRepository,
+    pub role_sync_requested_repository: TRoleSyncRequestedRepository,
+    pub role_change_log_repository: TRoleChangeLogRepository,
+    pub nickname_settings_repository: TNicknameSettingsRepository,
+    pub role_sync_exemption_repository: TRoleSyncExemptionRepository,
+    pub config: RoleSyncConfig,
 }
 
-impl<TDiscordPort, TAuthenticatedUserRepository, TRoleSyncRequestedRepository>
-    RoleSyncJobHandler<TDiscordPort, TAuthenticatedUserRepository, TRoleSyncRequestedRepository>
+impl<
+    TDiscordPort,
+    TAuthenticatedUserRepository,
+    TClassRepository,
+    TRoleSyncRequestedRepository,
+    TRoleChangeLogRepository,
+    TNicknameSettingsRepository,
+    TRoleSyncExemptionRepository,
+>
+    RoleSyncJobHandler<
+        TDiscordPort,
+        TAuthenticatedUserRepository,
+        TClassRepository,
+        TRoleSyncRequestedRepository,
+        TRoleChangeLogRepository,
+        TNicknameSettingsRepository,
+        TRoleSyncExemptionRepository,
+    >
 where
     TDiscordPort: DiscordPort + Send + Sync,
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+    TRoleChangeLogRepository: RoleChangeLogRepository + Send + Sync,
+    TNicknameSettingsRepository: NicknameSettingsRepository + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
 {
-    #[instrument(level = "trace", skip_all)]
-    pub fn new(
-        discord_port: TDiscordPort,
-        authenticated_user_repository: TAuthenticatedUserRepository,
-        role_sync_requested_repository: TRoleSyncRequestedRepository,
-        everyone_roles: Vec<RoleId>,
-        additional_student_roles: Vec<RoleId>,
-        unknown_class_role_id: RoleId,
-    ) -> Self {
-        let class_ids = create_class_ids();
-
-        Self {
-            discord_port,
-            authenticated_user_repository,
-            role_sync_requested_repository,
-            everyone_roles,
-            additional_student_roles,
-            class_ids,
-            roles_diff_service: None,
-            unknown_class_role_id,
-        }
-    }
-
     #[instrument(level = "info", skip(self))]
-    async fn handle(&mut self, request: RoleSyncRequested) -> Result<(), RoleSyncJobHandlerError> {
+    async fn handle(&self, request: RoleSyncRequested) -> Result<(), RoleSyncJobHandlerError> {
         const MIN_DURATION_SINCE_QUEUED: TimeDelta = Duration::milliseconds(400);
         const WAIT_TICK_DURATION: TimeDelta = Duration::milliseconds(100);
+        let reason = request.trigger.reason();
         let can_sync_since = request.queued_at + MIN_DURATION_SINCE_QUEUED;
         loop {
             if can_sync_since <= chrono::Utc::now() {
@@ -70,16 +133,10 @@ where
             tokio::time::sleep(WAIT_TICK_DURATION.to_std().unwrap()).await;
         }
 
-        if self.roles_diff_service.is_none() {
-            let roles_diff_service = self.create_roles_diff_service().await?;
-            self.roles_diff_service = Some(roles_diff_service);
-        }
-        let roles_diff_service = self.roles_diff_service.as_ref().ok_or_else(|| {
-            error!("Unreachable: roles_diff_service is None, but it should be Some at this point");
-            RoleSyncJobHandlerError::TemporaryUnavailable
-        })?;
+        // The class catalog is read on every sync, so changes made by the admins apply immediately
+        let roles_diff_service = self.create_roles_diff_service().await?;
 
-        let (assigned_roles, user) = tokio::try_join!(
+        let (assigned_roles, user, exemptions) = tokio::try_join!(
             async {
                 self.discord_port
                     .find_user_roles(request.user_id)
@@ -91,6 +148,12 @@ where
                     .find_by_user_id(request.user_id)
                     .await
                     .map_err(map_user_repo_err)
+            },
+            async {
+                self.role_sync_exemption_repository
+                    .find_all()
+                    .await
+                    .map_err(map_exemption_repo_err)
             }
         )?;
         let assigned_roles = match assigned_roles {
@@ -100,52 +163,191 @@ where
 
         let assigned_roles = assigned_roles.iter().map(|r| r.role_id).collect::<Vec<_>>();
 
+        let member_exemptions =
+            find_member_exemptions(&exemptions, request.user_id, &assigned_roles);
+        if !member_exemptions.is_empty() {
+            info!(
+                "User {:?} is exempt from the role sync by {:?}, skipping",
+                request.user_id, member_exemptions,
+            );
+            return Ok(());
+        }
+
         let mut role_diff = roles_diff_service.diff_roles(user.as_ref());
         role_diff.optimize_by_already_assigned_roles(&assigned_roles);
 
         self.discord_port
-            .apply_role_diff(request.user_id, &role_diff, "Role sync job handler")
+            .apply_role_diff(request.user_id, &role_diff, reason)
             .await
             .map_err(map_discord_err)?;
 
+        if let Some(entry) =
+            create_role_change_log_entry(request.user_id, &role_diff, reason, request.low_priority)
+        {
+            self.role_change_log_repository
+                .save(&entry)
+                .await
+                .map_err(map_role_change_log_repo_err)?;
+        }
+
         info!(
             "Successfully synced roles for user {:?} with diff {:?}",
             request.user_id, role_diff,
         );
 
+        if let Some(nickname_policy) = &self.config.nickname_policy {
+            self.sync_nickname(nickname_policy, request.user_id, user.as_ref(), reason)
+                .await?;
+        }
+
         Ok(())
     }
 
-    #[instrument(level = "trace", skip(self))]
-    async fn create_roles_diff_service(&self) -> Result<RolesDiffService, RoleSyncJobHandlerError> {
-        let mut class_id_to_role_id = Vec::new();
+    /// Sets the nickname of the verified user, or removes the nickname set by the bot
+    /// once the user is no longer verified or the nickname sync is disabled for them
+    #[instrument(level = "debug", skip(self, nickname_policy, user))]
+    async fn sync_nickname(
+        &self,
+        nickname_policy: &NicknamePolicy,
+        user_id: UserId,
+        user: Option<&AuthenticatedUser>,
+        reason: &str,
+    ) -> Result<(), RoleSyncJobHandlerError> {
+        let mut settings = self
+            .nickname_settings_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(map_nickname_settings_repo_err)?
+            .unwrap_or_else(|| NicknameSettings::new(user_id));
+
+        let Some(user) = user.filter(|_| settings.is_sync_enabled()) else {
+            let Some(applied_nickname) = settings.applied_nickname.take() else {
+                return Ok(());
+            };
 
-        for class_id in &self.class_ids {
-            let role = self
+            let nickname_update = self
                 .discord_port
-                .find_or_create_role_by_name(&class_id.to_uppercase(), "Role for students of class")
+                .reset_nickname(user_id, &applied_nickname, reason)
                 .await
                 .map_err(map_discord_err)?;
-            class_id_to_role_id.push((class_id.to_string(), role.role_id));
+            if nickname_update == NicknameUpdate::Updated {
+                info!("Reset nickname of user {:?}", user_id);
+            }
+
+            // The nickname is not reset again, even when the bot cannot rename the member
+            self.nickname_settings_repository
+                .save(&settings)
+                .await
+                .map_err(map_nickname_settings_repo_err)?;
+            return Ok(());
+        };
+
+        let nickname = nickname_policy.nickname(user);
+        let nickname_update = self
+            .discord_port
+            .set_nickname(user_id, &nickname, reason)
+            .await
+            .map_err(map_discord_err)?;
+
+        match nickname_update {
+            NicknameUpdate::Updated => {
+                info!("Set nickname of user {:?} to {:?}", user_id, nickname);
+            }
+            NicknameUpdate::Skipped => {
+                info!(
+                    "Nickname of user {:?} is not set, the bot cannot rename them",
+                    user_id,
+                );
+            }
+            NicknameUpdate::Unchanged | NicknameUpdate::MemberNotFound => {}
         }
 
-        Ok(RolesDiffService {
-            everyone_roles: self.everyone_roles.clone(),
-            additional_student_roles: self.additional_student_roles.clone(),
-            unknown_class_role_id: self.unknown_class_role_id,
-            class_ids: self.class_ids.clone(),
-            class_id_to_role_id,
-        })
+        if matches!(
+            nickname_update,
+            NicknameUpdate::Updated | NicknameUpdate::Unchanged
+        ) && settings.applied_nickname.as_deref() != Some(nickname.as_str())
+        {
+            settings.applied_nickname = Some(nickname);
+            self.nickname_settings_repository
+                .save(&settings)
+                .await
+                .map_err(map_nickname_settings_repo_err)?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn create_roles_diff_service(&self) -> Result<RolesDiffService, RoleSyncJobHandlerError> {
+        let classes = self
+            .class_repository
+            .find_all()
+            .await
+            .map_err(map_class_repo_err)?;
+
+        let mut class_id_to_role_id = Vec::new();
+        let mut retired_class_role_ids = Vec::new();
+
+        for mut class in classes {
+            if !class.is_active() {
+                retired_class_role_ids.extend(class.role_id());
+                continue;
+            }
+
+            let role_id = match class.role_id() {
+                Some(role_id) => role_id,
+                None => {
+                    let role = self
+                        .discord_port
+                        .find_or_create_role_by_name(
+                            class.display_name(),
+                            "Role for students of class",
+                        )
+                        .await
+                        .map_err(map_discord_err)?;
+                    class.assign_role(role.role_id);
+                    self.class_repository
+                        .save(&class)
+                        .await
+                        .map_err(map_class_repo_err)?;
+                    role.role_id
+                }
+            };
+            class_id_to_role_id.push((class.class_id().clone(), role_id));
+        }
+
+        Ok(self
+            .config
+            .create_roles_diff_service(class_id_to_role_id, retired_class_role_ids))
     }
 }
 
-impl<TDiscordPort, TAuthenticatedUserRepository, TRoleSyncRequestedRepository>
-    RoleSyncJobHandlerPort
-    for RoleSyncJobHandler<TDiscordPort, TAuthenticatedUserRepository, TRoleSyncRequestedRepository>
+impl<
+    TDiscordPort,
+    TAuthenticatedUserRepository,
+    TClassRepository,
+    TRoleSyncRequestedRepository,
+    TRoleChangeLogRepository,
+    TNicknameSettingsRepository,
+    TRoleSyncExemptionRepository,
+> RoleSyncJobHandlerPort
+    for RoleSyncJobHandler<
+        TDiscordPort,
+        TAuthenticatedUserRepository,
+        TClassRepository,
+        TRoleSyncRequestedRepository,
+        TRoleChangeLogRepository,
+        TNicknameSettingsRepository,
+        TRoleSyncExemptionRepository,
+    >
 where
     TDiscordPort: DiscordPort + Send + Sync,
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
+    TRoleChangeLogRepository: RoleChangeLogRepository + Send + Sync,
+    TNicknameSettingsRepository: NicknameSettingsRepository + Send + Sync,
+    TRoleSyncExemptionRepository: RoleSyncExemptionRepository + Send + Sync,
 {
     #[instrument(level = "debug", skip_all)]
     async fn tick(&mut self) -> Result<(), RoleSyncJobHandlerError> {
@@ -195,6 +397,16 @@ fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> RoleSyncJobHandle
     }
 }
 
+#[instrument(level = "trace", skip_all)]
+fn map_class_repo_err(err: ClassRepositoryError) -> RoleSyncJobHandlerError {
+    match err {
+        ClassRepositoryError::ServiceUnavailable => {
+            error!("ClassRepositoryError::ServiceUnavailable");
+            RoleSyncJobHandlerError::TemporaryUnavailable
+        }
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 fn map_sync_req_repo_err(err: RoleSyncRequestedRepositoryError) -> RoleSyncJobHandlerError {
     match err {
@@ -204,3 +416,33 @@ fn map_sync_req_repo_err(err: RoleSyncRequestedRepositoryError) -> RoleSyncJobHa
         }
     }
 }
+
+#[instrument(level = "trace", skip_all)]
+fn map_role_change_log_repo_err(err: RoleChangeLogRepositoryError) -> RoleSyncJobHandlerError {
+    match err {
+        RoleChangeLogRepositoryError::ServiceUnavailable => {
+            error!("RoleChangeLogRepositoryError::ServiceUnavailable");
+            RoleSyncJobHandlerError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_nickname_settings_repo_err(err: NicknameSettingsRepositoryError) -> RoleSyncJobHandlerError {
+    match err {
+        NicknameSettingsRepositoryError::ServiceUnavailable => {
+            error!("NicknameSettingsRepositoryError::ServiceUnavailable");
+            RoleSyncJobHandlerError::TemporaryUnavailable
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_exemption_repo_err(err: RoleSyncExemptionRepositoryError) -> RoleSyncJobHandlerError {
+    match err {
+        RoleSyncExemptionRepositoryError::ServiceUnavailable => {
+            error!("RoleSyncExemptionRepositoryError::ServiceUnavailable");
+            RoleSyncJobHandlerError::TemporaryUnavailable
+        }
+    }
+}
@@ -1,11 +1,19 @@
-use application_ports::user::{AuthenticatedUserInfoDto, UserError, UserPort};
+use application_ports::user::{
+    ArchivedUserInfoDto, AuthenticatedUserInfoDto, ManualVerificationDto, UserError,
+    UserHistoryQuery, UserPort, UserSearchPageDto,
+};
 use chrono::Duration;
+use domain::authentication::archived_authenticated_user::{
+    ArchivedAuthenticatedUser, ArchivedAuthenticatedUserRepository,
+    ArchivedAuthenticatedUserRepositoryError,
+};
 use domain::authentication::authenticated_user::{
-    AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
+    AuthenticatedUser, AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
 };
 use domain::jobs::role_sync_job::{
     RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError, request_role_sync,
 };
+use domain::jobs::sync_trigger::SyncTrigger;
 use domain::jobs::user_info_sync_job::{
     UserInfoSyncRequestedRepository, UserInfoSyncRequestedRepositoryError, request_user_info_sync,
 };
@@ -14,48 +22,65 @@ use tracing::{info, instrument, warn};
 
 pub struct UserService<
     TAuthenticatedUserRepository,
+    TArchivedAuthenticatedUserRepository,
     TRoleSyncRequestedRepository,
     TUserInfoSyncRequestedRepository,
 > {
     authenticated_user_repository: TAuthenticatedUserRepository,
+    archived_authenticated_user_repository: TArchivedAuthenticatedUserRepository,
     role_sync_requested_repository: TRoleSyncRequestedRepository,
     user_info_sync_requested_repository: TUserInfoSyncRequestedRepository,
 }
 
-impl<TAuthenticatedUserRepository, TRoleSyncRequestedRepository, TUserInfoSyncRequestedRepository>
+impl<
+    TAuthenticatedUserRepository,
+    TArchivedAuthenticatedUserRepository,
+    TRoleSyncRequestedRepository,
+    TUserInfoSyncRequestedRepository,
+>
     UserService<
         TAuthenticatedUserRepository,
+        TArchivedAuthenticatedUserRepository,
         TRoleSyncRequestedRepository,
         TUserInfoSyncRequestedRepository,
     >
 where
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
     TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
 {
     #[instrument(level = "trace", skip_all)]
     pub fn new(
         authenticated_user_repository: TAuthenticatedUserRepository,
+        archived_authenticated_user_repository: TArchivedAuthenticatedUserRepository,
         role_sync_requested_repository: TRoleSyncRequestedRepository,
         user_info_sync_requested_repository: TUserInfoSyncRequestedRepository,
     ) -> Self {
         Self {
             authenticated_user_repository,
+            archived_authenticated_user_repository,
             role_sync_requested_repository,
             user_info_sync_requested_repository,
         }
     }
 }
 
-impl<TAuthenticatedUserRepository, TRoleSyncRequestedRepository, TUserInfoSyncRequestedRepository>
-    UserPort
+impl<
+    TAuthenticatedUserRepository,
+    TArchivedAuthenticatedUserRepository,
+    TRoleSyncRequestedRepository,
+    TUserInfoSyncRequestedRepository,
+> UserPort
     for UserService<
         TAuthenticatedUserRepository,
+        TArchivedAuthenticatedUserRepository,
         TRoleSyncRequestedRepository,
         TUserInfoSyncRequestedRepository,
     >
 where
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TArchivedAuthenticatedUserRepository: ArchivedAuthenticatedUserRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
     TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
 {
@@ -74,18 +99,12 @@ where
             Some(user) => user,
         };
 
-        Ok(Some(AuthenticatedUserInfoDto {
-            user_id: user.user_id(),
-            name: user.name().to_string(),
-            email: user.email().to_string(),
-            class_id: user.class_id().map(|s| s.to_string()),
-            authenticated_at: user.authenticated_at(),
-        }))
+        Ok(Some(user_to_info_dto(&user)))
     }
 
     #[instrument(level = "info", skip(self))]
     async fn refresh_user_roles(&mut self, user_id: UserId) -> Result<(), UserError> {
-        let request = request_role_sync(user_id);
+        let request = request_role_sync(user_id, SyncTrigger::AdminRefresh);
         self.role_sync_requested_repository
             .save(&request)
             .await
@@ -94,9 +113,23 @@ where
         Ok(())
     }
 
+    #[instrument(level = "info", skip(self))]
+    async fn sync_joined_member_roles(&mut self, user_id: UserId) -> Result<(), UserError> {
+        let request = request_role_sync(user_id, SyncTrigger::MemberJoin);
+        self.role_sync_requested_repository
+            .save(&request)
+            .await
+            .map_err(map_role_sync_req_repo_err)?;
+        info!(
+            user_id = user_id.0,
+            "Role sync of the joined member requested"
+        );
+        Ok(())
+    }
+
     #[instrument(level = "info", skip(self))]
     async fn refresh_user_info(&mut self, user_id: UserId) -> Result<Duration, UserError> {
-        let request = request_user_info_sync(user_id);
+        let request = request_user_info_sync(user_id, SyncTrigger::AdminRefresh);
         self.user_info_sync_requested_repository
             .save(&request)
             .await
@@ -106,6 +139,88 @@ where
 
         Ok(Duration::milliseconds(750))
     }
+
+    #[instrument(level = "info", skip(self))]
+    async fn get_user_history(
+        &mut self,
+        query: UserHistoryQuery,
+    ) -> Result<Vec<ArchivedUserInfoDto>, UserError> {
+        let archived_users = match &query {
+            UserHistoryQuery::UserId(user_id) => {
+                self.archived_authenticated_user_repository
+                    .find_by_user_id(*user_id)
+                    .await
+            }
+            UserHistoryQuery::Email(email) => {
+                self.archived_authenticated_user_repository
+                    .find_by_email(email)
+                    .await
+            }
+        }
+        .map_err(map_archived_user_repo_err)?;
+
+        Ok(archived_users
+            .iter()
+            .map(archived_user_to_info_dto)
+            .collect())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    async fn search_users(
+        &mut self,
+        query: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<UserSearchPageDto, UserError> {
+        let total = self
+            .authenticated_user_repository
+            .count_search(query)
+            .await
+            .map_err(map_user_repo_err)?;
+        let users = self
+            .authenticated_user_repository
+            .search(query, offset, limit)
+            .await
+            .map_err(map_user_repo_err)?;
+
+        Ok(UserSearchPageDto {
+            users: users.iter().map(user_to_info_dto).collect(),
+            total,
+        })
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn user_to_info_dto(user: &AuthenticatedUser) -> AuthenticatedUserInfoDto {
+    AuthenticatedUserInfoDto {
+        user_id: user.user_id(),
+        name: user.name().to_string(),
+        email: user.email().to_string(),
+        kind: user.kind(),
+        class_id: user.class_id().map(|s| s.to_string()),
+        graduation_year: user.graduation_year(),
+        manual_verification: user.manual_verification().map(|manual_verification| {
+            ManualVerificationDto {
+                verified_by: manual_verification.verified_by,
+                reason: manual_verification.reason.clone(),
+            }
+        }),
+        authenticated_at: user.authenticated_at(),
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub(crate) fn archived_user_to_info_dto(
+    archived_user: &ArchivedAuthenticatedUser,
+) -> ArchivedUserInfoDto {
+    ArchivedUserInfoDto {
+        user_id: archived_user.user_id(),
+        name: archived_user.name().to_string(),
+        email: archived_user.email().to_string(),
+        class_id: archived_user.class_id().map(|s| s.to_string()),
+        authenticated_at: archived_user.authenticated_at(),
+        archived_at: archived_user.archived_at(),
+    }
 }
 
 #[instrument(level = "trace", skip_all)]
@@ -115,6 +230,15 @@ fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> UserError {
     }
 }
 
+#[instrument(level = "trace", skip_all)]
+fn map_archived_user_repo_err(err: ArchivedAuthenticatedUserRepositoryError) -> UserError {
+    match err {
+        ArchivedAuthenticatedUserRepositoryError::ServiceUnavailable => {
+            UserError::TemporaryUnavailable
+        }
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 fn map_role_sync_req_repo_err(err: RoleSyncRequestedRepositoryError) -> UserError {
     match err {
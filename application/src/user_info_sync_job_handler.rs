@@ -2,12 +2,16 @@ use application_ports::user_info_sync_job_handler::{
     UserInfoSyncJobHandlerError, UserInfoSyncJobHandlerPort,
 };
 use async_trait::async_trait;
-use chrono::{Duration, TimeDelta};
+use chrono::{Duration, TimeDelta, Utc};
 use domain::authentication::authenticated_user::{
     AuthenticatedUser, AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
 };
+use domain::authentication::group_role_rule::{GroupRoleRule, find_rule_group_ids};
+use domain::authentication::user_kind::find_user_kind;
+use domain::class::catalog::{ClassRepository, ClassRepositoryError};
 use domain::class::class_group::find_class_group;
 use domain::class::class_id::get_class_id;
+use domain::class::graduation::{GraduationDate, find_graduation_year};
 use domain::jobs::role_sync_job::{
     RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError, request_role_sync,
 };
@@ -15,34 +19,48 @@ use domain::jobs::user_info_sync_job::{
     UserInfoSyncRequested, UserInfoSyncRequestedRepository, UserInfoSyncRequestedRepositoryError,
 };
 use domain::ports::oauth::{OAuthError, OAuthPort, OAuthToken};
+use domain_shared::authentication::UserKind;
 use tracing::{error, info, instrument, warn};
 
+#[derive(Clone, Debug)]
+pub struct UserInfoSyncConfig {
+    pub staff_groups: Vec<String>,
+    pub group_role_rules: Vec<GroupRoleRule>,
+    pub graduation_date: GraduationDate,
+}
+
 pub struct UserInfoSyncJobHandler<
     TAuthenticatedUserRepository,
+    TClassRepository,
     TRoleSyncRequestedRepository,
     TUserInfoSyncRequestedRepository,
     TOAuthAdapter,
 > {
     authenticated_user_repository: TAuthenticatedUserRepository,
+    class_repository: TClassRepository,
     role_sync_requested_repository: TRoleSyncRequestedRepository,
     user_info_sync_requested_repository: TUserInfoSyncRequestedRepository,
     oauth_port: TOAuthAdapter,
+    config: UserInfoSyncConfig,
 }
 
 impl<
     TAuthenticatedUserRepository,
+    TClassRepository,
     TRoleSyncRequestedRepository,
     TUserInfoSyncRequestedRepository,
     TOAuthAdapter,
 >
     UserInfoSyncJobHandler<
         TAuthenticatedUserRepository,
+        TClassRepository,
         TRoleSyncRequestedRepository,
         TUserInfoSyncRequestedRepository,
         TOAuthAdapter,
     >
 where
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
     TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
     TOAuthAdapter: OAuthPort + Send + Sync,
@@ -50,15 +68,19 @@ where
     #[instrument(level = "trace", skip_all)]
     pub fn new(
         authenticated_user_repository: TAuthenticatedUserRepository,
+        class_repository: TClassRepository,
         role_sync_requested_repository: TRoleSyncRequestedRepository,
         user_info_sync_requested_repository: TUserInfoSyncRequestedRepository,
         oauth_port: TOAuthAdapter,
+        config: UserInfoSyncConfig,
     ) -> Self {
         Self {
             authenticated_user_repository,
+            class_repository,
             role_sync_requested_repository,
             user_info_sync_requested_repository,
             oauth_port,
+            config,
         }
     }
 
@@ -84,7 +106,14 @@ where
             .await
             .map_err(map_user_repo_err)?;
 
-        if let Some(mut user) = user {
+        if let Some(user) = &user
+            && user.is_manually_verified()
+        {
+            info!(
+                user_id = user.user_id().0,
+                "Skipping user info sync of a manually verified user",
+            );
+        } else if let Some(mut user) = user {
             match self.handle_authenticated_user(&mut user).await {
                 Ok(()) => {}
                 Err(err) => {
@@ -101,7 +130,7 @@ where
                 .map_err(map_user_repo_err)?;
         }
 
-        let request = request_role_sync(request.user_id);
+        let request = request_role_sync(request.user_id, request.trigger);
         self.role_sync_requested_repository
             .save(&request)
             .await
@@ -117,34 +146,110 @@ where
     ) -> Result<(), UserInfoSyncJobHandlerError> {
         let token = match self.refresh_oauth_token(user).await? {
             None => {
-                user.mark_class_unknown();
+                // Nothing is known about the groups of the user, the graduation is only detected
+                // from a vanished class group, so the final-year class is kept
+                if !user
+                    .class_id()
+                    .is_some_and(|class_id| class_id.is_final_year())
+                {
+                    user.mark_class_unknown();
+                }
                 return Ok(());
             }
             Some(token) => token,
         };
 
+        let access_token = token.access_token.clone();
         user.update_oauth_token(token);
 
         let user_info = self
             .oauth_port
-            .get_user_info(&user.oauth_token().access_token)
+            .get_user_info(&access_token)
             .await
             .map_err(map_oauth_err)?;
 
         user.update_user_info(user_info.name, user_info.email);
 
-        let class_group = find_class_group(&user_info.groups);
-        let class_id = class_group.and_then(get_class_id);
+        let group_ids = find_rule_group_ids(&user_info.groups, &self.config.group_role_rules);
+        user.update_group_ids(group_ids);
+
+        let kind = find_user_kind(&user_info.groups, &self.config.staff_groups);
+        user.update_kind(kind);
+        if kind == UserKind::Staff {
+            // Staff members do not belong to any class
+            user.mark_class_unknown();
+            return Ok(());
+        }
+
+        let class_ids = self
+            .class_repository
+            .find_all()
+            .await
+            .map_err(map_class_repo_err)?
+            .iter()
+            .map(|class| class.class_id().clone())
+            .collect::<Vec<_>>();
+        let class_group = find_class_group(&user_info.groups, &class_ids);
+        let class_id = class_group.and_then(|group| get_class_id(group, &class_ids));
 
         if let Some(class_id) = class_id {
+            if user.is_alumni() {
+                info!(
+                    user_id = user.user_id().0,
+                    class_id = %class_id,
+                    "Class group of an alumnus is back, marking the user as a student again",
+                );
+            }
             user.update_class_id(class_id);
         } else {
-            user.mark_class_unknown();
+            self.handle_vanished_class(user);
         }
 
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn handle_vanished_class(&self, user: &mut AuthenticatedUser) {
+        if user.is_alumni() {
+            // Alumni keep the class they graduated from
+            return;
+        }
+
+        let Some(class_id) = user.class_id().filter(|class_id| class_id.is_final_year()) else {
+            user.mark_class_unknown();
+            return;
+        };
+
+        let graduation_year = user.class_confirmed_at().and_then(|class_confirmed_at| {
+            find_graduation_year(
+                class_id,
+                class_confirmed_at,
+                self.config.graduation_date,
+                Utc::now(),
+            )
+        });
+        match graduation_year {
+            Some(graduation_year) => {
+                info!(
+                    user_id = user.user_id().0,
+                    class_id = %class_id,
+                    graduation_year,
+                    "User's final-year class group vanished, marking the user as alumni",
+                );
+                user.graduate(graduation_year);
+            }
+            None => {
+                // The group of a final-year class is removed after the final exams, keep the class
+                // so that the user is recognised as an alumnus once the graduation date passes
+                info!(
+                    user_id = user.user_id().0,
+                    class_id = %class_id,
+                    "User's final-year class group vanished before the graduation date, keeping the class",
+                );
+            }
+        }
+    }
+
     #[instrument(level = "trace", skip(self))]
     async fn refresh_oauth_token(
         &self,
@@ -154,7 +259,15 @@ where
             user_id = user.user_id().0,
             "Periodically refreshing user's OAuth token",
         );
-        match self.oauth_port.refresh_token(user.oauth_token()).await {
+        let oauth_token = match user.oauth_token() {
+            Some(oauth_token) => oauth_token,
+            None => {
+                warn!(user_id = user.user_id().0, "User has no OAuth token");
+                return Ok(None);
+            }
+        };
+
+        match self.oauth_port.refresh_token(oauth_token).await {
             Ok(new_token) => Ok(Some(new_token)),
             Err(err) => match err {
                 OAuthError::OAuthUnavailable => {
@@ -175,18 +288,21 @@ where
 #[async_trait]
 impl<
     TAuthenticatedUserRepository,
+    TClassRepository,
     TRoleSyncRequestedRepository,
     TUserInfoSyncRequestedRepository,
     TOAuthAdapter,
 > UserInfoSyncJobHandlerPort
     for UserInfoSyncJobHandler<
         TAuthenticatedUserRepository,
+        TClassRepository,
         TRoleSyncRequestedRepository,
         TUserInfoSyncRequestedRepository,
         TOAuthAdapter,
     >
 where
     TAuthenticatedUserRepository: AuthenticatedUserRepository + Send + Sync,
+    TClassRepository: ClassRepository + Send + Sync,
     TRoleSyncRequestedRepository: RoleSyncRequestedRepository + Send + Sync,
     TUserInfoSyncRequestedRepository: UserInfoSyncRequestedRepository + Send + Sync,
     TOAuthAdapter: OAuthPort + Send + Sync,
@@ -243,6 +359,16 @@ fn map_user_repo_err(err: AuthenticatedUserRepositoryError) -> UserInfoSyncJobHa
     }
 }
 
+#[instrument(level = "trace", skip_all)]
+fn map_class_repo_err(err: ClassRepositoryError) -> UserInfoSyncJobHandlerError {
+    match err {
+        ClassRepositoryError::ServiceUnavailable => {
+            error!("ClassRepositoryError::ServiceUnavailable");
+            UserInfoSyncJobHandlerError::TemporaryUnavailable
+        }
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 fn map_sync_req_repo_err(err: UserInfoSyncRequestedRepositoryError) -> UserInfoSyncJobHandlerError {
     match err {
@@ -264,3 +390,118 @@ fn map_role_sync_req_repo_err(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use domain::authentication::authenticated_user::{
+        AuthenticatedUserSnapshot, MockAuthenticatedUserRepository,
+    };
+    use domain::class::catalog::{MockClassRepository, create_class};
+    use domain::class::class_id::ClassId;
+    use domain::jobs::role_sync_job::MockRoleSyncRequestedRepository;
+    use domain::jobs::user_info_sync_job::MockUserInfoSyncRequestedRepository;
+    use domain::ports::oauth::{MockOAuthPort, UserInfoDto};
+    use domain_shared::authentication::{AccessToken, RefreshToken, UserGroup};
+    use domain_shared::discord::UserId;
+
+    type Handler = UserInfoSyncJobHandler<
+        MockAuthenticatedUserRepository,
+        MockClassRepository,
+        MockRoleSyncRequestedRepository,
+        MockUserInfoSyncRequestedRepository,
+        MockOAuthPort,
+    >;
+
+    fn handler(class_repository: MockClassRepository, oauth_port: MockOAuthPort) -> Handler {
+        UserInfoSyncJobHandler::new(
+            MockAuthenticatedUserRepository::new(),
+            class_repository,
+            MockRoleSyncRequestedRepository::new(),
+            MockUserInfoSyncRequestedRepository::new(),
+            oauth_port,
+            UserInfoSyncConfig {
+                staff_groups: vec![],
+                group_role_rules: vec![],
+                graduation_date: GraduationDate { month: 6, day: 30 },
+            },
+        )
+    }
+
+    fn oauth_token() -> OAuthToken {
+        OAuthToken {
+            access_token: AccessToken("access".to_string()),
+            expires_at: Utc::now(),
+            refresh_token: RefreshToken("refresh".to_string()),
+        }
+    }
+
+    fn student(class_id: &str, graduation_year: Option<i32>) -> AuthenticatedUser {
+        AuthenticatedUser::from_snapshot(AuthenticatedUserSnapshot {
+            user_id: UserId(1),
+            name: "Jan Novák".to_string(),
+            email: "novak@ssps.cz".to_string(),
+            kind: UserKind::Student,
+            oauth_token: Some(oauth_token()),
+            manual_verification: None,
+            class_id: ClassId::parse(class_id),
+            // Confirmed in a school year whose graduation date has passed
+            class_confirmed_at: Some(Utc.with_ymd_and_hms(2020, 5, 1, 12, 0, 0).unwrap()),
+            group_ids: vec![],
+            graduation_year,
+            authenticated_at: Utc::now(),
+        })
+    }
+
+    #[tokio::test]
+    async fn token_failure_does_not_graduate_final_year_students() {
+        let mut oauth_port = MockOAuthPort::new();
+        oauth_port
+            .expect_refresh_token()
+            .returning(|_| Box::pin(async { Err(OAuthError::TokenExpired) }));
+        let handler = handler(MockClassRepository::new(), oauth_port);
+
+        let mut user = student("c4b", None);
+        handler.handle_authenticated_user(&mut user).await.unwrap();
+
+        assert!(!user.is_alumni());
+        assert_eq!(user.class_id(), ClassId::parse("c4b").as_ref());
+    }
+
+    #[tokio::test]
+    async fn class_group_coming_back_marks_alumni_as_students() {
+        let mut oauth_port = MockOAuthPort::new();
+        oauth_port
+            .expect_refresh_token()
+            .returning(|_| Box::pin(async { Ok(oauth_token()) }));
+        oauth_port.expect_get_user_info().returning(|_| {
+            Box::pin(async {
+                Ok(UserInfoDto {
+                    name: "Jan Novák".to_string(),
+                    email: "novak@ssps.cz".to_string(),
+                    groups: vec![UserGroup {
+                        id: "group".to_string(),
+                        name: "C4B".to_string(),
+                        mail: Some("c4b@ssps.cz".to_string()),
+                    }],
+                })
+            })
+        });
+        let mut class_repository = MockClassRepository::new();
+        class_repository.expect_find_all().returning(|| {
+            Ok(vec![create_class(
+                ClassId::parse("c4b").unwrap(),
+                "C4B".to_string(),
+                None,
+            )])
+        });
+        let handler = handler(class_repository, oauth_port);
+
+        let mut user = student("c4b", Some(2020));
+        handler.handle_authenticated_user(&mut user).await.unwrap();
+
+        assert!(!user.is_alumni());
+        assert_eq!(user.class_id(), ClassId::parse("c4b").as_ref());
+    }
+}
@@ -1,4 +1,15 @@
+use anyhow::anyhow;
+use application::role_sync_job_handler::RoleSyncConfig;
 use clap::Args;
+use domain::authentication::group_role_rule::GroupRoleRule;
+use domain::class::class_id::FieldOfStudy;
+use domain::nickname::NicknamePolicy;
+use domain_shared::discord::RoleId;
+use infrastructure::encryption::TokenCipher;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::{info, instrument};
 
 #[derive(Args)]
 pub struct CommonArgs {
@@ -13,3 +24,163 @@ pub struct CommonArgs {
     #[arg(long, env = "SENTRY_TRACES_SAMPLE_RATE")]
     pub sentry_traces_sample_rate: Option<f32>,
 }
+
+#[derive(Args)]
+pub struct TokenEncryptionArgs {
+    /// Identifier of the key new OAuth tokens are encrypted with
+    #[arg(long, env = "TOKEN_ENCRYPTION_KEY_ID")]
+    pub token_encryption_key_id: String,
+    /// JSON object of base64 encoded 256-bit keys indexed by their identifiers, keep the
+    /// previous keys here until the tokens are re-encrypted
+    #[arg(long, env = "TOKEN_ENCRYPTION_KEYS")]
+    pub token_encryption_keys: String,
+}
+
+impl TokenEncryptionArgs {
+    #[instrument(level = "trace", skip(self))]
+    pub fn token_cipher(self) -> anyhow::Result<TokenCipher> {
+        let TokenEncryptionArgs {
+            token_encryption_key_id,
+            token_encryption_keys,
+        } = self;
+        let keys: HashMap<String, String> = serde_json::from_str(&token_encryption_keys)?;
+        Ok(TokenCipher::new(token_encryption_key_id, keys)?)
+    }
+}
+
+#[derive(Args)]
+pub struct RoleSyncArgs {
+    #[arg(long, env = "EVERYONE_ROLES")]
+    pub everyone_roles: String,
+    #[arg(long, env = "ADDITIONAL_STUDENT_ROLES")]
+    pub additional_student_roles: String,
+    #[arg(long, env = "UNKNOWN_CLASS_ROLE_ID")]
+    pub unknown_class_role_id: u64,
+    /// JSON list of role IDs assigned to verified staff members (teachers, school employees)
+    #[arg(long, env = "STAFF_ROLES", default_value = "[]")]
+    pub staff_roles: String,
+    /// JSON map of the year to the role ID assigned to the students of the year, e.g. `{"1":123}`
+    #[arg(long, env = "YEAR_ROLES", default_value = "{}")]
+    pub year_roles: String,
+    /// JSON map of the field of study prefix to the role ID assigned to its students, `none` for the
+    /// classes without a prefix, e.g. `{"c":123,"none":456}`
+    #[arg(long, env = "FIELD_ROLES", default_value = "{}")]
+    pub field_roles: String,
+    /// JSON file with the rules assigning roles to the members of Azure groups other than the class
+    /// groups, e.g. `[{"group":"robotics@school.cz","role_id":123}]`, the group is its ID or mail
+    #[arg(long, env = "GROUP_ROLE_RULES_FILE")]
+    pub group_role_rules_file: Option<PathBuf>,
+    /// The role assigned to alumni, i.e. students who graduated from a final-year class
+    #[arg(long, env = "ALUMNI_ROLE_ID")]
+    pub alumni_role_id: Option<u64>,
+    /// Nickname of the students with a known class using the `{name}` and `{class}` placeholders,
+    /// e.g. `{name} ({class})` for `Jan Novák (3B)`, the nicknames are not changed when unset
+    #[arg(long, env = "NICKNAME_FORMAT")]
+    pub nickname_format: Option<String>,
+    /// Nickname of the staff, the alumni and the students with an unknown class
+    #[arg(long, env = "NICKNAME_FORMAT_WITHOUT_CLASS", default_value = "{name}")]
+    pub nickname_format_without_class: String,
+}
+
+impl RoleSyncArgs {
+    #[instrument(level = "trace", skip(self))]
+    pub fn role_sync_config(self) -> anyhow::Result<RoleSyncConfig> {
+        let RoleSyncArgs {
+            everyone_roles,
+            additional_student_roles,
+            unknown_class_role_id,
+            staff_roles,
+            year_roles,
+            field_roles,
+            group_role_rules_file,
+            alumni_role_id,
+            nickname_format,
+            nickname_format_without_class,
+        } = self;
+
+        Ok(RoleSyncConfig {
+            everyone_roles: parse_role_ids(&everyone_roles)?,
+            additional_student_roles: parse_role_ids(&additional_student_roles)?,
+            staff_roles: parse_role_ids(&staff_roles)?,
+            alumni_role_id: alumni_role_id.map(RoleId),
+            unknown_class_role_id: RoleId(unknown_class_role_id),
+            year_roles: parse_year_roles(&year_roles)?,
+            field_roles: parse_field_roles(&field_roles)?,
+            group_role_rules: group_role_rules_file
+                .map(|path| load_group_role_rules(&path))
+                .transpose()?
+                .unwrap_or_default(),
+            nickname_policy: nickname_format.map(|format| NicknamePolicy {
+                format,
+                format_without_class: nickname_format_without_class,
+            }),
+        })
+    }
+}
+
+#[instrument(level = "trace")]
+fn parse_role_ids(role_ids: &str) -> anyhow::Result<Vec<RoleId>> {
+    Ok(serde_json::from_str::<Vec<u64>>(role_ids)?
+        .into_iter()
+        .map(RoleId)
+        .collect())
+}
+
+#[instrument(level = "trace")]
+fn parse_year_roles(year_roles: &str) -> anyhow::Result<Vec<(u8, RoleId)>> {
+    Ok(serde_json::from_str::<HashMap<u8, u64>>(year_roles)?
+        .into_iter()
+        .map(|(year, role_id)| (year, RoleId(role_id)))
+        .collect())
+}
+
+#[instrument(level = "trace")]
+fn parse_field_roles(field_roles: &str) -> anyhow::Result<Vec<(Option<FieldOfStudy>, RoleId)>> {
+    serde_json::from_str::<HashMap<String, u64>>(field_roles)?
+        .into_iter()
+        .map(|(field, role_id)| {
+            let field = match field.trim() {
+                "" | "none" => None,
+                prefix => {
+                    let mut chars = prefix.chars();
+                    let field = chars
+                        .next()
+                        .filter(|_| chars.next().is_none())
+                        .and_then(FieldOfStudy::from_prefix)
+                        .ok_or_else(|| {
+                            anyhow!("Invalid field of study {} in FIELD_ROLES", prefix)
+                        })?;
+                    Some(field)
+                }
+            };
+            Ok((field, RoleId(role_id)))
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct GroupRoleRuleConfig {
+    group: String,
+    role_id: u64,
+}
+
+#[instrument(level = "trace")]
+fn load_group_role_rules(path: &PathBuf) -> anyhow::Result<Vec<GroupRoleRule>> {
+    let rules = std::fs::read_to_string(path).map_err(|e| {
+        anyhow!(
+            "Failed to read the group role rules {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+    let rules = serde_json::from_str::<Vec<GroupRoleRuleConfig>>(&rules)?
+        .into_iter()
+        .map(|GroupRoleRuleConfig { group, role_id }| GroupRoleRule {
+            group,
+            role_id: RoleId(role_id),
+        })
+        .collect::<Vec<_>>();
+    info!(rules = rules.len(), "Loaded group role rules");
+
+    Ok(rules)
+}
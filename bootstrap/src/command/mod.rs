@@ -1,8 +1,12 @@
 pub mod migrate;
+pub mod reconcile_roles;
+pub mod reencrypt_tokens;
 pub mod serve;
 
 use crate::args::CommonArgs;
 use crate::command::migrate::MigrateArgs;
+use crate::command::reconcile_roles::ReconcileRolesArgs;
+use crate::command::reencrypt_tokens::ReencryptTokensArgs;
 use crate::command::serve::ServeArgs;
 use anyhow::anyhow;
 use clap::Subcommand;
@@ -14,6 +18,12 @@ pub enum Command {
     Serve(#[arg(flatten)] Box<ServeArgs>),
     #[command(name = "migrate")]
     Migrate(#[arg(flatten)] MigrateArgs),
+    /// Re-encrypts the OAuth tokens with the current key, which `serve` also does on startup
+    #[command(name = "reencrypt-tokens")]
+    ReencryptTokens(#[arg(flatten)] ReencryptTokensArgs),
+    /// Reports what the role sync would change for every guild member, without changing anything
+    #[command(name = "reconcile-roles")]
+    ReconcileRoles(#[arg(flatten)] Box<ReconcileRolesArgs>),
 }
 
 impl Command {
@@ -22,6 +32,8 @@ impl Command {
         match self {
             Command::Serve(args) => serve::run(common_args, *args).await.map_err(|e| anyhow!(e)),
             Command::Migrate(args) => migrate::run(common_args, args).await,
+            Command::ReencryptTokens(args) => reencrypt_tokens::run(common_args, args).await,
+            Command::ReconcileRoles(args) => reconcile_roles::run(common_args, *args).await,
         }
     }
 }
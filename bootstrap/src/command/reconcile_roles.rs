@@ -0,0 +1,105 @@
+use crate::args::{CommonArgs, RoleSyncArgs, TokenEncryptionArgs};
+use application::role_reconciliation::RoleReconciliationService;
+use application_ports::role_reconciliation::{RoleChangeCountDto, RoleReconciliationPort};
+use clap::Args;
+use infrastructure::authentication::authenticated_user::PostgresAuthenticatedUserRepository;
+use infrastructure::class::catalog::PostgresClassRepository;
+use infrastructure::discord::{DiscordAdapter, GuildRoleCache};
+use infrastructure::role_sync_exemption::PostgresRoleSyncExemptionRepository;
+use presentation::report::role_reconciliation_csv;
+use serenity::all::{GuildId, Http};
+use std::path::PathBuf;
+use tracing::{info, instrument};
+
+#[derive(Args)]
+pub struct ReconcileRolesArgs {
+    /// The token for the Discord bot
+    #[arg(long, env = "DISCORD_BOT_TOKEN")]
+    pub discord_bot_token: String,
+    /// The ID of the Discord guild (server) to reconcile the roles in
+    #[arg(long, env = "DISCORD_GUILD_ID")]
+    pub guild: u64,
+    /// File the per-member role diffs are written to as CSV, printed to stdout when unset
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub role_sync: RoleSyncArgs,
+    #[command(flatten)]
+    pub token_encryption: TokenEncryptionArgs,
+}
+
+#[instrument(level = "info", skip(common_args, args))]
+pub async fn run(common_args: CommonArgs, args: ReconcileRolesArgs) -> anyhow::Result<()> {
+    let CommonArgs {
+        database_url,
+        sentry_dsn: _,
+        sentry_environment: _,
+        sentry_sample_rate: _,
+        sentry_traces_sample_rate: _,
+    } = common_args;
+    let ReconcileRolesArgs {
+        discord_bot_token,
+        guild,
+        output,
+        role_sync,
+        token_encryption,
+    } = args;
+    let token_cipher = token_encryption.token_cipher()?;
+
+    info!("Running role reconciliation dry run, no roles are changed...");
+
+    let connection = sqlx::PgPool::connect(&database_url).await?;
+    let client = Http::new(&discord_bot_token);
+    let guild_role_cache = GuildRoleCache::default();
+    let mut role_reconciliation_service = RoleReconciliationService {
+        discord_port: DiscordAdapter::new(&client, GuildId::new(guild), &guild_role_cache),
+        authenticated_user_repository: PostgresAuthenticatedUserRepository::new(
+            &connection,
+            &token_cipher,
+        ),
+        class_repository: PostgresClassRepository::new(&connection),
+        role_sync_exemption_repository: PostgresRoleSyncExemptionRepository::new(&connection),
+        config: role_sync.role_sync_config()?,
+    };
+    let report = role_reconciliation_service
+        .reconcile_roles_dry_run()
+        .await?;
+
+    info!(
+        "Roles of {} out of {} members would change",
+        report.member_diffs.len(),
+        report.members,
+    );
+    for RoleChangeCountDto {
+        role_id,
+        role_name,
+        assigned,
+        removed,
+    } in &report.role_changes
+    {
+        info!(
+            "Role {} ({}) would be assigned to {} and removed from {} members",
+            role_name.as_deref().unwrap_or("unknown"),
+            role_id.0,
+            assigned,
+            removed,
+        );
+    }
+    if !report.classes_without_role.is_empty() {
+        info!(
+            "Classes without a role, created on the next role sync: {}",
+            report.classes_without_role.join(", "),
+        );
+    }
+
+    let csv = role_reconciliation_csv(&report);
+    match output {
+        Some(output) => {
+            std::fs::write(&output, csv)?;
+            info!("Per-member role diffs written to {}", output.display());
+        }
+        None => print!("{}", csv),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::args::{CommonArgs, TokenEncryptionArgs};
+use clap::Args;
+use infrastructure::authentication::token_reencryption::reencrypt_oauth_tokens;
+use tracing::{info, instrument};
+
+#[derive(Args)]
+pub struct ReencryptTokensArgs {
+    #[command(flatten)]
+    pub token_encryption: TokenEncryptionArgs,
+}
+
+#[instrument(level = "info", skip(common_args, args))]
+pub async fn run(common_args: CommonArgs, args: ReencryptTokensArgs) -> anyhow::Result<()> {
+    let CommonArgs {
+        database_url,
+        sentry_dsn: _,
+        sentry_environment: _,
+        sentry_sample_rate: _,
+        sentry_traces_sample_rate: _,
+    } = common_args;
+    let ReencryptTokensArgs { token_encryption } = args;
+    let token_cipher = token_encryption.token_cipher()?;
+
+    info!(
+        "Re-encrypting OAuth tokens with key {}...",
+        token_cipher.current_key_id()
+    );
+
+    let connection = sqlx::PgPool::connect(&database_url).await?;
+    let summary = reencrypt_oauth_tokens(&connection, &token_cipher).await?;
+
+    info!(
+        "Re-encrypted the tokens of {} authenticated and {} archived users",
+        summary.authenticated_users, summary.archived_authenticated_users,
+    );
+    Ok(())
+}
@@ -1,19 +1,30 @@
 use crate::locator;
 use anyhow::anyhow;
-use clap::Args;
-use domain_shared::discord::{InviteLink, RoleId};
+use application::retention_handler::RetentionConfig;
+use clap::{Args, ValueEnum};
+use domain::class::graduation::GraduationDate;
+use domain_shared::discord::{ChannelId, InviteLink};
+use infrastructure::authentication::token_reencryption::reencrypt_oauth_tokens;
+use infrastructure::discord::GuildRoleCache;
 use infrastructure::oauth::{OAuthAdapterConfig, TenantId};
-use oauth2::{ClientId, ClientSecret};
+use infrastructure::oauth_provider::OAuthProviderConfig;
+use infrastructure::oidc::{OidcAdapterConfig, OidcClaimsConfig, OidcEndpoints, OidcProvider};
+use oauth2::{AuthUrl, ClientId, ClientSecret, RevocationUrl, TokenUrl};
+use openidconnect::{IssuerUrl, JsonWebKeySetUrl, UserInfoUrl};
 use presentation::api::run_api;
 use presentation::discord::run_bot;
 use serenity::all::{ClientBuilder, GuildId};
+use std::sync::Arc;
 use url::Url;
 
-use crate::args::CommonArgs;
+use crate::args::{CommonArgs, RoleSyncArgs, TokenEncryptionArgs};
 use poise::serenity_prelude as serenity;
 use presentation::worker::run_worker;
 use tracing::{info, instrument};
 
+/// Archived users are needed for the moderation, a hundred years is plenty
+const MAX_ARCHIVED_USER_RETENTION_DAYS: i64 = 36_500;
+
 #[derive(Args)]
 pub struct ServeArgs {
     #[arg(long, env = "AUTHENTICATION_CALLBACK_URL")]
@@ -30,18 +41,82 @@ pub struct ServeArgs {
     /// The client secret for the OAuth2 application
     #[arg(long, env = "OAUTH_CLIENT_SECRET")]
     pub oauth_client_secret: String,
-    /// The tenant ID for the Azure AD application
+    /// The identity provider the users authenticate against
+    #[arg(long, env = "OAUTH_PROVIDER", value_enum, default_value_t = OAuthProviderKind::Azure)]
+    pub oauth_provider: OAuthProviderKind,
+    /// The tenant ID for the Azure AD application, required for the Azure provider
     #[arg(long, env = "TENANT_ID")]
-    pub tenant_id: String,
+    pub tenant_id: Option<String>,
+    /// Token revocation endpoint of the OAuth provider, tokens are only discarded when unset
+    #[arg(long, env = "OAUTH_REVOCATION_URL")]
+    pub oauth_revocation_url: Option<String>,
     /// The invite link for the Discord server
     #[arg(long, env = "INVITE_LINK")]
     pub invite_link: String,
-    #[arg(long, env = "EVERYONE_ROLES")]
-    pub everyone_roles: String,
-    #[arg(long, env = "ADDITIONAL_STUDENT_ROLES")]
-    pub additional_student_roles: String,
-    #[arg(long, env = "UNKNOWN_CLASS_ROLE_ID")]
-    pub unknown_class_role_id: u64,
+    /// The channel where notices for the moderators are posted, e.g. about replaced accounts
+    #[arg(long, env = "MODERATION_LOG_CHANNEL_ID")]
+    pub moderation_log_channel_id: Option<u64>,
+    /// JSON list of Azure AD group IDs or mails whose members are considered staff
+    #[arg(long, env = "STAFF_GROUPS", default_value = "[]")]
+    pub staff_groups: String,
+    #[command(flatten)]
+    pub role_sync: RoleSyncArgs,
+    /// The day of the year on which the final-year students graduate, in the `MM-DD` format
+    #[arg(long, env = "GRADUATION_DATE", default_value = "06-30")]
+    pub graduation_date: String,
+    /// How long an authentication link stays valid, in minutes
+    #[arg(long, env = "AUTHENTICATION_REQUEST_TTL_MINUTES", default_value_t = 30)]
+    pub authentication_request_ttl_minutes: i64,
+    /// How long archived users are kept, in days from 1 to 36500, they are kept forever when unset
+    #[arg(long, env = "ARCHIVED_USER_RETENTION_DAYS")]
+    pub archived_user_retention_days: Option<i64>,
+    /// Only logs what the retention policy would change in the archived users without changing it
+    #[arg(long, env = "RETENTION_DRY_RUN", default_value_t = false)]
+    pub retention_dry_run: bool,
+    #[command(flatten)]
+    pub oidc: OidcArgs,
+    #[command(flatten)]
+    pub token_encryption: TokenEncryptionArgs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OAuthProviderKind {
+    /// Azure AD with the user info read from Microsoft Graph
+    Azure,
+    /// Any OpenID Connect provider
+    Oidc,
+}
+
+#[derive(Args)]
+pub struct OidcArgs {
+    /// The issuer URL of the OpenID Connect provider, required for the OIDC provider
+    #[arg(long, env = "OIDC_ISSUER_URL")]
+    pub oidc_issuer_url: Option<String>,
+    /// The authorization endpoint, the endpoints are discovered from the issuer when unset
+    #[arg(long, env = "OIDC_AUTHORIZATION_URL")]
+    pub oidc_authorization_url: Option<String>,
+    #[arg(long, env = "OIDC_TOKEN_URL")]
+    pub oidc_token_url: Option<String>,
+    #[arg(long, env = "OIDC_USERINFO_URL")]
+    pub oidc_userinfo_url: Option<String>,
+    #[arg(long, env = "OIDC_JWKS_URL")]
+    pub oidc_jwks_url: Option<String>,
+    /// JSON list of scopes requested in addition to `openid`
+    #[arg(
+        long,
+        env = "OIDC_SCOPES",
+        default_value = r#"["profile","email","offline_access"]"#
+    )]
+    pub oidc_scopes: String,
+    /// The claim containing the full name of the user
+    #[arg(long, env = "OIDC_NAME_CLAIM", default_value = "name")]
+    pub oidc_name_claim: String,
+    /// The claim containing the email of the user
+    #[arg(long, env = "OIDC_EMAIL_CLAIM", default_value = "email")]
+    pub oidc_email_claim: String,
+    /// The claim containing the groups of the user
+    #[arg(long, env = "OIDC_GROUPS_CLAIM", default_value = "groups")]
+    pub oidc_groups_claim: String,
 }
 
 #[instrument(level = "trace", skip(common_args, args))]
@@ -55,41 +130,79 @@ pub async fn run(common_args: CommonArgs, args: ServeArgs) -> anyhow::Result<()>
     } = common_args;
     let ServeArgs {
         authentication_callback_url,
+        oauth_provider,
         discord_bot_token,
         guild,
         oauth_client_id,
         oauth_client_secret,
         tenant_id,
+        oauth_revocation_url,
         invite_link,
-        everyone_roles,
-        additional_student_roles,
-        unknown_class_role_id,
+        moderation_log_channel_id,
+        staff_groups,
+        role_sync,
+        graduation_date,
+        authentication_request_ttl_minutes,
+        archived_user_retention_days,
+        retention_dry_run,
+        oidc,
+        token_encryption,
     } = args;
     let guild = GuildId::new(guild);
     let authentication_callback_url = Url::parse(&authentication_callback_url)?;
     let oauth_client_id = ClientId::new(oauth_client_id);
     let oauth_client_secret = ClientSecret::new(oauth_client_secret);
-    let tenant_id = TenantId(tenant_id);
+    let oauth_revocation_url = oauth_revocation_url.map(RevocationUrl::new).transpose()?;
     let invite_link = InviteLink(invite_link);
-    let everyone_roles: Vec<RoleId> = serde_json::from_str::<Vec<u64>>(&everyone_roles)?
-        .into_iter()
-        .map(RoleId)
-        .collect();
-    let additional_student_roles: Vec<RoleId> =
-        serde_json::from_str::<Vec<u64>>(&additional_student_roles)?
-            .into_iter()
-            .map(RoleId)
-            .collect();
-    let unknown_class_role_id = RoleId(unknown_class_role_id);
-
-    let oauth_adapter_config = OAuthAdapterConfig {
-        client_id: oauth_client_id,
-        client_secret: oauth_client_secret,
-        tenant_id,
-        authentication_callback_url,
+    let staff_groups: Vec<String> = serde_json::from_str(&staff_groups)?;
+    let role_sync_config = role_sync.role_sync_config()?;
+    let moderation_log_channel_id = moderation_log_channel_id.map(ChannelId);
+    let graduation_date = GraduationDate::parse(&graduation_date)
+        .ok_or_else(|| anyhow!("Invalid graduation date, expected the MM-DD format"))?;
+    let authentication_request_ttl = chrono::Duration::minutes(authentication_request_ttl_minutes);
+    let archived_user_retention = archived_user_retention_days
+        .map(|days| {
+            if !(1..=MAX_ARCHIVED_USER_RETENTION_DAYS).contains(&days) {
+                return Err(anyhow!(
+                    "Invalid archived user retention, expected 1 to {} days",
+                    MAX_ARCHIVED_USER_RETENTION_DAYS,
+                ));
+            }
+            Ok(chrono::Duration::days(days))
+        })
+        .transpose()?;
+    let retention_config = RetentionConfig {
+        archived_user_retention,
+        dry_run: retention_dry_run,
+    };
+
+    let oauth_provider = match oauth_provider {
+        OAuthProviderKind::Azure => {
+            let tenant_id =
+                tenant_id.ok_or_else(|| anyhow!("TENANT_ID is required for the Azure provider"))?;
+            OAuthProviderConfig::Azure(OAuthAdapterConfig {
+                client_id: oauth_client_id,
+                client_secret: oauth_client_secret,
+                tenant_id: TenantId(tenant_id),
+                authentication_callback_url,
+                revocation_url: oauth_revocation_url,
+            })
+        }
+        OAuthProviderKind::Oidc => {
+            let oidc_adapter_config = create_oidc_adapter_config(
+                oidc,
+                oauth_client_id,
+                oauth_client_secret,
+                authentication_callback_url,
+                oauth_revocation_url,
+            )?;
+            OAuthProviderConfig::Oidc(OidcProvider::discover(oidc_adapter_config).await?)
+        }
     };
 
-    let intents = serenity::GatewayIntents::non_privileged();
+    // The members intent is privileged, it must be enabled in the Discord developer portal
+    let intents =
+        serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::GUILD_MEMBERS;
 
     let database_connection = sqlx::PgPool::connect(&database_url).await?;
     let serenity_client = ClientBuilder::new(&discord_bot_token, intents).await?.http;
@@ -97,16 +210,36 @@ pub async fn run(common_args: CommonArgs, args: ServeArgs) -> anyhow::Result<()>
     let (role_sync_job_wake_tx, role_sync_job_wake_rx) = tokio::sync::mpsc::channel(24);
     let (user_info_sync_job_wake_tx, user_info_sync_job_wake_rx) = tokio::sync::mpsc::channel(24);
 
+    let token_cipher = token_encryption.token_cipher()?;
+    // Tokens stored in plain text or with a previous key would not be readable by the bot
+    let reencryption_summary = reencrypt_oauth_tokens(&database_connection, &token_cipher)
+        .await
+        .map_err(|err| {
+            anyhow!(
+                "Failed to re-encrypt the OAuth tokens, not starting: {}",
+                err
+            )
+        })?;
+    info!(
+        "Re-encrypted the tokens of {} authenticated and {} archived users",
+        reencryption_summary.authenticated_users, reencryption_summary.archived_authenticated_users,
+    );
+
     let locator = locator::ApplicationPortLocator {
-        everyone_roles: everyone_roles.clone(),
-        additional_student_roles: additional_student_roles.clone(),
-        unknown_class_role_id,
+        staff_groups,
+        role_sync_config,
+        graduation_date,
+        authentication_request_ttl,
+        retention_config,
         invite_link: invite_link.clone(),
+        moderation_log_channel_id,
         guild_id: guild,
-        oauth_adapter_config,
+        oauth_provider,
 
+        token_cipher,
         postgres_pool: database_connection,
         serenity_client: serenity_client.clone(),
+        guild_role_cache: Arc::new(GuildRoleCache::default()),
 
         role_sync_job_wake_tx,
         user_info_sync_job_wake_tx,
@@ -128,3 +261,63 @@ pub async fn run(common_args: CommonArgs, args: ServeArgs) -> anyhow::Result<()>
 
     Ok(())
 }
+
+#[instrument(level = "trace", skip_all)]
+fn create_oidc_adapter_config(
+    args: OidcArgs,
+    client_id: ClientId,
+    client_secret: ClientSecret,
+    authentication_callback_url: Url,
+    revocation_url: Option<RevocationUrl>,
+) -> anyhow::Result<OidcAdapterConfig> {
+    let OidcArgs {
+        oidc_issuer_url,
+        oidc_authorization_url,
+        oidc_token_url,
+        oidc_userinfo_url,
+        oidc_jwks_url,
+        oidc_scopes,
+        oidc_name_claim,
+        oidc_email_claim,
+        oidc_groups_claim,
+    } = args;
+
+    let issuer_url = oidc_issuer_url
+        .ok_or_else(|| anyhow!("OIDC_ISSUER_URL is required for the OIDC provider"))?;
+    let endpoints = match (
+        oidc_authorization_url,
+        oidc_token_url,
+        oidc_userinfo_url,
+        oidc_jwks_url,
+    ) {
+        (Some(auth_url), Some(token_url), Some(user_info_url), Some(jwks_url)) => {
+            Some(OidcEndpoints {
+                auth_url: AuthUrl::new(auth_url)?,
+                token_url: TokenUrl::new(token_url)?,
+                user_info_url: UserInfoUrl::new(user_info_url)?,
+                jwks_url: JsonWebKeySetUrl::new(jwks_url)?,
+            })
+        }
+        (None, None, None, None) => None,
+        _ => {
+            return Err(anyhow!(
+                "Either all or none of the OIDC endpoints must be configured"
+            ));
+        }
+    };
+
+    Ok(OidcAdapterConfig {
+        client_id,
+        client_secret,
+        issuer_url: IssuerUrl::new(issuer_url)?,
+        authentication_callback_url,
+        endpoints,
+        scopes: serde_json::from_str(&oidc_scopes)?,
+        claims: OidcClaimsConfig {
+            name: oidc_name_claim,
+            email: oidc_email_claim,
+            groups: oidc_groups_claim,
+        },
+        revocation_url,
+    })
+}
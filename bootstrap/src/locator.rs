@@ -1,30 +1,58 @@
 use application::authentication::AuthenticationService;
+use application::class::ClassService;
+use application::class_rollover::ClassRolloverService;
 use application::information_channel::InformationChannelService;
+use application::nickname::NicknameService;
 use application::periodic_scheduling_handler::PeriodicSchedulingHandler;
-use application::role_sync_job_handler::RoleSyncJobHandler;
+use application::personal_data::PersonalDataService;
+use application::retention_handler::{RetentionConfig, RetentionHandler};
+use application::role_log::RoleLogService;
+use application::role_reconciliation::RoleReconciliationService;
+use application::role_sync_exemption::RoleSyncExemptionService;
+use application::role_sync_job_handler::{RoleSyncConfig, RoleSyncJobHandler};
 use application::user::UserService;
-use application::user_info_sync_job_handler::UserInfoSyncJobHandler;
+use application::user_info_sync_job_handler::{UserInfoSyncConfig, UserInfoSyncJobHandler};
 use application_ports::authentication::AuthenticationPort;
+use application_ports::class::ClassPort;
+use application_ports::class_rollover::ClassRolloverPort;
 use application_ports::information_channel::InformationChannelPort;
+use application_ports::nickname::NicknamePort;
 use application_ports::periodic_scheduling_handler::PeriodicSchedulingHandlerPort;
+use application_ports::personal_data::PersonalDataPort;
+use application_ports::retention_handler::RetentionHandlerPort;
+use application_ports::role_log::RoleLogPort;
+use application_ports::role_reconciliation::RoleReconciliationPort;
+use application_ports::role_sync_exemption::RoleSyncExemptionPort;
 use application_ports::role_sync_job_handler::RoleSyncJobHandlerPort;
 use application_ports::user::UserPort;
 use application_ports::user_info_sync_job_handler::UserInfoSyncJobHandlerPort;
 use domain::authentication::archived_authenticated_user::ArchivedAuthenticatedUserRepository;
 use domain::authentication::authenticated_user::AuthenticatedUserRepository;
 use domain::authentication::user_authentication_request::UserAuthenticationRequestRepository;
+use domain::class::catalog::ClassRepository;
+use domain::class::graduation::GraduationDate;
+use domain::class::rollover::ClassRolloverRepository;
 use domain::jobs::role_sync_job::RoleSyncRequestedRepository;
 use domain::jobs::user_info_sync_job::UserInfoSyncRequestedRepository;
+use domain::nickname::NicknameSettingsRepository;
 use domain::ports::discord::DiscordPort;
 use domain::ports::oauth::OAuthPort;
-use domain_shared::discord::{InviteLink, RoleId};
+use domain::role_change_log::RoleChangeLogRepository;
+use domain::role_sync_exemption::RoleSyncExemptionRepository;
+use domain_shared::discord::{ChannelId, InviteLink};
 use infrastructure::authentication::archived_authenticated_user::PostgresArchivedAuthenticatedUserRepository;
 use infrastructure::authentication::authenticated_user::PostgresAuthenticatedUserRepository;
 use infrastructure::authentication::user_authentication_request::PostgresUserAuthenticationRequestRepository;
-use infrastructure::discord::DiscordAdapter;
+use infrastructure::class::catalog::PostgresClassRepository;
+use infrastructure::class::rollover::PostgresClassRolloverRepository;
+use infrastructure::discord::{DiscordAdapter, GuildRoleCache};
+use infrastructure::encryption::TokenCipher;
 use infrastructure::jobs::role_sync_job_repository::PostgresRoleSyncRequestedRepository;
 use infrastructure::jobs::user_info_sync_job_repository::PostgresUserInfoSyncRequestedRepository;
-use infrastructure::oauth::{OAuthAdapter, OAuthAdapterConfig};
+use infrastructure::nickname::PostgresNicknameSettingsRepository;
+use infrastructure::oauth_provider::{OAuthProviderAdapter, OAuthProviderConfig};
+use infrastructure::role_change_log::PostgresRoleChangeLogRepository;
+use infrastructure::role_sync_exemption::PostgresRoleSyncExemptionRepository;
 use presentation::application_ports::{Locator, LocatorScope};
 use serenity::all::GuildId;
 use std::sync::Arc;
@@ -32,15 +60,20 @@ use tracing::instrument;
 
 #[derive(Clone)]
 pub struct ApplicationPortLocator {
-    pub(crate) everyone_roles: Vec<RoleId>,
-    pub(crate) additional_student_roles: Vec<RoleId>,
-    pub(crate) unknown_class_role_id: RoleId,
+    pub(crate) staff_groups: Vec<String>,
+    pub(crate) role_sync_config: RoleSyncConfig,
+    pub(crate) graduation_date: GraduationDate,
+    pub(crate) authentication_request_ttl: chrono::Duration,
+    pub(crate) retention_config: RetentionConfig,
     pub(crate) invite_link: InviteLink,
+    pub(crate) moderation_log_channel_id: Option<ChannelId>,
     pub(crate) guild_id: GuildId,
-    pub(crate) oauth_adapter_config: OAuthAdapterConfig,
+    pub(crate) oauth_provider: OAuthProviderConfig,
+    pub(crate) token_cipher: TokenCipher,
 
     pub(crate) postgres_pool: sqlx::PgPool,
     pub(crate) serenity_client: Arc<serenity::http::Http>,
+    pub(crate) guild_role_cache: Arc<GuildRoleCache>,
 
     pub(crate) role_sync_job_wake_tx: tokio::sync::mpsc::Sender<()>,
     pub(crate) user_info_sync_job_wake_tx: tokio::sync::mpsc::Sender<()>,
@@ -51,14 +84,24 @@ impl ApplicationPortLocator {
     fn authenticated_user_repository(
         &self,
     ) -> impl AuthenticatedUserRepository + Send + Sync + use<'_> {
-        PostgresAuthenticatedUserRepository::new(&self.postgres_pool)
+        PostgresAuthenticatedUserRepository::new(&self.postgres_pool, &self.token_cipher)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn class_repository(&self) -> impl ClassRepository + Send + Sync + use<'_> {
+        PostgresClassRepository::new(&self.postgres_pool)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn class_rollover_repository(&self) -> impl ClassRolloverRepository + Send + Sync + use<'_> {
+        PostgresClassRolloverRepository::new(&self.postgres_pool)
     }
 
     #[instrument(level = "trace", skip(self))]
     fn archived_authenticated_user_repository(
         &self,
     ) -> impl ArchivedAuthenticatedUserRepository + Send + Sync + use<'_> {
-        PostgresArchivedAuthenticatedUserRepository::new(&self.postgres_pool)
+        PostgresArchivedAuthenticatedUserRepository::new(&self.postgres_pool, &self.token_cipher)
     }
 
     #[instrument(level = "trace", skip(self))]
@@ -68,6 +111,25 @@ impl ApplicationPortLocator {
         PostgresRoleSyncRequestedRepository::new(&self.postgres_pool, &self.role_sync_job_wake_tx)
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn nickname_settings_repository(
+        &self,
+    ) -> impl NicknameSettingsRepository + Send + Sync + use<'_> {
+        PostgresNicknameSettingsRepository::new(&self.postgres_pool)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn role_change_log_repository(&self) -> impl RoleChangeLogRepository + Send + Sync + use<'_> {
+        PostgresRoleChangeLogRepository::new(&self.postgres_pool)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn role_sync_exemption_repository(
+        &self,
+    ) -> impl RoleSyncExemptionRepository + Send + Sync + use<'_> {
+        PostgresRoleSyncExemptionRepository::new(&self.postgres_pool)
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn user_authentication_request_repository(
         &self,
@@ -87,12 +149,12 @@ impl ApplicationPortLocator {
 
     #[instrument(level = "trace", skip(self))]
     fn discord_adapter(&self) -> impl DiscordPort + Send + Sync + use<'_> {
-        DiscordAdapter::new(&self.serenity_client, self.guild_id)
+        DiscordAdapter::new(&self.serenity_client, self.guild_id, &self.guild_role_cache)
     }
 
     #[instrument(level = "trace", skip(self))]
     fn oauth_adapter(&self) -> impl OAuthPort + Send + Sync {
-        OAuthAdapter::new(&self.oauth_adapter_config)
+        OAuthProviderAdapter::new(&self.oauth_provider)
     }
 }
 
@@ -109,11 +171,15 @@ impl LocatorScope for ApplicationPortLocatorScope<'_> {
                 .locator
                 .archived_authenticated_user_repository(),
             authenticated_user_repository: self.locator.authenticated_user_repository(),
+            class_repository: self.locator.class_repository(),
             user_authentication_request_repository: self
                 .locator
                 .user_authentication_request_repository(),
             user_info_sync_requested_repository: self.locator.user_info_sync_requested_repository(),
             role_sync_requested_repository: self.locator.role_sync_requested_repository(),
+            staff_groups: self.locator.staff_groups.clone(),
+            group_role_rules: self.locator.role_sync_config.group_role_rules.clone(),
+            authentication_request_ttl: self.locator.authentication_request_ttl,
         }
     }
 }
@@ -125,9 +191,13 @@ impl Locator for ApplicationPortLocator {
             oauth_port: self.oauth_adapter(),
             archived_authenticated_user_repository: self.archived_authenticated_user_repository(),
             authenticated_user_repository: self.authenticated_user_repository(),
+            class_repository: self.class_repository(),
             user_authentication_request_repository: self.user_authentication_request_repository(),
             user_info_sync_requested_repository: self.user_info_sync_requested_repository(),
             role_sync_requested_repository: self.role_sync_requested_repository(),
+            staff_groups: self.staff_groups.clone(),
+            group_role_rules: self.role_sync_config.group_role_rules.clone(),
+            authentication_request_ttl: self.authentication_request_ttl,
         }
     }
 
@@ -142,26 +212,43 @@ impl Locator for ApplicationPortLocator {
     }
 
     #[instrument(level = "trace", skip(self))]
-    fn create_role_sync_job_handler_port(&self) -> impl RoleSyncJobHandlerPort + Send + Sync {
-        RoleSyncJobHandler::new(
-            self.discord_adapter(),
-            self.authenticated_user_repository(),
-            self.role_sync_requested_repository(),
-            self.everyone_roles.clone(),
-            self.additional_student_roles.clone(),
-            self.unknown_class_role_id,
+    fn create_retention_handler_port(&self) -> impl RetentionHandlerPort + Send + Sync {
+        RetentionHandler::new(
+            self.archived_authenticated_user_repository(),
+            self.user_authentication_request_repository(),
+            self.retention_config.clone(),
         )
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn create_role_sync_job_handler_port(&self) -> impl RoleSyncJobHandlerPort + Send + Sync {
+        RoleSyncJobHandler {
+            discord_port: self.discord_adapter(),
+            authenticated_user_repository: self.authenticated_user_repository(),
+            class_repository: self.class_repository(),
+            role_sync_requested_repository: self.role_sync_requested_repository(),
+            role_change_log_repository: self.role_change_log_repository(),
+            nickname_settings_repository: self.nickname_settings_repository(),
+            role_sync_exemption_repository: self.role_sync_exemption_repository(),
+            config: self.role_sync_config.clone(),
+        }
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn create_user_info_sync_job_handler_port(
         &self,
     ) -> impl UserInfoSyncJobHandlerPort + Send + Sync {
         UserInfoSyncJobHandler::new(
             self.authenticated_user_repository(),
+            self.class_repository(),
             self.role_sync_requested_repository(),
             self.user_info_sync_requested_repository(),
             self.oauth_adapter(),
+            UserInfoSyncConfig {
+                staff_groups: self.staff_groups.clone(),
+                group_role_rules: self.role_sync_config.group_role_rules.clone(),
+                graduation_date: self.graduation_date,
+            },
         )
     }
 
@@ -174,11 +261,81 @@ impl Locator for ApplicationPortLocator {
     fn create_user_port(&self) -> impl UserPort + Send + Sync {
         UserService::new(
             self.authenticated_user_repository(),
+            self.archived_authenticated_user_repository(),
             self.role_sync_requested_repository(),
             self.user_info_sync_requested_repository(),
         )
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn create_class_port(&self) -> impl ClassPort + Send + Sync {
+        ClassService {
+            class_repository: self.class_repository(),
+            discord_port: self.discord_adapter(),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn create_class_rollover_port(&self) -> impl ClassRolloverPort + Send + Sync {
+        ClassRolloverService {
+            authenticated_user_repository: self.authenticated_user_repository(),
+            class_repository: self.class_repository(),
+            class_rollover_repository: self.class_rollover_repository(),
+            user_info_sync_requested_repository: self.user_info_sync_requested_repository(),
+            discord_port: self.discord_adapter(),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn create_personal_data_port(&self) -> impl PersonalDataPort + Send + Sync {
+        PersonalDataService {
+            oauth_port: self.oauth_adapter(),
+            discord_port: self.discord_adapter(),
+            archived_authenticated_user_repository: self.archived_authenticated_user_repository(),
+            authenticated_user_repository: self.authenticated_user_repository(),
+            user_authentication_request_repository: self.user_authentication_request_repository(),
+            nickname_settings_repository: self.nickname_settings_repository(),
+            role_change_log_repository: self.role_change_log_repository(),
+            role_sync_exemption_repository: self.role_sync_exemption_repository(),
+            role_sync_requested_repository: self.role_sync_requested_repository(),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn create_role_reconciliation_port(&self) -> impl RoleReconciliationPort + Send + Sync {
+        RoleReconciliationService {
+            discord_port: self.discord_adapter(),
+            authenticated_user_repository: self.authenticated_user_repository(),
+            class_repository: self.class_repository(),
+            role_sync_exemption_repository: self.role_sync_exemption_repository(),
+            config: self.role_sync_config.clone(),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn create_role_log_port(&self) -> impl RoleLogPort + Send + Sync {
+        RoleLogService {
+            role_change_log_repository: self.role_change_log_repository(),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn create_nickname_port(&self) -> impl NicknamePort + Send + Sync {
+        NicknameService {
+            nickname_settings_repository: self.nickname_settings_repository(),
+            role_sync_requested_repository: self.role_sync_requested_repository(),
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn create_role_sync_exemption_port(&self) -> impl RoleSyncExemptionPort + Send + Sync {
+        RoleSyncExemptionService {
+            discord_port: self.discord_adapter(),
+            role_sync_exemption_repository: self.role_sync_exemption_repository(),
+            role_sync_requested_repository: self.role_sync_requested_repository(),
+        }
+    }
+
     #[instrument(level = "trace", skip(self))]
     async fn create_scope(&self) -> impl LocatorScope + Send + Sync {
         ApplicationPortLocatorScope { locator: self }
@@ -189,8 +346,18 @@ impl Locator for ApplicationPortLocator {
         &self.invite_link
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn get_moderation_log_channel_id(&self) -> Option<ChannelId> {
+        self.moderation_log_channel_id
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn get_discord_client(&self) -> &serenity::http::Http {
         self.serenity_client.as_ref()
     }
+
+    #[instrument(level = "debug", skip(self))]
+    fn invalidate_guild_role_cache(&self) {
+        self.guild_role_cache.invalidate();
+    }
 }
@@ -20,6 +20,16 @@ impl_sensitive_tuple_debug!(AuthenticationLink);
 pub struct CsrfToken(pub String);
 impl_sensitive_tuple_debug!(CsrfToken);
 
+/// PKCE code verifier, proving the token exchange is made by the client which started the flow
+#[derive(Clone, PartialEq)]
+pub struct PkceVerifier(pub String);
+impl_sensitive_tuple_debug!(PkceVerifier);
+
+/// Random value bound to the authentication request, echoed back in the ID token
+#[derive(Clone, PartialEq)]
+pub struct Nonce(pub String);
+impl_sensitive_tuple_debug!(Nonce);
+
 #[derive(Clone, PartialEq)]
 pub struct ClientCallbackToken(pub String);
 impl_sensitive_tuple_debug!(ClientCallbackToken);
@@ -40,5 +50,11 @@ pub struct UserGroup {
     pub mail: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserKind {
+    Student,
+    Staff,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ArchivedUserId(pub UserId, pub DateTime<Utc>);
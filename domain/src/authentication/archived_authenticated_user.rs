@@ -1,7 +1,7 @@
 use crate::authentication::authenticated_user::AuthenticatedUser;
 use crate::ports::oauth::OAuthToken;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SubsecRound, Utc};
 use domain_shared::authentication::ArchivedUserId;
 use domain_shared::discord::UserId;
 use thiserror::Error;
@@ -11,7 +11,7 @@ pub struct ArchivedAuthenticatedUser {
     archived_user_id: ArchivedUserId,
     name: String,
     email: String,
-    oauth_token: OAuthToken,
+    oauth_token: Option<OAuthToken>,
     class_id: Option<String>,
     authenticated_at: DateTime<Utc>,
 }
@@ -43,8 +43,8 @@ impl ArchivedAuthenticatedUser {
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub fn oauth_token(&self) -> &OAuthToken {
-        &self.oauth_token
+    pub fn oauth_token(&self) -> Option<&OAuthToken> {
+        self.oauth_token.as_ref()
     }
 
     #[instrument(level = "trace", skip(self))]
@@ -63,12 +63,13 @@ pub fn create_archived_authenticated_user_from_user(
     user: &AuthenticatedUser,
 ) -> ArchivedAuthenticatedUser {
     let user_id = user.user_id();
-    let archived_at = Utc::now();
+    // The database keeps microseconds, the archived user is looked up by the time
+    let archived_at = Utc::now().trunc_subsecs(6);
 
     let archived_user_id = ArchivedUserId(user_id, archived_at);
     let name = user.name().to_string();
     let email = user.email().to_string();
-    let oauth_token = user.oauth_token().clone();
+    let oauth_token = user.oauth_token().cloned();
     let class_id = user.class_id().map(|c| c.to_string());
     let authenticated_at = user.authenticated_at();
 
@@ -82,6 +83,18 @@ pub fn create_archived_authenticated_user_from_user(
     }
 }
 
+/// Archives the user without the OAuth token, so that the token of an unlinked account
+/// is not kept even when the provider cannot revoke it.
+#[instrument(level = "trace")]
+pub fn create_archived_authenticated_user_without_token(
+    user: &AuthenticatedUser,
+) -> ArchivedAuthenticatedUser {
+    ArchivedAuthenticatedUser {
+        oauth_token: None,
+        ..create_archived_authenticated_user_from_user(user)
+    }
+}
+
 impl ArchivedAuthenticatedUser {
     #[instrument(level = "trace", skip(snapshot))]
     pub fn from_snapshot(snapshot: ArchivedAuthenticatedUserSnapshot) -> Self {
@@ -113,7 +126,7 @@ pub struct ArchivedAuthenticatedUserSnapshot {
     pub archived_user_id: ArchivedUserId,
     pub name: String,
     pub email: String,
-    pub oauth_token: OAuthToken,
+    pub oauth_token: Option<OAuthToken>,
     pub class_id: Option<String>,
     pub authenticated_at: DateTime<Utc>,
 }
@@ -125,6 +138,40 @@ pub trait ArchivedAuthenticatedUserRepository {
         &self,
         user: &ArchivedAuthenticatedUser,
     ) -> Result<(), ArchivedAuthenticatedUserRepositoryError>;
+
+    /// Finds all archived identities of the Discord user, the most recently archived first
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<ArchivedAuthenticatedUser>, ArchivedAuthenticatedUserRepositoryError>;
+
+    /// Finds all archived identities with the email, the most recently archived first
+    async fn find_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Vec<ArchivedAuthenticatedUser>, ArchivedAuthenticatedUserRepositoryError>;
+
+    async fn remove(
+        &self,
+        archived_user_id: ArchivedUserId,
+    ) -> Result<(), ArchivedAuthenticatedUserRepositoryError>;
+
+    async fn count_with_oauth_token(&self)
+    -> Result<u64, ArchivedAuthenticatedUserRepositoryError>;
+
+    /// Discards the OAuth tokens of all archived users, returning the number of scrubbed users
+    async fn remove_oauth_tokens(&self) -> Result<u64, ArchivedAuthenticatedUserRepositoryError>;
+
+    async fn count_archived_before(
+        &self,
+        archived_before: DateTime<Utc>,
+    ) -> Result<u64, ArchivedAuthenticatedUserRepositoryError>;
+
+    /// Removes users archived before `archived_before`, returning the number of removed users
+    async fn remove_archived_before(
+        &self,
+        archived_before: DateTime<Utc>,
+    ) -> Result<u64, ArchivedAuthenticatedUserRepositoryError>;
 }
 
 #[derive(Debug, Error)]
@@ -1,7 +1,9 @@
 use crate::authentication::user_authentication_request::UserAuthenticationRequest;
+use crate::class::class_id::ClassId;
 use crate::ports::oauth::OAuthToken;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use domain_shared::authentication::UserKind;
 use domain_shared::discord::UserId;
 use thiserror::Error;
 use tracing::instrument;
@@ -11,11 +13,25 @@ pub struct AuthenticatedUser {
     user_id: UserId,
     name: String,
     email: String,
-    oauth_token: OAuthToken,
-    class_id: Option<String>,
+    kind: UserKind,
+    oauth_token: Option<OAuthToken>,
+    manual_verification: Option<ManualVerification>,
+    class_id: Option<ClassId>,
+    /// Last time the class was read from the class group of the user
+    class_confirmed_at: Option<DateTime<Utc>>,
+    /// Groups referenced by the group role rules which the user is a member of
+    group_ids: Vec<String>,
+    graduation_year: Option<i32>,
     authenticated_at: DateTime<Utc>,
 }
 
+/// Verification done by an admin for a user who cannot finish the OAuth flow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManualVerification {
+    pub verified_by: UserId,
+    pub reason: String,
+}
+
 impl AuthenticatedUser {
     #[instrument(level = "trace", skip(self))]
     pub fn user_id(&self) -> UserId {
@@ -39,28 +55,85 @@ impl AuthenticatedUser {
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub fn oauth_token(&self) -> &OAuthToken {
-        &self.oauth_token
+    pub fn kind(&self) -> UserKind {
+        self.kind
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn update_kind(&mut self, kind: UserKind) {
+        self.kind = kind;
+    }
+
+    /// Manually verified users have no OAuth token
+    #[instrument(level = "trace", skip(self))]
+    pub fn oauth_token(&self) -> Option<&OAuthToken> {
+        self.oauth_token.as_ref()
     }
 
     #[instrument(level = "trace", skip(self, oauth_token))]
     pub fn update_oauth_token(&mut self, oauth_token: OAuthToken) {
-        self.oauth_token = oauth_token;
+        self.oauth_token = Some(oauth_token);
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn manual_verification(&self) -> Option<&ManualVerification> {
+        self.manual_verification.as_ref()
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub fn class_id(&self) -> Option<&str> {
-        self.class_id.as_deref()
+    pub fn is_manually_verified(&self) -> bool {
+        self.manual_verification.is_some()
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub fn update_class_id(&mut self, class_id: String) {
+    pub fn class_id(&self) -> Option<&ClassId> {
+        self.class_id.as_ref()
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn class_confirmed_at(&self) -> Option<DateTime<Utc>> {
+        self.class_confirmed_at
+    }
+
+    /// Sets the class read from the class group of the user, a user with a class group
+    /// is a student even if they were marked as an alumnus before
+    #[instrument(level = "trace", skip(self))]
+    pub fn update_class_id(&mut self, class_id: ClassId) {
         self.class_id = Some(class_id);
+        self.class_confirmed_at = Some(Utc::now());
+        self.graduation_year = None;
     }
 
     #[instrument(level = "trace", skip(self))]
     pub fn mark_class_unknown(&mut self) {
         self.class_id = None;
+        self.class_confirmed_at = None;
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn group_ids(&self) -> &[String] {
+        &self.group_ids
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn update_group_ids(&mut self, group_ids: Vec<String>) {
+        self.group_ids = group_ids;
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn graduation_year(&self) -> Option<i32> {
+        self.graduation_year
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn is_alumni(&self) -> bool {
+        self.graduation_year.is_some()
+    }
+
+    /// Marks the user as an alumnus, keeping their last class as the class they graduated from.
+    #[instrument(level = "trace", skip(self))]
+    pub fn graduate(&mut self, graduation_year: i32) {
+        self.graduation_year = Some(graduation_year);
     }
 
     #[instrument(level = "trace", skip(self))]
@@ -74,14 +147,43 @@ pub fn create_user_from_successful_authentication(
     request: &UserAuthenticationRequest,
     name: String,
     email: String,
+    kind: UserKind,
     oauth_token: OAuthToken,
 ) -> AuthenticatedUser {
     AuthenticatedUser {
         user_id: request.user_id(),
         name,
         email,
-        oauth_token,
+        kind,
+        oauth_token: Some(oauth_token),
+        manual_verification: None,
         class_id: None,
+        class_confirmed_at: None,
+        group_ids: Vec::new(),
+        graduation_year: None,
+        authenticated_at: Utc::now(),
+    }
+}
+
+#[instrument(level = "trace")]
+pub fn create_manually_verified_user(
+    user_id: UserId,
+    name: String,
+    email: String,
+    class_id: ClassId,
+    manual_verification: ManualVerification,
+) -> AuthenticatedUser {
+    AuthenticatedUser {
+        user_id,
+        name,
+        email,
+        kind: UserKind::Student,
+        oauth_token: None,
+        manual_verification: Some(manual_verification),
+        class_id: Some(class_id),
+        class_confirmed_at: Some(Utc::now()),
+        group_ids: Vec::new(),
+        graduation_year: None,
         authenticated_at: Utc::now(),
     }
 }
@@ -93,8 +195,13 @@ impl AuthenticatedUser {
             user_id: snapshot.user_id,
             name: snapshot.name,
             email: snapshot.email,
+            kind: snapshot.kind,
             oauth_token: snapshot.oauth_token,
+            manual_verification: snapshot.manual_verification,
             class_id: snapshot.class_id,
+            class_confirmed_at: snapshot.class_confirmed_at,
+            group_ids: snapshot.group_ids,
+            graduation_year: snapshot.graduation_year,
             authenticated_at: snapshot.authenticated_at,
         }
     }
@@ -105,8 +212,13 @@ impl AuthenticatedUser {
             user_id: self.user_id,
             name: self.name.clone(),
             email: self.email.clone(),
+            kind: self.kind,
             oauth_token: self.oauth_token.clone(),
+            manual_verification: self.manual_verification.clone(),
             class_id: self.class_id.clone(),
+            class_confirmed_at: self.class_confirmed_at,
+            group_ids: self.group_ids.clone(),
+            graduation_year: self.graduation_year,
             authenticated_at: self.authenticated_at,
         }
     }
@@ -117,8 +229,13 @@ pub struct AuthenticatedUserSnapshot {
     pub user_id: UserId,
     pub name: String,
     pub email: String,
-    pub oauth_token: OAuthToken,
-    pub class_id: Option<String>,
+    pub kind: UserKind,
+    pub oauth_token: Option<OAuthToken>,
+    pub manual_verification: Option<ManualVerification>,
+    pub class_id: Option<ClassId>,
+    pub class_confirmed_at: Option<DateTime<Utc>>,
+    pub group_ids: Vec<String>,
+    pub graduation_year: Option<i32>,
     pub authenticated_at: DateTime<Utc>,
 }
 
@@ -136,6 +253,14 @@ pub trait AuthenticatedUserRepository {
         &self,
         email: &str,
     ) -> Result<Option<AuthenticatedUser>, AuthenticatedUserRepositoryError>;
+    /// Case and accent insensitive search over the name, email and class, ordered by name
+    async fn search(
+        &self,
+        query: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<AuthenticatedUser>, AuthenticatedUserRepositoryError>;
+    async fn count_search(&self, query: &str) -> Result<u64, AuthenticatedUserRepositoryError>;
 }
 
 #[derive(Debug, Error)]
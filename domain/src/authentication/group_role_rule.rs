@@ -0,0 +1,47 @@
+use domain_shared::authentication::UserGroup;
+use domain_shared::discord::RoleId;
+use tracing::instrument;
+
+/// Assigns the role to the members of an Azure group other than the class group, e.g. a club
+/// or the student council. The group is referenced by its ID or mail.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupRoleRule {
+    pub group: String,
+    pub role_id: RoleId,
+}
+
+impl GroupRoleRule {
+    /// Returns the group reference as it is stored with the users, i.e. trimmed and lowercase
+    #[instrument(level = "trace", skip(self))]
+    pub fn group_id(&self) -> String {
+        self.group.trim().to_lowercase()
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn matches(&self, group: &UserGroup) -> bool {
+        let rule_group = self.group.trim();
+        let is_id_match = group.id.eq_ignore_ascii_case(rule_group);
+        let is_mail_match = group
+            .mail
+            .as_ref()
+            .map(|mail| mail.trim().eq_ignore_ascii_case(rule_group))
+            .unwrap_or(false);
+
+        is_id_match || is_mail_match
+    }
+}
+
+/// Finds the groups referenced by the rules which the user is a member of, the other groups
+/// are irrelevant for the role sync and are not persisted.
+#[instrument(level = "trace")]
+pub fn find_rule_group_ids(groups: &[UserGroup], rules: &[GroupRoleRule]) -> Vec<String> {
+    let mut group_ids = rules
+        .iter()
+        .filter(|rule| groups.iter().any(|group| rule.matches(group)))
+        .map(|rule| rule.group_id())
+        .collect::<Vec<_>>();
+    group_ids.sort();
+    group_ids.dedup();
+
+    group_ids
+}
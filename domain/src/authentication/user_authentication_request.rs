@@ -1,26 +1,37 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use domain_shared::authentication::CsrfToken;
+use chrono::{DateTime, Duration, Utc};
+use domain_shared::authentication::{CsrfToken, Nonce, PkceVerifier};
 use domain_shared::discord::UserId;
 use thiserror::Error;
 use tracing::instrument;
 
 pub struct UserAuthenticationRequest {
     csrf_token: CsrfToken,
+    pkce_verifier: Option<PkceVerifier>,
+    nonce: Option<Nonce>,
     user_id: UserId,
     requested_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
     confirmed_at: Option<DateTime<Utc>>,
 }
 
-#[instrument(level = "trace", skip(csrf_token))]
+#[instrument(level = "trace", skip(csrf_token, pkce_verifier, nonce))]
 pub fn create_user_authentication_request(
     csrf_token: CsrfToken,
+    pkce_verifier: PkceVerifier,
+    nonce: Nonce,
     user_id: UserId,
+    ttl: Duration,
 ) -> UserAuthenticationRequest {
+    let requested_at = Utc::now();
+
     UserAuthenticationRequest {
         csrf_token,
+        pkce_verifier: Some(pkce_verifier),
+        nonce: Some(nonce),
         user_id,
-        requested_at: Utc::now(),
+        requested_at,
+        expires_at: requested_at + ttl,
         confirmed_at: None,
     }
 }
@@ -31,6 +42,17 @@ impl UserAuthenticationRequest {
         &self.csrf_token
     }
 
+    /// Requests created before PKCE was introduced have no verifier
+    #[instrument(level = "trace", skip(self))]
+    pub fn pkce_verifier(&self) -> Option<&PkceVerifier> {
+        self.pkce_verifier.as_ref()
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn nonce(&self) -> Option<&Nonce> {
+        self.nonce.as_ref()
+    }
+
     #[instrument(level = "trace", skip(self))]
     pub fn user_id(&self) -> UserId {
         self.user_id
@@ -41,6 +63,16 @@ impl UserAuthenticationRequest {
         self.requested_at
     }
 
+    #[instrument(level = "trace", skip(self))]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
     #[instrument(level = "trace", skip(self))]
     pub fn confirmed_at(&self) -> Option<DateTime<Utc>> {
         self.confirmed_at
@@ -62,8 +94,11 @@ impl UserAuthenticationRequest {
     pub fn from_snapshot(snapshot: UserAuthenticationRequestSnapshot) -> Self {
         Self {
             csrf_token: snapshot.csrf_token,
+            pkce_verifier: snapshot.pkce_verifier,
+            nonce: snapshot.nonce,
             user_id: snapshot.user_id,
             requested_at: snapshot.requested_at,
+            expires_at: snapshot.expires_at,
             confirmed_at: snapshot.confirmed_at,
         }
     }
@@ -72,8 +107,11 @@ impl UserAuthenticationRequest {
     pub fn to_snapshot(&self) -> UserAuthenticationRequestSnapshot {
         UserAuthenticationRequestSnapshot {
             csrf_token: self.csrf_token.clone(),
+            pkce_verifier: self.pkce_verifier.clone(),
+            nonce: self.nonce.clone(),
             user_id: self.user_id,
             requested_at: self.requested_at,
+            expires_at: self.expires_at,
             confirmed_at: self.confirmed_at,
         }
     }
@@ -82,8 +120,11 @@ impl UserAuthenticationRequest {
 #[derive(Clone)]
 pub struct UserAuthenticationRequestSnapshot {
     pub csrf_token: CsrfToken,
+    pub pkce_verifier: Option<PkceVerifier>,
+    pub nonce: Option<Nonce>,
     pub user_id: UserId,
     pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
     pub confirmed_at: Option<DateTime<Utc>>,
 }
 
@@ -98,6 +139,22 @@ pub trait UserAuthenticationRequestRepository {
         &self,
         csrf_token: &CsrfToken,
     ) -> Result<Option<UserAuthenticationRequest>, UserAuthenticationRequestRepositoryError>;
+    /// Removes requests that expired before `expired_before`
+    /// or were confirmed before `confirmed_before`, returning the number of removed requests.
+    async fn remove_stale(
+        &self,
+        expired_before: DateTime<Utc>,
+        confirmed_before: DateTime<Utc>,
+    ) -> Result<u64, UserAuthenticationRequestRepositoryError>;
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<UserAuthenticationRequest>, UserAuthenticationRequestRepositoryError>;
+    /// Removes all requests of the user, returning the number of removed requests.
+    async fn remove_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<u64, UserAuthenticationRequestRepositoryError>;
 }
 
 #[derive(Debug, Error)]
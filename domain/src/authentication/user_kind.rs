@@ -0,0 +1,25 @@
+use domain_shared::authentication::{UserGroup, UserKind};
+use tracing::instrument;
+
+/// Determines whether the user is a student or a staff member (teacher, school employee)
+/// by their membership in any of the configured staff groups, matched by group ID or mail.
+#[instrument(level = "trace")]
+pub fn find_user_kind(groups: &[UserGroup], staff_groups: &[String]) -> UserKind {
+    for group in groups {
+        for staff_group in staff_groups {
+            let staff_group = staff_group.trim();
+            let is_id_match = group.id.eq_ignore_ascii_case(staff_group);
+            let is_mail_match = group
+                .mail
+                .as_ref()
+                .map(|mail| mail.trim().eq_ignore_ascii_case(staff_group))
+                .unwrap_or(false);
+
+            if is_id_match || is_mail_match {
+                return UserKind::Staff;
+            }
+        }
+    }
+
+    UserKind::Student
+}
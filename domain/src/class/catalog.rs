@@ -0,0 +1,125 @@
+use crate::class::class_id::ClassId;
+use async_trait::async_trait;
+use domain_shared::discord::RoleId;
+use thiserror::Error;
+use tracing::instrument;
+
+/// A class of the school known to the bot, only active classes get a Discord role.
+pub struct Class {
+    class_id: ClassId,
+    display_name: String,
+    role_id: Option<RoleId>,
+    active: bool,
+}
+
+impl Class {
+    #[instrument(level = "trace", skip(self))]
+    pub fn class_id(&self) -> &ClassId {
+        &self.class_id
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// The Discord role of the class, it is created by the role sync when unset
+    #[instrument(level = "trace", skip(self))]
+    pub fn role_id(&self) -> Option<RoleId> {
+        self.role_id
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn rename(&mut self, display_name: String) {
+        self.display_name = display_name;
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn assign_role(&mut self, role_id: RoleId) {
+        self.role_id = Some(role_id);
+    }
+
+    /// Moves a role of another class to this class, see the school-year rollover
+    #[instrument(level = "trace", skip(self))]
+    pub fn move_role(&mut self, role_id: Option<RoleId>) {
+        self.role_id = role_id;
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn retire(&mut self) {
+        self.active = false;
+    }
+
+    /// Brings a retired class back, e.g. when the school opens the class again
+    #[instrument(level = "trace", skip(self))]
+    pub fn reactivate(&mut self, display_name: String, role_id: Option<RoleId>) {
+        self.display_name = display_name;
+        self.role_id = role_id.or(self.role_id);
+        self.active = true;
+    }
+}
+
+#[instrument(level = "trace")]
+pub fn create_class(class_id: ClassId, display_name: String, role_id: Option<RoleId>) -> Class {
+    Class {
+        class_id,
+        display_name,
+        role_id,
+        active: true,
+    }
+}
+
+impl Class {
+    #[instrument(level = "trace", skip(snapshot))]
+    pub fn from_snapshot(snapshot: ClassSnapshot) -> Self {
+        Self {
+            class_id: snapshot.class_id,
+            display_name: snapshot.display_name,
+            role_id: snapshot.role_id,
+            active: snapshot.active,
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn to_snapshot(&self) -> ClassSnapshot {
+        ClassSnapshot {
+            class_id: self.class_id.clone(),
+            display_name: self.display_name.clone(),
+            role_id: self.role_id,
+            active: self.active,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ClassSnapshot {
+    pub class_id: ClassId,
+    pub display_name: String,
+    pub role_id: Option<RoleId>,
+    pub active: bool,
+}
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+#[async_trait]
+pub trait ClassRepository {
+    async fn save(&self, class: &Class) -> Result<(), ClassRepositoryError>;
+
+    /// Finds all classes including the retired ones, ordered by the class ID
+    async fn find_all(&self) -> Result<Vec<Class>, ClassRepositoryError>;
+
+    async fn find_by_class_id(
+        &self,
+        class_id: &ClassId,
+    ) -> Result<Option<Class>, ClassRepositoryError>;
+}
+
+#[derive(Debug, Error)]
+pub enum ClassRepositoryError {
+    #[error("Service unavailable")]
+    ServiceUnavailable,
+}
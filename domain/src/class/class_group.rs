@@ -1,22 +1,24 @@
-use crate::authentication::create_class_ids;
+use crate::class::class_id::ClassId;
 use domain_shared::authentication::UserGroup;
 use tracing::{error, instrument};
 
-#[instrument(level = "trace")]
-pub fn find_class_group(groups: &[UserGroup]) -> Option<&UserGroup> {
-    let class_ids = create_class_ids();
-
+/// Finds the group of the user's class, the class IDs come from the class catalog.
+#[instrument(level = "trace", skip(class_ids))]
+pub fn find_class_group<'a>(
+    groups: &'a [UserGroup],
+    class_ids: &[ClassId],
+) -> Option<&'a UserGroup> {
     for group in groups {
         if let Some(ref mail) = group.mail {
             let mut mail = mail.split('@');
             let local_part = mail.next().unwrap_or("").trim();
             let domain_part = mail.next().unwrap_or("").trim();
 
-            for class_id in &class_ids {
-                if class_id.eq_ignore_ascii_case(local_part) && check_mail_domain_part(domain_part)
-                {
-                    return Some(group);
-                }
+            if let Some(class_id) = ClassId::parse(local_part)
+                && class_ids.contains(&class_id)
+                && check_mail_domain_part(domain_part)
+            {
+                return Some(group);
             }
         }
     }
@@ -1,9 +1,136 @@
-use crate::authentication::create_class_ids;
 use domain_shared::authentication::UserGroup;
+use std::fmt::{Display, Formatter};
 use tracing::{error, instrument};
 
-#[instrument(level = "trace")]
-pub fn get_class_id(group: &UserGroup) -> Option<String> {
+/// The last year of the study, the students of the final-year classes graduate
+pub const FINAL_YEAR: u8 = 4;
+const MAX_GROUP_LENGTH: usize = 2;
+
+/// Class ID following the school's naming scheme, e.g. `c2b` is the group `b` of the second year
+/// of the field of study `c`. Classes without the prefix belong to the main field of study.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClassId {
+    field: Option<FieldOfStudy>,
+    year: u8,
+    group: String,
+}
+
+/// Field of study distinguished by the prefix of the class ID
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FieldOfStudy {
+    C,
+    H,
+    G,
+    L,
+}
+
+impl FieldOfStudy {
+    #[instrument(level = "trace")]
+    pub fn from_prefix(prefix: char) -> Option<Self> {
+        match prefix.to_ascii_lowercase() {
+            'c' => Some(Self::C),
+            'h' => Some(Self::H),
+            'g' => Some(Self::G),
+            'l' => Some(Self::L),
+            _ => None,
+        }
+    }
+
+    #[instrument(level = "trace")]
+    pub fn prefix(self) -> char {
+        match self {
+            Self::C => 'c',
+            Self::H => 'h',
+            Self::G => 'g',
+            Self::L => 'l',
+        }
+    }
+}
+
+impl ClassId {
+    /// Parses the class ID case-insensitively, e.g. `C2B` or `2b`.
+    #[instrument(level = "trace")]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        let mut chars = value.chars().peekable();
+
+        let field = match chars.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                let field = FieldOfStudy::from_prefix(*c)?;
+                chars.next();
+                Some(field)
+            }
+            _ => None,
+        };
+
+        let year = chars.next()?.to_digit(10)? as u8;
+        if !(1..=FINAL_YEAR).contains(&year) {
+            return None;
+        }
+
+        let group = chars.collect::<String>();
+        if group.is_empty()
+            || group.len() > MAX_GROUP_LENGTH
+            || !group.chars().all(|c| c.is_ascii_lowercase())
+        {
+            return None;
+        }
+
+        Some(Self { field, year, group })
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn field(&self) -> Option<FieldOfStudy> {
+        self.field
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn year(&self) -> u8 {
+        self.year
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn is_final_year(&self) -> bool {
+        self.year == FINAL_YEAR
+    }
+
+    /// Returns the class the students move to in the next school year, final-year classes have none
+    #[instrument(level = "trace", skip(self))]
+    pub fn next_year(&self) -> Option<Self> {
+        if self.is_final_year() {
+            return None;
+        }
+
+        Some(Self {
+            field: self.field,
+            year: self.year + 1,
+            group: self.group.clone(),
+        })
+    }
+
+    /// Returns whether the classes differ in the year only, e.g. `c1b` and `c2b`
+    #[instrument(level = "trace", skip(self))]
+    pub fn is_same_series(&self, other: &Self) -> bool {
+        self.field == other.field && self.group == other.group
+    }
+}
+
+impl Display for ClassId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(field) = self.field {
+            write!(f, "{}", field.prefix())?;
+        }
+        write!(f, "{}{}", self.year, self.group)
+    }
+}
+
+#[instrument(level = "trace", skip(class_ids))]
+pub fn get_class_id(group: &UserGroup, class_ids: &[ClassId]) -> Option<ClassId> {
     let mail = group.mail.as_ref()?;
     let mut mail = mail.split('@');
     let local_part = mail.next().unwrap_or("").trim();
@@ -11,14 +138,87 @@ pub fn get_class_id(group: &UserGroup) -> Option<String> {
         return None;
     }
 
-    let class_id = local_part.to_string();
-    let class_ids = create_class_ids();
+    let Some(class_id) = ClassId::parse(local_part) else {
+        error!(
+            class_id = local_part,
+            "Class ID does not follow the naming scheme of the school",
+        );
+        return None;
+    };
     if !class_ids.contains(&class_id) {
         error!(
-            class_id = class_id.as_str(),
-            "Class ID does not match any of the class IDs in the application",
+            class_id = %class_id,
+            "Class ID does not match any of the class IDs in the class catalog",
         );
     }
 
     Some(class_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_class_ids_with_and_without_field() {
+        let class_id = ClassId::parse("c2b").unwrap();
+        assert_eq!(class_id.field(), Some(FieldOfStudy::C));
+        assert_eq!(class_id.year(), 2);
+        assert_eq!(class_id.group(), "b");
+
+        let class_id = ClassId::parse("1ga").unwrap();
+        assert_eq!(class_id.field(), None);
+        assert_eq!(class_id.year(), 1);
+        assert_eq!(class_id.group(), "ga");
+
+        let class_id = ClassId::parse("g1a").unwrap();
+        assert_eq!(class_id.field(), Some(FieldOfStudy::G));
+        assert_eq!(class_id.year(), 1);
+        assert_eq!(class_id.group(), "a");
+    }
+
+    #[test]
+    fn parses_case_insensitively_and_trims() {
+        assert_eq!(ClassId::parse(" C2B "), ClassId::parse("c2b"));
+        assert_eq!(ClassId::parse("4K"), ClassId::parse("4k"));
+    }
+
+    #[test]
+    fn rejects_class_ids_not_following_the_naming_scheme() {
+        for value in [
+            "", "c", "2", "b2", "x2b", "c0b", "c5b", "c2", "c2abc", "c2b1", "c12b", "c-2b",
+        ] {
+            assert_eq!(ClassId::parse(value), None, "{value:?} must be rejected");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for value in ["1a", "4k", "1ga", "c2b", "g1a", "h3gb", "l4d"] {
+            let class_id = ClassId::parse(value).unwrap();
+            assert_eq!(class_id.to_string(), value);
+            assert_eq!(ClassId::parse(&class_id.to_string()), Some(class_id));
+        }
+        assert_eq!(ClassId::parse("C2B").unwrap().to_string(), "c2b");
+    }
+
+    #[test]
+    fn next_year_keeps_field_and_group() {
+        let class_id = ClassId::parse("c1b").unwrap();
+        assert_eq!(class_id.next_year(), ClassId::parse("c2b"));
+        assert_eq!(
+            ClassId::parse("3ga").unwrap().next_year(),
+            ClassId::parse("4ga")
+        );
+        assert_eq!(ClassId::parse("c4b").unwrap().next_year(), None);
+        assert!(ClassId::parse("c4b").unwrap().is_final_year());
+    }
+
+    #[test]
+    fn series_ignores_the_year_only() {
+        let class_id = ClassId::parse("1ga").unwrap();
+        assert!(class_id.is_same_series(&ClassId::parse("2ga").unwrap()));
+        assert!(!class_id.is_same_series(&ClassId::parse("g2a").unwrap()));
+        assert!(!class_id.is_same_series(&ClassId::parse("2gb").unwrap()));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::class::class_id::ClassId;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use tracing::instrument;
+
+/// Day of the year on which the final-year students graduate, e.g. 30th of June.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraduationDate {
+    pub month: u32,
+    pub day: u32,
+}
+
+impl GraduationDate {
+    /// Parses the graduation date from the `MM-DD` format.
+    #[instrument(level = "trace")]
+    pub fn parse(value: &str) -> Option<Self> {
+        let (month, day) = value.trim().split_once('-')?;
+        let month = month.parse().ok()?;
+        let day = day.parse().ok()?;
+
+        // Validate the date against a leap year, so the 29th of February is accepted
+        NaiveDate::from_ymd_opt(2000, month, day)?;
+
+        Some(Self { month, day })
+    }
+
+    /// Returns the graduation date in the year, the 29th of February falls
+    /// on the 28th in non-leap years.
+    #[instrument(level = "trace")]
+    pub fn in_year(self, year: i32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, self.month, self.day)
+            .or_else(|| NaiveDate::from_ymd_opt(year, self.month, self.day - 1))
+    }
+}
+
+/// Returns the graduation year of a user whose class group has vanished, if the vanishing
+/// can be explained by the graduation, i.e. the user was in a final-year class and the graduation
+/// date ending the school year in which the class was last confirmed has passed. A group vanishing
+/// earlier, e.g. during the final exams or by a mistake, does not graduate the user.
+#[instrument(level = "trace")]
+pub fn find_graduation_year(
+    last_class_id: &ClassId,
+    class_confirmed_at: DateTime<Utc>,
+    graduation_date: GraduationDate,
+    now: DateTime<Utc>,
+) -> Option<i32> {
+    if !last_class_id.is_final_year() {
+        return None;
+    }
+
+    let confirmed_on = class_confirmed_at.date_naive();
+    let mut graduated_on = graduation_date.in_year(confirmed_on.year())?;
+    if graduated_on < confirmed_on {
+        graduated_on = graduation_date.in_year(confirmed_on.year() + 1)?;
+    }
+
+    (now.date_naive() >= graduated_on).then_some(graduated_on.year())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap()
+    }
+
+    fn final_year() -> ClassId {
+        ClassId::parse("c4b").unwrap()
+    }
+
+    const JUNE_30: GraduationDate = GraduationDate { month: 6, day: 30 };
+    const FEBRUARY_29: GraduationDate = GraduationDate { month: 2, day: 29 };
+
+    #[test]
+    fn parses_graduation_dates() {
+        assert_eq!(GraduationDate::parse("06-30"), Some(JUNE_30));
+        assert_eq!(GraduationDate::parse("02-29"), Some(FEBRUARY_29));
+        assert_eq!(GraduationDate::parse("02-30"), None);
+        assert_eq!(GraduationDate::parse("13-01"), None);
+        assert_eq!(GraduationDate::parse("0630"), None);
+    }
+
+    #[test]
+    fn only_final_year_classes_graduate() {
+        let class_id = ClassId::parse("c3b").unwrap();
+        assert_eq!(
+            find_graduation_year(&class_id, at(2026, 5, 1), JUNE_30, at(2026, 7, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn graduates_on_and_after_the_date_of_the_final_year() {
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 5, 1), JUNE_30, at(2026, 6, 30)),
+            Some(2026)
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 5, 1), JUNE_30, at(2026, 7, 1)),
+            Some(2026)
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 6, 30), JUNE_30, at(2026, 7, 1)),
+            Some(2026)
+        );
+    }
+
+    #[test]
+    fn does_not_graduate_before_the_date_of_the_final_year() {
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 5, 1), JUNE_30, at(2026, 5, 15)),
+            None
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 5, 1), JUNE_30, at(2026, 6, 29)),
+            None
+        );
+        // The class was confirmed in the next school year, the user graduates in the next year
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 10, 1), JUNE_30, at(2026, 11, 15)),
+            None
+        );
+    }
+
+    #[test]
+    fn graduation_year_does_not_follow_the_year_of_the_check() {
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 5, 1), JUNE_30, at(2027, 1, 15)),
+            Some(2026)
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 5, 1), JUNE_30, at(2027, 11, 15)),
+            Some(2026)
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 10, 1), JUNE_30, at(2027, 7, 1)),
+            Some(2027)
+        );
+    }
+
+    #[test]
+    fn february_29_falls_on_february_28_in_non_leap_years() {
+        assert_eq!(
+            FEBRUARY_29.in_year(2027),
+            NaiveDate::from_ymd_opt(2027, 2, 28)
+        );
+        assert_eq!(
+            FEBRUARY_29.in_year(2028),
+            NaiveDate::from_ymd_opt(2028, 2, 29)
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 10, 1), FEBRUARY_29, at(2027, 2, 27)),
+            None
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2026, 10, 1), FEBRUARY_29, at(2027, 2, 28)),
+            Some(2027)
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2027, 10, 1), FEBRUARY_29, at(2028, 2, 28)),
+            None
+        );
+        assert_eq!(
+            find_graduation_year(&final_year(), at(2027, 10, 1), FEBRUARY_29, at(2028, 2, 29)),
+            Some(2028)
+        );
+    }
+}
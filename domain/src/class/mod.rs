@@ -1,2 +1,5 @@
+pub mod catalog;
 pub mod class_group;
 pub mod class_id;
+pub mod graduation;
+pub mod rollover;
@@ -0,0 +1,142 @@
+use crate::authentication::authenticated_user::AuthenticatedUser;
+use crate::class::catalog::Class;
+use crate::class::class_id::ClassId;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use domain_shared::authentication::UserKind;
+use domain_shared::discord::{RoleId, UserId};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use tracing::instrument;
+
+/// Class of a student at the start of the school-year rollover.
+#[derive(Clone, Debug)]
+pub struct ClassRolloverEntry {
+    pub user_id: UserId,
+    pub class_id: ClassId,
+    pub taken_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClassChangeKind {
+    /// The student moved to the next year of the same class
+    Advanced,
+    /// The student left a final-year class and is alumni now
+    Graduated,
+    /// The class did not change, e.g. the user info sync did not run or the student repeats the year
+    NotAdvanced,
+    /// The student moved elsewhere, lost the class or is no longer verified
+    Unexpected,
+}
+
+/// Takes the classes of all students, staff and alumni do not advance.
+#[instrument(level = "debug", skip_all)]
+pub fn create_class_rollover_entries(users: &[AuthenticatedUser]) -> Vec<ClassRolloverEntry> {
+    let taken_at = Utc::now();
+
+    users
+        .iter()
+        .filter(|user| user.kind() == UserKind::Student && !user.is_alumni())
+        .filter_map(|user| {
+            Some(ClassRolloverEntry {
+                user_id: user.user_id(),
+                class_id: user.class_id()?.clone(),
+                taken_at,
+            })
+        })
+        .collect()
+}
+
+/// A rollover started this recently is not started again unless forced, a second run would move
+/// the class roles once more and replace the classes the report compares against
+const ROLLOVER_COOLDOWN: Duration = Duration::days(60);
+
+/// Returns when the rollover recorded by the entries was started, if it was started recently
+#[instrument(level = "debug", skip(entries))]
+pub fn find_recent_rollover(
+    entries: &[ClassRolloverEntry],
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    entries
+        .iter()
+        .map(|entry| entry.taken_at)
+        .min()
+        .filter(|taken_at| now - *taken_at < ROLLOVER_COOLDOWN)
+}
+
+#[instrument(level = "trace", skip(user))]
+pub fn classify_class_change(
+    entry: &ClassRolloverEntry,
+    user: Option<&AuthenticatedUser>,
+) -> ClassChangeKind {
+    let Some(user) = user else {
+        return ClassChangeKind::Unexpected;
+    };
+
+    if user.is_alumni() {
+        return if entry.class_id.is_final_year() {
+            ClassChangeKind::Graduated
+        } else {
+            ClassChangeKind::Unexpected
+        };
+    }
+
+    match user.class_id() {
+        Some(class_id) if *class_id == entry.class_id => ClassChangeKind::NotAdvanced,
+        Some(class_id) if entry.class_id.next_year().as_ref() == Some(class_id) => {
+            ClassChangeKind::Advanced
+        }
+        _ => ClassChangeKind::Unexpected,
+    }
+}
+
+/// Moves the roles of the active classes one year up, so the roles and their channel permissions
+/// follow the students. The roles of the final-year classes are reused by the first-year classes.
+///
+/// Returns the classes whose role changed together with the new role.
+#[instrument(level = "debug", skip_all)]
+pub fn plan_class_role_shift(classes: &[Class]) -> Vec<(ClassId, Option<RoleId>)> {
+    // Classes of the same series differ in the year only, e.g. `c1b` and `c2b`
+    let mut series = BTreeMap::<_, Vec<&Class>>::new();
+    for class in classes.iter().filter(|class| class.is_active()) {
+        let series_key = (class.class_id().field(), class.class_id().group());
+        series.entry(series_key).or_default().push(class);
+    }
+
+    let mut shifted_roles = Vec::new();
+    for mut classes in series.into_values() {
+        classes.sort_by_key(|class| class.class_id().year());
+
+        let mut role_ids = classes
+            .iter()
+            .map(|class| class.role_id())
+            .collect::<Vec<_>>();
+        role_ids.rotate_right(1);
+
+        for (class, role_id) in classes.into_iter().zip(role_ids) {
+            if class.role_id() != role_id {
+                shifted_roles.push((class.class_id().clone(), role_id));
+            }
+        }
+    }
+
+    shifted_roles
+}
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+#[async_trait]
+pub trait ClassRolloverRepository {
+    /// Replaces the entries of the previous rollover
+    async fn replace_all(
+        &self,
+        entries: &[ClassRolloverEntry],
+    ) -> Result<(), ClassRolloverRepositoryError>;
+
+    async fn find_all(&self) -> Result<Vec<ClassRolloverEntry>, ClassRolloverRepositoryError>;
+}
+
+#[derive(Debug, Error)]
+pub enum ClassRolloverRepositoryError {
+    #[error("Service unavailable")]
+    ServiceUnavailable,
+}
@@ -1,2 +1,3 @@
 pub mod role_sync_job;
+pub mod sync_trigger;
 pub mod user_info_sync_job;
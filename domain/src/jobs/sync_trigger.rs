@@ -0,0 +1,38 @@
+use tracing::instrument;
+
+/// What a sync was requested by, the role changes are logged with its reason
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncTrigger {
+    Verification,
+    ManualVerification,
+    Unverification,
+    /// Another account was verified with the email of the user
+    EmailReused,
+    MemberJoin,
+    AdminRefresh,
+    ClassRollover,
+    ExemptionChange,
+    NicknameSettingsChange,
+    PersonalDataErasure,
+    Periodic,
+}
+
+impl SyncTrigger {
+    /// The reason shown in the role change log and the Discord audit log
+    #[instrument(level = "trace")]
+    pub fn reason(self) -> &'static str {
+        match self {
+            SyncTrigger::Verification => "User verified",
+            SyncTrigger::ManualVerification => "User manually verified by an admin",
+            SyncTrigger::Unverification => "User unverified",
+            SyncTrigger::EmailReused => "Another account verified with the same email",
+            SyncTrigger::MemberJoin => "Member joined the guild",
+            SyncTrigger::AdminRefresh => "Sync requested by an admin",
+            SyncTrigger::ClassRollover => "School-year rollover",
+            SyncTrigger::ExemptionChange => "Role sync exemption removed",
+            SyncTrigger::NicknameSettingsChange => "Nickname settings changed",
+            SyncTrigger::PersonalDataErasure => "Personal data erased",
+            SyncTrigger::Periodic => "Periodic sync",
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::jobs::sync_trigger::SyncTrigger;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use domain_shared::discord::UserId;
@@ -9,14 +10,16 @@ pub struct UserInfoSyncRequested {
     pub user_id: UserId,
     pub queued_at: DateTime<Utc>,
     pub low_priority: bool,
+    pub trigger: SyncTrigger,
 }
 
 #[instrument(level = "info")]
-pub fn request_user_info_sync(user_id: UserId) -> UserInfoSyncRequested {
+pub fn request_user_info_sync(user_id: UserId, trigger: SyncTrigger) -> UserInfoSyncRequested {
     UserInfoSyncRequested {
         user_id,
         queued_at: Utc::now(),
         low_priority: false,
+        trigger,
     }
 }
 
@@ -26,9 +29,11 @@ pub fn request_periodic_user_info_sync(user_id: UserId) -> UserInfoSyncRequested
         user_id,
         queued_at: Utc::now(),
         low_priority: true,
+        trigger: SyncTrigger::Periodic,
     }
 }
 
+#[cfg_attr(feature = "mock", mockall::automock)]
 #[async_trait]
 pub trait UserInfoSyncRequestedRepository {
     async fn save(
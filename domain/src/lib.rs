@@ -2,6 +2,9 @@ pub mod authentication;
 pub mod class;
 pub mod information_channel;
 pub mod jobs;
+pub mod nickname;
 pub mod ports;
 mod resources;
+pub mod role_change_log;
+pub mod role_sync_exemption;
 pub mod roles;
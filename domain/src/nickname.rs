@@ -0,0 +1,108 @@
+use crate::authentication::authenticated_user::AuthenticatedUser;
+use async_trait::async_trait;
+use domain_shared::authentication::UserKind;
+use domain_shared::discord::UserId;
+use thiserror::Error;
+use tracing::instrument;
+
+/// Discord allows at most 32 characters in a nickname
+const MAX_NICKNAME_LENGTH: usize = 32;
+
+/// Nicknames of the verified users, the formats use the `{name}` and `{class}` placeholders
+#[derive(Clone, Debug)]
+pub struct NicknamePolicy {
+    /// Used for the students with a known class, e.g. `{name} ({class})`
+    pub format: String,
+    /// Used for the staff, the alumni and the students with an unknown class
+    pub format_without_class: String,
+}
+
+impl NicknamePolicy {
+    /// Creates the nickname of the user, the name is shortened to fit the nickname length limit
+    #[instrument(level = "trace", skip_all)]
+    pub fn nickname(&self, user: &AuthenticatedUser) -> String {
+        let class_id = user
+            .class_id()
+            .filter(|_| user.kind() == UserKind::Student && !user.is_alumni());
+        let (format, class) = match class_id {
+            // Class IDs are stored in lowercase, the class is written as `3B` elsewhere
+            Some(class_id) => (&self.format, class_id.to_string().to_uppercase()),
+            None => (&self.format_without_class, String::new()),
+        };
+
+        let format = format.replace("{class}", &class);
+        let name = user.name().trim();
+        let format_length = format.replace("{name}", "").chars().count();
+        let max_name_length = MAX_NICKNAME_LENGTH.saturating_sub(format_length);
+        let name = if name.chars().count() > max_name_length {
+            name.chars()
+                .take(max_name_length)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        } else {
+            name.to_string()
+        };
+
+        format
+            .replace("{name}", &name)
+            .chars()
+            .take(MAX_NICKNAME_LENGTH)
+            .collect()
+    }
+}
+
+/// Nickname preferences of a user, kept when the user verifies again
+#[derive(Clone, Debug)]
+pub struct NicknameSettings {
+    pub user_id: UserId,
+    /// Set by the user who does not want their nickname to be managed
+    pub opt_out: bool,
+    /// Set by an admin, `Some(true)` enforces the nickname despite the opt-out
+    /// and `Some(false)` never changes the nickname of the user
+    pub admin_override: Option<bool>,
+    /// Nickname last set by the bot, removed again when the user is no longer verified
+    /// or stops the nickname sync
+    pub applied_nickname: Option<String>,
+}
+
+impl NicknameSettings {
+    #[instrument(level = "trace")]
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            user_id,
+            opt_out: false,
+            admin_override: None,
+            applied_nickname: None,
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn is_sync_enabled(&self) -> bool {
+        self.admin_override.unwrap_or(!self.opt_out)
+    }
+}
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+#[async_trait]
+pub trait NicknameSettingsRepository {
+    async fn save(
+        &self,
+        settings: &NicknameSettings,
+    ) -> Result<(), NicknameSettingsRepositoryError>;
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<Option<NicknameSettings>, NicknameSettingsRepositoryError>;
+    /// Removes the settings of the user, returning whether there were any
+    async fn remove_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<bool, NicknameSettingsRepositoryError>;
+}
+
+#[derive(Debug, Error)]
+pub enum NicknameSettingsRepositoryError {
+    #[error("Service unavailable")]
+    ServiceUnavailable,
+}
@@ -2,6 +2,7 @@ mod create_action_row;
 mod create_attachment;
 mod create_button;
 mod create_message;
+mod nickname_update;
 mod role;
 mod role_diff;
 
@@ -11,6 +12,7 @@ pub use create_button::{ButtonId, ButtonKind, CreateButton};
 pub use create_message::CreateMessage;
 pub use domain_shared::discord::ChannelId;
 use domain_shared::discord::{RoleId, UserId};
+pub use nickname_update::NicknameUpdate;
 pub use role::Role;
 pub use role_diff::RoleDiff;
 use std::future::Future;
@@ -35,6 +37,13 @@ pub trait DiscordPort {
         reason: &str,
     ) -> impl Future<Output = Result<Role, DiscordError>> + Send;
 
+    fn rename_role(
+        &self,
+        role_id: RoleId,
+        role_name: &str,
+        reason: &str,
+    ) -> impl Future<Output = Result<(), DiscordError>> + Send;
+
     fn apply_role_diff(
         &self,
         user_id: UserId,
@@ -42,6 +51,23 @@ pub trait DiscordPort {
         reason: &str,
     ) -> impl Future<Output = Result<(), DiscordError>> + Send;
 
+    /// Sets the nickname of the member, the guild owner and the members ranked above the bot
+    /// cannot be renamed by the bot and are skipped
+    fn set_nickname(
+        &self,
+        user_id: UserId,
+        nickname: &str,
+        reason: &str,
+    ) -> impl Future<Output = Result<NicknameUpdate, DiscordError>> + Send;
+
+    /// Removes the nickname set by the bot, a nickname the member has changed since is kept
+    fn reset_nickname(
+        &self,
+        user_id: UserId,
+        nickname: &str,
+        reason: &str,
+    ) -> impl Future<Output = Result<NicknameUpdate, DiscordError>> + Send;
+
     fn find_user_roles(
         &self,
         user_id: UserId,
@@ -0,0 +1,9 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NicknameUpdate {
+    Updated,
+    /// The member already has the nickname, or has changed the nickname set by the bot
+    Unchanged,
+    /// The member is the guild owner or is ranked above the bot
+    Skipped,
+    MemberNotFound,
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use domain_shared::authentication::{
-    AccessToken, AuthenticationLink, ClientCallbackToken, CsrfToken, RefreshToken, UserGroup,
+    AccessToken, AuthenticationLink, ClientCallbackToken, CsrfToken, Nonce, PkceVerifier,
+    RefreshToken, UserGroup,
 };
 use std::fmt::Debug;
 use std::future::Future;
@@ -11,12 +12,12 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait OAuthPort {
-    fn create_authentication_link(
-        &self,
-    ) -> impl Future<Output = (AuthenticationLink, CsrfToken)> + Send;
+    fn create_authentication_link(&self) -> impl Future<Output = AuthenticationLinkDto> + Send;
     fn exchange_code_after_callback(
         &self,
         client_callback_token: ClientCallbackToken,
+        pkce_verifier: Option<PkceVerifier>,
+        nonce: Option<Nonce>,
     ) -> impl Future<Output = Result<OAuthToken, OAuthError>> + Send;
     fn refresh_token(
         &self,
@@ -26,6 +27,18 @@ pub trait OAuthPort {
         &self,
         access_token: &AccessToken,
     ) -> impl Future<Output = Result<UserInfoDto, OAuthError>> + Send;
+    fn revoke_token(
+        &self,
+        oauth_token: &OAuthToken,
+    ) -> impl Future<Output = Result<(), OAuthError>> + Send;
+}
+
+#[derive(Debug)]
+pub struct AuthenticationLinkDto {
+    pub link: AuthenticationLink,
+    pub csrf_token: CsrfToken,
+    pub pkce_verifier: PkceVerifier,
+    pub nonce: Nonce,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -0,0 +1,67 @@
+use crate::ports::discord::RoleDiff;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain_shared::discord::{RoleId, UserId};
+use thiserror::Error;
+use tracing::instrument;
+
+/// Role changes applied to a guild member by the role sync
+#[derive(Clone, Debug)]
+pub struct RoleChangeLogEntry {
+    pub user_id: UserId,
+    pub assigned: Vec<RoleId>,
+    pub removed: Vec<RoleId>,
+    /// The reason the roles were changed with, shown in the Discord audit log as well
+    pub reason: String,
+    /// Whether the change was triggered by a periodic role sync rather than a user action
+    pub low_priority: bool,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Creates the log entry of the applied role diff, empty diffs are not logged
+#[instrument(level = "trace", skip(role_diff))]
+pub fn create_role_change_log_entry(
+    user_id: UserId,
+    role_diff: &RoleDiff,
+    reason: &str,
+    low_priority: bool,
+) -> Option<RoleChangeLogEntry> {
+    if role_diff.to_assign().is_empty() && role_diff.to_remove().is_empty() {
+        return None;
+    }
+
+    Some(RoleChangeLogEntry {
+        user_id,
+        assigned: role_diff.to_assign().to_vec(),
+        removed: role_diff.to_remove().to_vec(),
+        reason: reason.to_string(),
+        low_priority,
+        changed_at: Utc::now(),
+    })
+}
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+#[async_trait]
+pub trait RoleChangeLogRepository {
+    async fn save(&self, entry: &RoleChangeLogEntry) -> Result<(), RoleChangeLogRepositoryError>;
+
+    /// Finds the role changes of the user, the most recent first
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<RoleChangeLogEntry>, RoleChangeLogRepositoryError>;
+
+    async fn count_by_user_id(&self, user_id: UserId) -> Result<u64, RoleChangeLogRepositoryError>;
+
+    /// Removes the role changes of the user, returning the number of removed entries
+    async fn remove_by_user_id(&self, user_id: UserId)
+    -> Result<u64, RoleChangeLogRepositoryError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RoleChangeLogRepositoryError {
+    #[error("Service unavailable")]
+    ServiceUnavailable,
+}
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain_shared::discord::{RoleId, UserId};
+use thiserror::Error;
+use tracing::instrument;
+
+/// Member whose roles are never changed by the role sync, either directly or by having the role
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExemptionTarget {
+    User(UserId),
+    Role(RoleId),
+}
+
+#[derive(Clone, Debug)]
+pub struct RoleSyncExemption {
+    pub target: ExemptionTarget,
+    pub reason: String,
+    pub created_by: UserId,
+    pub created_at: DateTime<Utc>,
+}
+
+#[instrument(level = "trace")]
+pub fn create_role_sync_exemption(
+    target: ExemptionTarget,
+    reason: String,
+    created_by: UserId,
+) -> RoleSyncExemption {
+    RoleSyncExemption {
+        target,
+        reason,
+        created_by,
+        created_at: Utc::now(),
+    }
+}
+
+/// Finds the exemptions applying to the member with the assigned roles
+#[instrument(level = "trace", skip(exemptions, assigned_roles))]
+pub fn find_member_exemptions<'a>(
+    exemptions: &'a [RoleSyncExemption],
+    user_id: UserId,
+    assigned_roles: &[RoleId],
+) -> Vec<&'a RoleSyncExemption> {
+    exemptions
+        .iter()
+        .filter(|exemption| match exemption.target {
+            ExemptionTarget::User(exempt_user_id) => exempt_user_id == user_id,
+            ExemptionTarget::Role(exempt_role_id) => assigned_roles.contains(&exempt_role_id),
+        })
+        .collect()
+}
+
+#[cfg_attr(feature = "mock", mockall::automock)]
+#[async_trait]
+pub trait RoleSyncExemptionRepository {
+    /// Saves the exemption, replacing the one of the same target
+    async fn save(
+        &self,
+        exemption: &RoleSyncExemption,
+    ) -> Result<(), RoleSyncExemptionRepositoryError>;
+    /// Removes the exemption of the target, returning whether there was any
+    async fn remove(
+        &self,
+        target: ExemptionTarget,
+    ) -> Result<bool, RoleSyncExemptionRepositoryError>;
+    async fn find_all(&self) -> Result<Vec<RoleSyncExemption>, RoleSyncExemptionRepositoryError>;
+    async fn find_by_target(
+        &self,
+        target: ExemptionTarget,
+    ) -> Result<Option<RoleSyncExemption>, RoleSyncExemptionRepositoryError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RoleSyncExemptionRepositoryError {
+    #[error("Service unavailable")]
+    ServiceUnavailable,
+}
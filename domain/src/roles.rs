@@ -1,14 +1,27 @@
 use crate::authentication::authenticated_user::AuthenticatedUser;
+use crate::authentication::group_role_rule::GroupRoleRule;
+use crate::class::class_id::{ClassId, FieldOfStudy};
 use crate::ports::discord::RoleDiff;
+use domain_shared::authentication::UserKind;
 use domain_shared::discord::RoleId;
 use tracing::{error, instrument};
 
 pub struct RolesDiffService {
     pub everyone_roles: Vec<RoleId>,
     pub additional_student_roles: Vec<RoleId>,
+    pub staff_roles: Vec<RoleId>,
+    pub alumni_role_id: Option<RoleId>,
     pub unknown_class_role_id: RoleId,
-    pub class_ids: Vec<String>,
-    pub class_id_to_role_id: Vec<(String, RoleId)>,
+    /// Roles of the active classes in the class catalog
+    pub class_id_to_role_id: Vec<(ClassId, RoleId)>,
+    /// Roles of the retired classes, they are only ever removed
+    pub retired_class_role_ids: Vec<RoleId>,
+    /// Optional roles of the students of a year, e.g. "1. ročník"
+    pub year_to_role_id: Vec<(u8, RoleId)>,
+    /// Optional roles of the students of a field of study, `None` is the field without a prefix
+    pub field_to_role_id: Vec<(Option<FieldOfStudy>, RoleId)>,
+    /// Roles of the members of the Azure groups other than the class groups
+    pub group_role_rules: Vec<GroupRoleRule>,
 }
 
 impl RolesDiffService {
@@ -16,9 +29,18 @@ impl RolesDiffService {
     pub fn diff_roles(&self, user: Option<&AuthenticatedUser>) -> RoleDiff {
         let mut diff = RoleDiff::default();
 
+        let student = user.filter(|u| u.kind() == UserKind::Student && !u.is_alumni());
+        let alumni = user.filter(|u| u.kind() == UserKind::Student && u.is_alumni());
+        let staff = user.filter(|u| u.kind() == UserKind::Staff);
+
         self.diff_everyone_roles(&mut diff);
-        self.diff_additional_student_roles(user, &mut diff);
-        self.diff_class_roles(user, &mut diff);
+        self.diff_additional_student_roles(student, &mut diff);
+        self.diff_class_roles(student, &mut diff);
+        self.diff_year_roles(student, &mut diff);
+        self.diff_field_roles(student, &mut diff);
+        self.diff_alumni_roles(alumni, &mut diff);
+        self.diff_staff_roles(staff, &mut diff);
+        self.diff_group_roles(user, &mut diff);
 
         diff
     }
@@ -41,6 +63,40 @@ impl RolesDiffService {
         }
     }
 
+    #[instrument(level = "trace", skip_all)]
+    fn diff_alumni_roles(&self, alumni: Option<&AuthenticatedUser>, diff: &mut RoleDiff) {
+        if let Some(alumni_role_id) = self.alumni_role_id {
+            if let Some(_alumni) = alumni {
+                diff.assign(alumni_role_id);
+            } else {
+                diff.remove(alumni_role_id);
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    fn diff_staff_roles(&self, staff: Option<&AuthenticatedUser>, diff: &mut RoleDiff) {
+        for staff_role in &self.staff_roles {
+            if let Some(_staff) = staff {
+                diff.assign(*staff_role);
+            } else {
+                diff.remove(*staff_role);
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    fn diff_group_roles(&self, user: Option<&AuthenticatedUser>, diff: &mut RoleDiff) {
+        for rule in &self.group_role_rules {
+            let group_id = rule.group_id();
+            if user.is_some_and(|u| u.group_ids().contains(&group_id)) {
+                diff.assign(rule.role_id);
+            } else {
+                diff.remove(rule.role_id);
+            }
+        }
+    }
+
     #[instrument(level = "trace", skip_all)]
     fn diff_class_roles(&self, user: Option<&AuthenticatedUser>, diff: &mut RoleDiff) {
         diff.remove(self.unknown_class_role_id);
@@ -49,25 +105,8 @@ impl RolesDiffService {
             diff.remove(*role_id);
         }
 
-        if let Some(user) = user
-            && let Some(user_class_id) = user.class_id()
-        {
-            for (class_id, role) in &self.class_id_to_role_id {
-                if class_id.eq_ignore_ascii_case(user_class_id) {
-                    diff.assign(*role);
-                    break;
-                }
-            }
-        }
-
-        for (class_id, role_id) in &self.class_id_to_role_id {
-            if user
-                .and_then(|u| u.class_id().map(|c| c.eq_ignore_ascii_case(class_id)))
-                .unwrap_or(false)
-            {
-                diff.assign(*role_id);
-                break;
-            }
+        for role_id in &self.retired_class_role_ids {
+            diff.remove(*role_id);
         }
 
         if let Some(user) = user {
@@ -78,13 +117,39 @@ impl RolesDiffService {
         }
     }
 
+    #[instrument(level = "trace", skip_all)]
+    fn diff_year_roles(&self, user: Option<&AuthenticatedUser>, diff: &mut RoleDiff) {
+        let year = user.and_then(|u| u.class_id()).map(|c| c.year());
+
+        for (role_year, role_id) in &self.year_to_role_id {
+            if Some(*role_year) == year {
+                diff.assign(*role_id);
+            } else {
+                diff.remove(*role_id);
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    fn diff_field_roles(&self, user: Option<&AuthenticatedUser>, diff: &mut RoleDiff) {
+        let field = user.and_then(|u| u.class_id()).map(|c| c.field());
+
+        for (role_field, role_id) in &self.field_to_role_id {
+            if Some(*role_field) == field {
+                diff.assign(*role_id);
+            } else {
+                diff.remove(*role_id);
+            }
+        }
+    }
+
     #[instrument(level = "trace", skip_all)]
     fn get_class_role_id(&self, user: &AuthenticatedUser) -> Option<RoleId> {
         let class_id = user.class_id()?;
         let (_, role_id) = self
             .class_id_to_role_id
             .iter()
-            .find(|(c, _)| c.eq_ignore_ascii_case(class_id))
+            .find(|(c, _)| c == class_id)
             .or_else(|| {
                 error!(
                     "Not found class role for class id {:?} for user {:?}: {:?}",
@@ -0,0 +1 @@
+ALTER TABLE authenticated_users ADD COLUMN kind VARCHAR(16) NOT NULL DEFAULT 'student';
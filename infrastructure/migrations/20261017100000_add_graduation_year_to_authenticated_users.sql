@@ -0,0 +1,4 @@
+ALTER TABLE authenticated_users ADD COLUMN graduation_year INTEGER DEFAULT NULL;
+-- The graduation is only detected for the classes confirmed before the graduation date
+ALTER TABLE authenticated_users ADD COLUMN class_confirmed_at TIMESTAMP WITHOUT TIME ZONE DEFAULT NULL;
+UPDATE authenticated_users SET class_confirmed_at = NOW() AT TIME ZONE 'UTC' WHERE class_id IS NOT NULL;
@@ -0,0 +1,4 @@
+ALTER TABLE user_authentication_requests ADD COLUMN expires_at TIMESTAMP WITHOUT TIME ZONE DEFAULT NULL;
+-- Matches the default AUTHENTICATION_REQUEST_TTL_MINUTES
+UPDATE user_authentication_requests SET expires_at = requested_at + INTERVAL '30 minutes';
+ALTER TABLE user_authentication_requests ALTER COLUMN expires_at SET NOT NULL;
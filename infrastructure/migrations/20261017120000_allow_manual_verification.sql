@@ -0,0 +1,10 @@
+ALTER TABLE authenticated_users ALTER COLUMN access_token DROP NOT NULL;
+ALTER TABLE authenticated_users ALTER COLUMN refresh_token DROP NOT NULL;
+ALTER TABLE authenticated_users ALTER COLUMN access_token_expires_at DROP NOT NULL;
+ALTER TABLE authenticated_users ALTER COLUMN access_token_expires_at DROP DEFAULT;
+ALTER TABLE authenticated_users ADD COLUMN manually_verified_by BIGINT DEFAULT NULL;
+ALTER TABLE authenticated_users ADD COLUMN manual_verification_reason TEXT DEFAULT NULL;
+
+ALTER TABLE archived_authenticated_users ALTER COLUMN access_token DROP NOT NULL;
+ALTER TABLE archived_authenticated_users ALTER COLUMN refresh_token DROP NOT NULL;
+ALTER TABLE archived_authenticated_users ALTER COLUMN access_token_expires_at DROP NOT NULL;
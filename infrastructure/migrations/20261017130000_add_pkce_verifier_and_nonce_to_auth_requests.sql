@@ -0,0 +1,2 @@
+ALTER TABLE user_authentication_requests ADD COLUMN pkce_verifier TEXT DEFAULT NULL;
+ALTER TABLE user_authentication_requests ADD COLUMN nonce TEXT DEFAULT NULL;
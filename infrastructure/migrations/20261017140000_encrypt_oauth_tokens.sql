@@ -0,0 +1,2 @@
+ALTER TABLE authenticated_users ADD COLUMN token_key_id VARCHAR(64) DEFAULT NULL;
+ALTER TABLE archived_authenticated_users ADD COLUMN token_key_id VARCHAR(64) DEFAULT NULL;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS idx_archived_authenticated_users_email ON archived_authenticated_users (lower(email));
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS classes
+(
+    class_id     VARCHAR(16) NOT NULL,
+    display_name VARCHAR(64) NOT NULL,
+    role_id      BIGINT,
+    year         INTEGER     NOT NULL,
+    field        VARCHAR(16),
+    active       BOOLEAN     NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (class_id)
+);
+
+-- Seed the catalog with the classes that were hard-coded in the application and have
+-- verified students, the roles are looked up by the display name on the next role sync.
+-- The other classes are added by the admins, so no roles are created for classes that do not exist
+INSERT INTO classes (class_id, display_name, year, field)
+SELECT prefix || year || suffix, upper(prefix || year || suffix), year, NULLIF(prefix, '')
+FROM unnest(ARRAY ['', 'c', 'h', 'g', 'l']) AS prefix,
+     generate_series(1, 4) AS year,
+     unnest(ARRAY ['a', 'b', 'c', 'd', 'g', 'ga', 'gb', 'k']) AS suffix
+WHERE prefix || year || suffix IN (SELECT DISTINCT class_id FROM authenticated_users WHERE class_id IS NOT NULL)
+ON CONFLICT (class_id) DO NOTHING;
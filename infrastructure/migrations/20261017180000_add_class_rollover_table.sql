@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS class_rollover
+(
+    user_id  BIGINT                      NOT NULL,
+    class_id VARCHAR(16)                 NOT NULL,
+    taken_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id)
+);
@@ -0,0 +1,3 @@
+-- Groups referenced by the group role rules which the user is a member of
+ALTER TABLE authenticated_users
+    ADD COLUMN group_ids TEXT[] NOT NULL DEFAULT '{}';
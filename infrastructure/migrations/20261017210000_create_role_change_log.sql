@@ -0,0 +1,13 @@
+-- Audit log of the role changes applied by the role sync
+CREATE TABLE role_change_log
+(
+    id           BIGSERIAL PRIMARY KEY,
+    user_id      BIGINT    NOT NULL,
+    assigned     BIGINT[]  NOT NULL,
+    removed      BIGINT[]  NOT NULL,
+    reason       TEXT      NOT NULL,
+    low_priority BOOLEAN   NOT NULL,
+    changed_at   TIMESTAMP NOT NULL
+);
+
+CREATE INDEX role_change_log_user_id_changed_at_idx ON role_change_log (user_id, changed_at DESC);
@@ -0,0 +1,10 @@
+-- Nickname preferences of the users, kept when the user verifies again
+CREATE TABLE nickname_settings
+(
+    user_id          BIGINT PRIMARY KEY,
+    opt_out          BOOLEAN NOT NULL DEFAULT FALSE,
+    -- NULL follows the preference of the user
+    admin_override   BOOLEAN,
+    -- Nickname last set by the bot, NULL when the bot has not renamed the user
+    applied_nickname VARCHAR(32)
+);
@@ -0,0 +1,11 @@
+-- Members whose roles are never changed by the role sync, by their user ID or by one of their roles
+CREATE TABLE role_sync_exemptions
+(
+    -- Either 'user' or 'role'
+    kind       TEXT      NOT NULL,
+    target_id  BIGINT    NOT NULL,
+    reason     TEXT      NOT NULL,
+    created_by BIGINT    NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (kind, target_id)
+);
@@ -0,0 +1,3 @@
+-- What the sync was requested by, the role changes are logged with it
+ALTER TABLE role_sync_requested ADD COLUMN triggered_by VARCHAR(32) NOT NULL DEFAULT 'periodic';
+ALTER TABLE user_info_sync_requested ADD COLUMN triggered_by VARCHAR(32) NOT NULL DEFAULT 'periodic';
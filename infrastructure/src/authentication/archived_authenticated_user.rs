@@ -1,24 +1,62 @@
+use crate::authentication::oauth_token::{
+    DbOAuthToken, TokenRow, db_to_domain_oauth_token, domain_to_db_oauth_token,
+};
+use crate::encryption::{TokenCipher, TokenCipherError};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use domain::authentication::archived_authenticated_user::{
     ArchivedAuthenticatedUser, ArchivedAuthenticatedUserRepository,
     ArchivedAuthenticatedUserRepositoryError, ArchivedAuthenticatedUserSnapshot,
 };
-use domain::ports::oauth::OAuthToken;
 use domain_shared::authentication::ArchivedUserId;
+use domain_shared::discord::UserId;
 use sqlx::{PgPool, query};
 use tracing::{instrument, warn};
 
 pub struct PostgresArchivedAuthenticatedUserRepository<'a> {
     pool: &'a PgPool,
+    token_cipher: &'a TokenCipher,
 }
 
 impl<'a> PostgresArchivedAuthenticatedUserRepository<'a> {
     #[instrument(level = "trace", skip_all)]
-    pub fn new(pool: &'a PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: &'a PgPool, token_cipher: &'a TokenCipher) -> Self {
+        Self { pool, token_cipher }
     }
 }
 
+macro_rules! record_to_archived_user {
+    ($record:ident, $token_cipher:expr) => {
+        db_to_domain_oauth_token(
+            $token_cipher,
+            TokenRow::ArchivedAuthenticatedUser(ArchivedUserId(
+                UserId($record.user_id as u64),
+                $record.archived_at.and_utc(),
+            )),
+            DbOAuthToken {
+                access_token: $record.access_token,
+                access_token_expires_at: $record.access_token_expires_at,
+                refresh_token: $record.refresh_token,
+                token_key_id: $record.token_key_id,
+            },
+        )
+        .map(|oauth_token| {
+            ArchivedAuthenticatedUser::from_snapshot(ArchivedAuthenticatedUserSnapshot {
+                archived_user_id: ArchivedUserId(
+                    UserId($record.user_id as u64),
+                    $record.archived_at.and_utc(),
+                ),
+                name: $record.name,
+                email: $record.email,
+                oauth_token,
+                class_id: $record.class_id,
+                authenticated_at: $record.authenticated_at.and_utc(),
+            })
+        })
+        .map_err(map_cipher_err)
+    };
+}
+
 #[async_trait]
 impl<'a> ArchivedAuthenticatedUserRepository for PostgresArchivedAuthenticatedUserRepository<'a> {
     #[instrument(level = "debug", err, skip(self, user))]
@@ -30,35 +68,157 @@ impl<'a> ArchivedAuthenticatedUserRepository for PostgresArchivedAuthenticatedUs
             archived_user_id: ArchivedUserId(user_id, archived_at),
             name,
             email,
-            oauth_token:
-                OAuthToken {
-                    access_token,
-                    expires_at: access_token_expires_at,
-                    refresh_token,
-                },
+            oauth_token,
             class_id,
             authenticated_at,
         } = user.to_snapshot();
+        let DbOAuthToken {
+            access_token,
+            access_token_expires_at,
+            refresh_token,
+            token_key_id,
+        } = domain_to_db_oauth_token(
+            self.token_cipher,
+            TokenRow::ArchivedAuthenticatedUser(ArchivedUserId(user_id, archived_at)),
+            oauth_token,
+        )
+        .map_err(map_cipher_err)?;
 
         query!(
-            "INSERT INTO archived_authenticated_users (user_id, archived_at, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            "INSERT INTO archived_authenticated_users (user_id, archived_at, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at, token_key_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
             user_id.0 as i64,
             archived_at.naive_utc(),
             name.clone(),
             email.clone(),
-            access_token.0,
-            access_token_expires_at.naive_utc(),
-            refresh_token.0,
+            access_token,
+            access_token_expires_at,
+            refresh_token,
             class_id,
             authenticated_at.naive_utc(),
+            token_key_id,
         ).execute(self.pool).await.map_err(map_err)?;
 
         Ok(())
     }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<ArchivedAuthenticatedUser>, ArchivedAuthenticatedUserRepositoryError> {
+        let rows = query!(
+            "SELECT user_id, archived_at, name, email, access_token, access_token_expires_at, refresh_token, token_key_id, class_id, authenticated_at FROM archived_authenticated_users WHERE user_id = $1 ORDER BY archived_at DESC",
+            user_id.0 as i64,
+        ).fetch_all(self.pool).await.map_err(map_err)?;
+
+        rows.into_iter()
+            .map(|row| record_to_archived_user!(row, self.token_cipher))
+            .collect()
+    }
+
+    #[instrument(level = "debug", err, skip(self, email))]
+    async fn find_by_email(
+        &self,
+        email: &str,
+    ) -> Result<Vec<ArchivedAuthenticatedUser>, ArchivedAuthenticatedUserRepositoryError> {
+        let rows = query!(
+            "SELECT user_id, archived_at, name, email, access_token, access_token_expires_at, refresh_token, token_key_id, class_id, authenticated_at FROM archived_authenticated_users WHERE lower(email) = lower($1) ORDER BY archived_at DESC",
+            email,
+        ).fetch_all(self.pool).await.map_err(map_err)?;
+
+        rows.into_iter()
+            .map(|row| record_to_archived_user!(row, self.token_cipher))
+            .collect()
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove(
+        &self,
+        archived_user_id: ArchivedUserId,
+    ) -> Result<(), ArchivedAuthenticatedUserRepositoryError> {
+        let ArchivedUserId(user_id, archived_at) = archived_user_id;
+        query!(
+            "DELETE FROM archived_authenticated_users WHERE user_id = $1 AND archived_at = $2",
+            user_id.0 as i64,
+            archived_at.naive_utc(),
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn count_with_oauth_token(
+        &self,
+    ) -> Result<u64, ArchivedAuthenticatedUserRepositoryError> {
+        let count = query!(
+            "SELECT COUNT(*) AS \"count!\" FROM archived_authenticated_users WHERE access_token IS NOT NULL OR refresh_token IS NOT NULL",
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(map_err)?
+        .count;
+
+        Ok(count as u64)
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove_oauth_tokens(&self) -> Result<u64, ArchivedAuthenticatedUserRepositoryError> {
+        let result = query!(
+            "UPDATE archived_authenticated_users SET access_token = NULL, access_token_expires_at = NULL, refresh_token = NULL, token_key_id = NULL
+                WHERE access_token IS NOT NULL OR refresh_token IS NOT NULL",
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn count_archived_before(
+        &self,
+        archived_before: DateTime<Utc>,
+    ) -> Result<u64, ArchivedAuthenticatedUserRepositoryError> {
+        let count = query!(
+            "SELECT COUNT(*) AS \"count!\" FROM archived_authenticated_users WHERE archived_at < $1",
+            archived_before.naive_utc(),
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(map_err)?
+        .count;
+
+        Ok(count as u64)
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove_archived_before(
+        &self,
+        archived_before: DateTime<Utc>,
+    ) -> Result<u64, ArchivedAuthenticatedUserRepositoryError> {
+        let result = query!(
+            "DELETE FROM archived_authenticated_users WHERE archived_at < $1",
+            archived_before.naive_utc(),
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected())
+    }
 }
 
 #[instrument(level = "trace", skip_all)]
 fn map_err(err: sqlx::Error) -> ArchivedAuthenticatedUserRepositoryError {
-    warn!(error = ?err, "Failed to save archived authenticated user");
+    warn!(error = ?err, "Failed to access archived authenticated users");
+    ArchivedAuthenticatedUserRepositoryError::ServiceUnavailable
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_cipher_err(err: TokenCipherError) -> ArchivedAuthenticatedUserRepositoryError {
+    warn!(error = ?err, "Failed to encrypt or decrypt the OAuth token of an archived authenticated user");
     ArchivedAuthenticatedUserRepositoryError::ServiceUnavailable
 }
@@ -1,42 +1,99 @@
+use crate::authentication::oauth_token::{
+    DbOAuthToken, TokenRow, db_to_domain_oauth_token, domain_to_db_oauth_token,
+};
+use crate::authentication::user_kind::{db_to_domain_user_kind, domain_to_db_user_kind};
+use crate::class::class_id::{db_to_domain_class_id, domain_to_db_class_id};
+use crate::encryption::{TokenCipher, TokenCipherError};
 use async_trait::async_trait;
 use domain::authentication::authenticated_user::{
     AuthenticatedUser, AuthenticatedUserRepository, AuthenticatedUserRepositoryError,
-    AuthenticatedUserSnapshot,
+    AuthenticatedUserSnapshot, ManualVerification,
 };
-use domain::ports::oauth::OAuthToken;
-use domain_shared::authentication::{AccessToken, RefreshToken};
 use domain_shared::discord::UserId;
 use sqlx::{PgPool, query};
-use tracing::{instrument, warn};
+use tracing::{error, instrument, warn};
 
 pub struct PostgresAuthenticatedUserRepository<'a> {
     pool: &'a PgPool,
+    token_cipher: &'a TokenCipher,
 }
 
 impl<'a> PostgresAuthenticatedUserRepository<'a> {
     #[instrument(level = "trace", skip_all)]
-    pub fn new(pool: &'a PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: &'a PgPool, token_cipher: &'a TokenCipher) -> Self {
+        Self { pool, token_cipher }
     }
 }
 
 macro_rules! record_to_user {
-    ($record:ident) => {
-        AuthenticatedUser::from_snapshot(AuthenticatedUserSnapshot {
-            user_id: UserId($record.user_id as u64),
-            name: $record.name,
-            email: $record.email,
-            oauth_token: OAuthToken {
-                access_token: AccessToken($record.access_token),
-                expires_at: $record.access_token_expires_at.and_utc(),
-                refresh_token: RefreshToken($record.refresh_token),
+    ($record:ident, $token_cipher:expr) => {
+        db_to_domain_oauth_token(
+            $token_cipher,
+            TokenRow::AuthenticatedUser(UserId($record.user_id as u64)),
+            DbOAuthToken {
+                access_token: $record.access_token,
+                access_token_expires_at: $record.access_token_expires_at,
+                refresh_token: $record.refresh_token,
+                token_key_id: $record.token_key_id,
             },
-            class_id: $record.class_id,
-            authenticated_at: $record.authenticated_at.and_utc(),
+        )
+        .map(|oauth_token| {
+            AuthenticatedUser::from_snapshot(AuthenticatedUserSnapshot {
+                user_id: UserId($record.user_id as u64),
+                name: $record.name,
+                email: $record.email,
+                kind: db_to_domain_user_kind(&$record.kind),
+                oauth_token,
+                manual_verification: $record.manually_verified_by.map(|verified_by| {
+                    ManualVerification {
+                        verified_by: UserId(verified_by as u64),
+                        reason: $record.manual_verification_reason.unwrap_or_default(),
+                    }
+                }),
+                class_id: $record.class_id.as_deref().and_then(db_to_domain_class_id),
+                class_confirmed_at: $record
+                    .class_confirmed_at
+                    .map(|class_confirmed_at| class_confirmed_at.and_utc()),
+                group_ids: $record.group_ids,
+                graduation_year: $record.graduation_year,
+                authenticated_at: $record.authenticated_at.and_utc(),
+            })
         })
+        .map_err(map_cipher_err)
     };
 }
 
+/// Converts the records to users, skipping the users whose token cannot be decrypted, e.g. with
+/// a key that is no longer configured, so that a single bad row does not make all the users
+/// unavailable. The skipped users are reported as errors, as they are left out of the syncs.
+macro_rules! records_to_users {
+    ($records:ident, $token_cipher:expr) => {{
+        let mut skipped = 0;
+        let users = $records
+            .into_iter()
+            .filter_map(|record| {
+                let user_id = record.user_id;
+                record_to_user!(record, $token_cipher)
+                    .inspect_err(|_| {
+                        skipped += 1;
+                        error!(
+                            user_id,
+                            "Skipping authenticated user whose OAuth token cannot be decrypted",
+                        );
+                    })
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        if skipped > 0 {
+            error!(
+                skipped,
+                "Skipped {} authenticated users whose OAuth token cannot be decrypted", skipped,
+            );
+        }
+        users
+    }};
+}
+
 #[async_trait]
 impl<'a> AuthenticatedUserRepository for PostgresAuthenticatedUserRepository<'a> {
     #[instrument(level = "debug", err, skip(self, user))]
@@ -45,30 +102,55 @@ impl<'a> AuthenticatedUserRepository for PostgresAuthenticatedUserRepository<'a>
             user_id,
             name,
             email,
-            oauth_token:
-                OAuthToken {
-                    access_token,
-                    expires_at: access_token_expires_at,
-                    refresh_token,
-                },
+            kind,
+            oauth_token,
+            manual_verification,
             class_id,
+            class_confirmed_at,
+            group_ids,
+            graduation_year,
             authenticated_at,
         } = user.to_snapshot();
+        let DbOAuthToken {
+            access_token,
+            access_token_expires_at,
+            refresh_token,
+            token_key_id,
+        } = domain_to_db_oauth_token(
+            self.token_cipher,
+            TokenRow::AuthenticatedUser(user_id),
+            oauth_token,
+        )
+        .map_err(map_cipher_err)?;
+        let (manually_verified_by, manual_verification_reason) = match manual_verification {
+            Some(ManualVerification {
+                verified_by,
+                reason,
+            }) => (Some(verified_by.0 as i64), Some(reason)),
+            None => (None, None),
+        };
 
         query!(
             "INSERT INTO authenticated_users
-                (user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                (user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at, kind, graduation_year, manually_verified_by, manual_verification_reason, token_key_id, group_ids, class_confirmed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
             ON CONFLICT (user_id) DO UPDATE SET
-                name = $2, email = $3, access_token = $4, access_token_expires_at = $5, refresh_token = $6, class_id = $7, authenticated_at = $8",
+                name = $2, email = $3, access_token = $4, access_token_expires_at = $5, refresh_token = $6, class_id = $7, authenticated_at = $8, kind = $9, graduation_year = $10, manually_verified_by = $11, manual_verification_reason = $12, token_key_id = $13, group_ids = $14, class_confirmed_at = $15",
             user_id.0 as i64,
             name.clone(),
             email.clone(),
-            access_token.0,
-            access_token_expires_at.naive_utc(),
-            refresh_token.0,
-            class_id,
+            access_token,
+            access_token_expires_at,
+            refresh_token,
+            class_id.as_ref().map(domain_to_db_class_id),
             authenticated_at.naive_utc(),
+            domain_to_db_user_kind(kind),
+            graduation_year,
+            manually_verified_by,
+            manual_verification_reason,
+            token_key_id,
+            &group_ids,
+            class_confirmed_at.map(|class_confirmed_at| class_confirmed_at.naive_utc()),
         ).execute(self.pool).await.map_err(map_err)?;
 
         Ok(())
@@ -90,10 +172,9 @@ impl<'a> AuthenticatedUserRepository for PostgresAuthenticatedUserRepository<'a>
     #[instrument(level = "debug", err, skip(self))]
     async fn find_all(&self) -> Result<Vec<AuthenticatedUser>, AuthenticatedUserRepositoryError> {
         let rows = query!(
-            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at FROM authenticated_users",
+            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at, kind, graduation_year, manually_verified_by, manual_verification_reason, token_key_id, group_ids, class_confirmed_at FROM authenticated_users",
         ).fetch_all(self.pool).await.map_err(map_err)?;
-        let users = rows.into_iter().map(|row| record_to_user!(row)).collect();
-        Ok(users)
+        Ok(records_to_users!(rows, self.token_cipher))
     }
 
     #[instrument(level = "debug", err, skip(self, user_id))]
@@ -102,12 +183,12 @@ impl<'a> AuthenticatedUserRepository for PostgresAuthenticatedUserRepository<'a>
         user_id: UserId,
     ) -> Result<Option<AuthenticatedUser>, AuthenticatedUserRepositoryError> {
         let row = query!(
-            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at FROM authenticated_users WHERE user_id = $1",
+            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at, kind, graduation_year, manually_verified_by, manual_verification_reason, token_key_id, group_ids, class_confirmed_at FROM authenticated_users WHERE user_id = $1",
             user_id.0 as i64,
         ).fetch_optional(self.pool).await.map_err(map_err)?;
 
         if let Some(row) = row {
-            Ok(Some(record_to_user!(row)))
+            Ok(Some(record_to_user!(row, self.token_cipher)?))
         } else {
             Ok(None)
         }
@@ -119,16 +200,51 @@ impl<'a> AuthenticatedUserRepository for PostgresAuthenticatedUserRepository<'a>
         email: &str,
     ) -> Result<Option<AuthenticatedUser>, AuthenticatedUserRepositoryError> {
         let row = query!(
-            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at FROM authenticated_users WHERE email = $1",
+            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at, kind, graduation_year, manually_verified_by, manual_verification_reason, token_key_id, group_ids, class_confirmed_at FROM authenticated_users WHERE email = $1",
             email,
         ).fetch_optional(self.pool).await.map_err(map_err)?;
 
         if let Some(row) = row {
-            Ok(Some(record_to_user!(row)))
+            Ok(Some(record_to_user!(row, self.token_cipher)?))
         } else {
             Ok(None)
         }
     }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn search(
+        &self,
+        query: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<AuthenticatedUser>, AuthenticatedUserRepositoryError> {
+        let rows = query!(
+            "SELECT user_id, name, email, access_token, access_token_expires_at, refresh_token, class_id, authenticated_at, kind, graduation_year, manually_verified_by, manual_verification_reason, token_key_id, group_ids, class_confirmed_at FROM authenticated_users
+                WHERE lower(unaccent(name)) LIKE lower(unaccent($1)) OR lower(email) LIKE $1 OR lower(class_id) LIKE $1
+                ORDER BY lower(unaccent(name)), user_id
+                LIMIT $2 OFFSET $3",
+            search_pattern(query),
+            limit as i64,
+            offset as i64,
+        ).fetch_all(self.pool).await.map_err(map_err)?;
+
+        Ok(records_to_users!(rows, self.token_cipher))
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn count_search(&self, query: &str) -> Result<u64, AuthenticatedUserRepositoryError> {
+        let count = query!(
+            "SELECT COUNT(*) AS \"count!\" FROM authenticated_users
+                WHERE lower(unaccent(name)) LIKE lower(unaccent($1)) OR lower(email) LIKE $1 OR lower(class_id) LIKE $1",
+            search_pattern(query),
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(map_err)?
+        .count;
+
+        Ok(count as u64)
+    }
 }
 
 #[instrument(level = "trace", skip_all)]
@@ -139,3 +255,24 @@ fn map_err(err: sqlx::Error) -> AuthenticatedUserRepositoryError {
     );
     AuthenticatedUserRepositoryError::ServiceUnavailable
 }
+
+#[instrument(level = "trace", skip_all)]
+fn map_cipher_err(err: TokenCipherError) -> AuthenticatedUserRepositoryError {
+    warn!(
+        error = ?err,
+        "Failed to encrypt or decrypt the OAuth token of an authenticated user",
+    );
+    AuthenticatedUserRepositoryError::ServiceUnavailable
+}
+
+/// Creates a `LIKE` pattern matching the lowercase query anywhere in the value
+#[instrument(level = "trace", skip_all)]
+fn search_pattern(query: &str) -> String {
+    let escaped = query
+        .trim()
+        .to_lowercase()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
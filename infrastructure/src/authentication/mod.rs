@@ -1,3 +1,6 @@
 pub mod archived_authenticated_user;
 pub mod authenticated_user;
+mod oauth_token;
+pub mod token_reencryption;
 pub mod user_authentication_request;
+mod user_kind;
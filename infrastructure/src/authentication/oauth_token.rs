@@ -0,0 +1,108 @@
+use crate::encryption::{TokenCipher, TokenCipherError};
+use chrono::NaiveDateTime;
+use domain::ports::oauth::OAuthToken;
+use domain_shared::authentication::{AccessToken, ArchivedUserId, RefreshToken};
+use domain_shared::discord::UserId;
+use tracing::instrument;
+
+pub const ACCESS_TOKEN_COLUMN: &str = "access_token";
+pub const REFRESH_TOKEN_COLUMN: &str = "refresh_token";
+
+/// Row the token is stored in, it is bound to the ciphertext together with the column,
+/// so that a sealed token copied to the row of another user fails to decrypt
+#[derive(Clone, Copy, Debug)]
+pub enum TokenRow {
+    AuthenticatedUser(UserId),
+    ArchivedAuthenticatedUser(ArchivedUserId),
+}
+
+impl TokenRow {
+    #[instrument(level = "trace")]
+    pub fn associated_data(self, column: &str) -> String {
+        match self {
+            Self::AuthenticatedUser(user_id) => {
+                format!("authenticated_users:{}:{}", user_id.0, column)
+            }
+            // The database keeps the archival time in microseconds
+            Self::ArchivedAuthenticatedUser(ArchivedUserId(user_id, archived_at)) => format!(
+                "archived_authenticated_users:{}:{}:{}",
+                user_id.0,
+                archived_at.timestamp_micros(),
+                column,
+            ),
+        }
+    }
+}
+
+pub struct DbOAuthToken {
+    pub access_token: Option<String>,
+    pub access_token_expires_at: Option<NaiveDateTime>,
+    pub refresh_token: Option<String>,
+    pub token_key_id: Option<String>,
+}
+
+/// Splits the token into the `access_token`, `access_token_expires_at`, `refresh_token` and
+/// `token_key_id` columns, encrypting both tokens with the current key
+#[instrument(level = "trace", skip(cipher, oauth_token))]
+pub fn domain_to_db_oauth_token(
+    cipher: &TokenCipher,
+    row: TokenRow,
+    oauth_token: Option<OAuthToken>,
+) -> Result<DbOAuthToken, TokenCipherError> {
+    match oauth_token {
+        Some(OAuthToken {
+            access_token,
+            expires_at,
+            refresh_token,
+        }) => Ok(DbOAuthToken {
+            access_token: Some(
+                cipher.encrypt(&access_token.0, &row.associated_data(ACCESS_TOKEN_COLUMN))?,
+            ),
+            access_token_expires_at: Some(expires_at.naive_utc()),
+            refresh_token: Some(
+                cipher.encrypt(&refresh_token.0, &row.associated_data(REFRESH_TOKEN_COLUMN))?,
+            ),
+            token_key_id: Some(cipher.current_key_id().to_string()),
+        }),
+        None => Ok(DbOAuthToken {
+            access_token: None,
+            access_token_expires_at: None,
+            refresh_token: None,
+            token_key_id: None,
+        }),
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+pub fn db_to_domain_oauth_token(
+    cipher: &TokenCipher,
+    row: TokenRow,
+    db_oauth_token: DbOAuthToken,
+) -> Result<Option<OAuthToken>, TokenCipherError> {
+    let DbOAuthToken {
+        access_token,
+        access_token_expires_at,
+        refresh_token,
+        token_key_id,
+    } = db_oauth_token;
+
+    match (access_token, access_token_expires_at, refresh_token) {
+        (Some(access_token), Some(expires_at), Some(refresh_token)) => {
+            let key_id = token_key_id.as_deref();
+            Ok(Some(OAuthToken {
+                access_token: AccessToken(cipher.decrypt(
+                    key_id,
+                    &access_token,
+                    &row.associated_data(ACCESS_TOKEN_COLUMN),
+                )?),
+                expires_at: expires_at.and_utc(),
+                refresh_token: RefreshToken(cipher.decrypt(
+                    key_id,
+                    &refresh_token,
+                    &row.associated_data(REFRESH_TOKEN_COLUMN),
+                )?),
+            }))
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,134 @@
+use crate::authentication::oauth_token::{ACCESS_TOKEN_COLUMN, REFRESH_TOKEN_COLUMN, TokenRow};
+use crate::encryption::{TokenCipher, TokenCipherError};
+use domain_shared::authentication::ArchivedUserId;
+use domain_shared::discord::UserId;
+use sqlx::{PgPool, query};
+use thiserror::Error;
+use tracing::{info, instrument};
+
+#[derive(Debug, Error)]
+pub enum TokenReencryptionError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Failed to re-encrypt the token of user {0}: {1}")]
+    Cipher(u64, TokenCipherError),
+}
+
+#[derive(Debug, Default)]
+pub struct TokenReencryptionSummary {
+    pub authenticated_users: u64,
+    pub archived_authenticated_users: u64,
+}
+
+/// Re-encrypts the tokens of all rows not encrypted with the current key, including the ones
+/// stored in plain text before the encryption was introduced
+#[instrument(level = "info", err, skip_all)]
+pub async fn reencrypt_oauth_tokens(
+    pool: &PgPool,
+    token_cipher: &TokenCipher,
+) -> Result<TokenReencryptionSummary, TokenReencryptionError> {
+    let current_key_id = token_cipher.current_key_id();
+    let mut summary = TokenReencryptionSummary::default();
+    let mut transaction = pool.begin().await?;
+
+    let rows = query!(
+        "SELECT user_id, access_token, refresh_token, token_key_id FROM authenticated_users
+            WHERE access_token IS NOT NULL AND refresh_token IS NOT NULL AND token_key_id IS DISTINCT FROM $1
+            FOR UPDATE",
+        current_key_id,
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+    for row in rows {
+        let (access_token, refresh_token) = reencrypt(
+            token_cipher,
+            TokenRow::AuthenticatedUser(UserId(row.user_id as u64)),
+            row.token_key_id.as_deref(),
+            row.access_token.as_deref().unwrap_or_default(),
+            row.refresh_token.as_deref().unwrap_or_default(),
+        )?;
+        query!(
+            "UPDATE authenticated_users SET access_token = $2, refresh_token = $3, token_key_id = $4 WHERE user_id = $1",
+            row.user_id,
+            access_token,
+            refresh_token,
+            current_key_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+        summary.authenticated_users += 1;
+    }
+
+    let rows = query!(
+        "SELECT user_id, archived_at, access_token, refresh_token, token_key_id FROM archived_authenticated_users
+            WHERE access_token IS NOT NULL AND refresh_token IS NOT NULL AND token_key_id IS DISTINCT FROM $1
+            FOR UPDATE",
+        current_key_id,
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+    for row in rows {
+        let (access_token, refresh_token) = reencrypt(
+            token_cipher,
+            TokenRow::ArchivedAuthenticatedUser(ArchivedUserId(
+                UserId(row.user_id as u64),
+                row.archived_at.and_utc(),
+            )),
+            row.token_key_id.as_deref(),
+            row.access_token.as_deref().unwrap_or_default(),
+            row.refresh_token.as_deref().unwrap_or_default(),
+        )?;
+        query!(
+            "UPDATE archived_authenticated_users SET access_token = $3, refresh_token = $4, token_key_id = $5 WHERE user_id = $1 AND archived_at = $2",
+            row.user_id,
+            row.archived_at,
+            access_token,
+            refresh_token,
+            current_key_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+        summary.archived_authenticated_users += 1;
+    }
+
+    transaction.commit().await?;
+
+    info!(
+        authenticated_users = summary.authenticated_users,
+        archived_authenticated_users = summary.archived_authenticated_users,
+        "Re-encrypted OAuth tokens with key {}",
+        current_key_id,
+    );
+
+    Ok(summary)
+}
+
+#[instrument(level = "trace", skip(token_cipher, access_token, refresh_token))]
+fn reencrypt(
+    token_cipher: &TokenCipher,
+    row: TokenRow,
+    key_id: Option<&str>,
+    access_token: &str,
+    refresh_token: &str,
+) -> Result<(String, String), TokenReencryptionError> {
+    let reencrypt_column = |token: &str, column: &str| {
+        let associated_data = row.associated_data(column);
+        let token = match key_id {
+            // The token was stored before the encryption was introduced
+            None => token.to_string(),
+            Some(_) => token_cipher.decrypt(key_id, token, &associated_data)?,
+        };
+        token_cipher.encrypt(&token, &associated_data)
+    };
+    let user_id = match row {
+        TokenRow::AuthenticatedUser(user_id) => user_id,
+        TokenRow::ArchivedAuthenticatedUser(ArchivedUserId(user_id, _)) => user_id,
+    };
+
+    let access_token = reencrypt_column(access_token, ACCESS_TOKEN_COLUMN)
+        .map_err(|err| TokenReencryptionError::Cipher(user_id.0, err))?;
+    let refresh_token = reencrypt_column(refresh_token, REFRESH_TOKEN_COLUMN)
+        .map_err(|err| TokenReencryptionError::Cipher(user_id.0, err))?;
+
+    Ok((access_token, refresh_token))
+}
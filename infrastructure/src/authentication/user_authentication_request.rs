@@ -1,9 +1,11 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use domain::authentication::user_authentication_request::{
     UserAuthenticationRequest, UserAuthenticationRequestRepository,
     UserAuthenticationRequestRepositoryError, UserAuthenticationRequestSnapshot,
 };
-use domain_shared::authentication::CsrfToken;
+use domain_shared::authentication::{CsrfToken, Nonce, PkceVerifier};
+use domain_shared::discord::UserId;
 use sqlx::{PgPool, query};
 use tracing::{instrument, warn};
 
@@ -18,6 +20,20 @@ impl<'a> PostgresUserAuthenticationRequestRepository<'a> {
     }
 }
 
+macro_rules! record_to_request {
+    ($record:ident) => {
+        UserAuthenticationRequest::from_snapshot(UserAuthenticationRequestSnapshot {
+            csrf_token: CsrfToken($record.csrf_token),
+            pkce_verifier: $record.pkce_verifier.map(PkceVerifier),
+            nonce: $record.nonce.map(Nonce),
+            user_id: UserId($record.user_id as u64),
+            requested_at: $record.requested_at.and_utc(),
+            expires_at: $record.expires_at.and_utc(),
+            confirmed_at: $record.confirmed_at.map(|t| t.and_utc()),
+        })
+    };
+}
+
 #[async_trait]
 impl<'a> UserAuthenticationRequestRepository for PostgresUserAuthenticationRequestRepository<'a> {
     #[instrument(level = "debug", err, skip(self, request))]
@@ -27,18 +43,24 @@ impl<'a> UserAuthenticationRequestRepository for PostgresUserAuthenticationReque
     ) -> Result<(), UserAuthenticationRequestRepositoryError> {
         let UserAuthenticationRequestSnapshot {
             csrf_token,
+            pkce_verifier,
+            nonce,
             user_id,
             requested_at,
+            expires_at,
             confirmed_at,
         } = request.to_snapshot();
 
         query!(
-            "INSERT INTO user_authentication_requests (csrf_token, user_id, requested_at, confirmed_at) VALUES ($1, $2, $3, $4)
-            ON CONFLICT (csrf_token) DO UPDATE SET user_id = $2, requested_at = $3, confirmed_at = $4",
+            "INSERT INTO user_authentication_requests (csrf_token, user_id, requested_at, expires_at, confirmed_at, pkce_verifier, nonce) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (csrf_token) DO UPDATE SET user_id = $2, requested_at = $3, expires_at = $4, confirmed_at = $5, pkce_verifier = $6, nonce = $7",
             csrf_token.0,
             user_id.0 as i64,
             requested_at.naive_utc(),
+            expires_at.naive_utc(),
             confirmed_at.map(|t| t.naive_utc()),
+            pkce_verifier.map(|v| v.0),
+            nonce.map(|n| n.0),
         ).execute(self.pool).await.map_err(map_err)?;
 
         Ok(())
@@ -50,25 +72,70 @@ impl<'a> UserAuthenticationRequestRepository for PostgresUserAuthenticationReque
         csrf_token: &CsrfToken,
     ) -> Result<Option<UserAuthenticationRequest>, UserAuthenticationRequestRepositoryError> {
         let row = query!(
-            "SELECT csrf_token, user_id, requested_at, confirmed_at FROM user_authentication_requests WHERE csrf_token = $1",
+            "SELECT csrf_token, user_id, requested_at, expires_at, confirmed_at, pkce_verifier, nonce FROM user_authentication_requests WHERE csrf_token = $1",
             csrf_token.0,
         )
         .fetch_optional(self.pool)
         .await.map_err(map_err)?;
 
         if let Some(row) = row {
-            Ok(Some(UserAuthenticationRequest::from_snapshot(
-                UserAuthenticationRequestSnapshot {
-                    csrf_token: CsrfToken(row.csrf_token),
-                    user_id: domain_shared::discord::UserId(row.user_id as u64),
-                    requested_at: row.requested_at.and_utc(),
-                    confirmed_at: row.confirmed_at.map(|t| t.and_utc()),
-                },
-            )))
+            Ok(Some(record_to_request!(row)))
         } else {
             Ok(None)
         }
     }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove_stale(
+        &self,
+        expired_before: DateTime<Utc>,
+        confirmed_before: DateTime<Utc>,
+    ) -> Result<u64, UserAuthenticationRequestRepositoryError> {
+        let result = query!(
+            "DELETE FROM user_authentication_requests WHERE expires_at <= $1 OR confirmed_at <= $2",
+            expired_before.naive_utc(),
+            confirmed_before.naive_utc(),
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<UserAuthenticationRequest>, UserAuthenticationRequestRepositoryError> {
+        let rows = query!(
+            "SELECT csrf_token, user_id, requested_at, expires_at, confirmed_at, pkce_verifier, nonce FROM user_authentication_requests WHERE user_id = $1 ORDER BY requested_at DESC",
+            user_id.0 as i64,
+        )
+        .fetch_all(self.pool)
+        .await.map_err(map_err)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| record_to_request!(row))
+            .collect())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<u64, UserAuthenticationRequestRepositoryError> {
+        let result = query!(
+            "DELETE FROM user_authentication_requests WHERE user_id = $1",
+            user_id.0 as i64,
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected())
+    }
 }
 
 #[instrument(level = "trace", skip_all)]
@@ -0,0 +1,25 @@
+use domain_shared::authentication::UserKind;
+use tracing::{instrument, warn};
+
+#[instrument(level = "trace", skip(kind))]
+pub fn domain_to_db_user_kind(kind: UserKind) -> &'static str {
+    match kind {
+        UserKind::Student => "student",
+        UserKind::Staff => "staff",
+    }
+}
+
+#[instrument(level = "trace", skip(kind))]
+pub fn db_to_domain_user_kind(kind: &str) -> UserKind {
+    match kind {
+        "student" => UserKind::Student,
+        "staff" => UserKind::Staff,
+        _ => {
+            warn!(
+                kind,
+                "Unknown user kind stored in the database, using student"
+            );
+            UserKind::Student
+        }
+    }
+}
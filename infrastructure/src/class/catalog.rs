@@ -0,0 +1,99 @@
+use crate::class::class_id::{db_to_domain_class_id, domain_to_db_class_id};
+use async_trait::async_trait;
+use domain::class::catalog::{Class, ClassRepository, ClassRepositoryError, ClassSnapshot};
+use domain::class::class_id::ClassId;
+use domain_shared::discord::RoleId;
+use sqlx::{PgPool, query};
+use tracing::{instrument, warn};
+
+pub struct PostgresClassRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PostgresClassRepository<'a> {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+macro_rules! record_to_class {
+    ($record:ident) => {
+        db_to_domain_class_id(&$record.class_id).map(|class_id| {
+            Class::from_snapshot(ClassSnapshot {
+                class_id,
+                display_name: $record.display_name,
+                role_id: $record.role_id.map(|role_id| RoleId(role_id as u64)),
+                active: $record.active,
+            })
+        })
+    };
+}
+
+#[async_trait]
+impl<'a> ClassRepository for PostgresClassRepository<'a> {
+    #[instrument(level = "debug", err, skip(self, class))]
+    async fn save(&self, class: &Class) -> Result<(), ClassRepositoryError> {
+        let snapshot = class.to_snapshot();
+
+        // The year and the field are parsed from the class ID, they are stored for reporting only
+        query!(
+            "INSERT INTO classes (class_id, display_name, role_id, year, field, active) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (class_id) DO UPDATE SET display_name = $2, role_id = $3, year = $4, field = $5, active = $6",
+            domain_to_db_class_id(&snapshot.class_id),
+            snapshot.display_name,
+            snapshot.role_id.map(|role_id| role_id.0 as i64),
+            snapshot.class_id.year() as i32,
+            snapshot
+                .class_id
+                .field()
+                .map(|field| field.prefix().to_string()),
+            snapshot.active,
+        )
+        .execute(self.pool)
+        .await
+        .map_err(|err| {
+            warn!(error = ?err, "Failed to save class");
+            ClassRepositoryError::ServiceUnavailable
+        })?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_all(&self) -> Result<Vec<Class>, ClassRepositoryError> {
+        let records = query!(
+            "SELECT class_id, display_name, role_id, active FROM classes ORDER BY class_id",
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(|err| {
+            warn!(error = ?err, "Failed to find classes");
+            ClassRepositoryError::ServiceUnavailable
+        })?;
+
+        // Classes not following the naming scheme cannot be matched with any user
+        Ok(records
+            .into_iter()
+            .filter_map(|record| record_to_class!(record))
+            .collect())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_by_class_id(
+        &self,
+        class_id: &ClassId,
+    ) -> Result<Option<Class>, ClassRepositoryError> {
+        let record = query!(
+            "SELECT class_id, display_name, role_id, active FROM classes WHERE class_id = $1",
+            domain_to_db_class_id(class_id),
+        )
+        .fetch_optional(self.pool)
+        .await
+        .map_err(|err| {
+            warn!(error = ?err, "Failed to find class by class ID");
+            ClassRepositoryError::ServiceUnavailable
+        })?;
+
+        Ok(record.and_then(|record| record_to_class!(record)))
+    }
+}
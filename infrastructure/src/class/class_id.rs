@@ -0,0 +1,19 @@
+use domain::class::class_id::ClassId;
+use tracing::{instrument, warn};
+
+#[instrument(level = "trace", skip(class_id))]
+pub fn domain_to_db_class_id(class_id: &ClassId) -> String {
+    class_id.to_string()
+}
+
+#[instrument(level = "trace", skip(class_id))]
+pub fn db_to_domain_class_id(class_id: &str) -> Option<ClassId> {
+    let parsed = ClassId::parse(class_id);
+    if parsed.is_none() {
+        warn!(
+            class_id,
+            "Invalid class ID stored in the database, ignoring it"
+        );
+    }
+    parsed
+}
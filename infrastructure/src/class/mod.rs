@@ -0,0 +1,3 @@
+pub mod catalog;
+pub(crate) mod class_id;
+pub mod rollover;
@@ -0,0 +1,91 @@
+use crate::class::class_id::{db_to_domain_class_id, domain_to_db_class_id};
+use async_trait::async_trait;
+use domain::class::rollover::{
+    ClassRolloverEntry, ClassRolloverRepository, ClassRolloverRepositoryError,
+};
+use domain_shared::discord::UserId;
+use sqlx::{PgPool, query};
+use tracing::{instrument, warn};
+
+pub struct PostgresClassRolloverRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PostgresClassRolloverRepository<'a> {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<'a> ClassRolloverRepository for PostgresClassRolloverRepository<'a> {
+    #[instrument(level = "debug", err, skip(self, entries))]
+    async fn replace_all(
+        &self,
+        entries: &[ClassRolloverEntry],
+    ) -> Result<(), ClassRolloverRepositoryError> {
+        let map_err = |err: sqlx::Error| {
+            warn!(error = ?err, "Failed to replace class rollover entries");
+            ClassRolloverRepositoryError::ServiceUnavailable
+        };
+
+        let user_ids = entries
+            .iter()
+            .map(|entry| entry.user_id.0 as i64)
+            .collect::<Vec<_>>();
+        let class_ids = entries
+            .iter()
+            .map(|entry| domain_to_db_class_id(&entry.class_id))
+            .collect::<Vec<_>>();
+        let taken_ats = entries
+            .iter()
+            .map(|entry| entry.taken_at.naive_utc())
+            .collect::<Vec<_>>();
+
+        let mut transaction = self.pool.begin().await.map_err(map_err)?;
+
+        query!("DELETE FROM class_rollover")
+            .execute(&mut *transaction)
+            .await
+            .map_err(map_err)?;
+
+        query!(
+            "INSERT INTO class_rollover (user_id, class_id, taken_at) SELECT * FROM UNNEST($1::BIGINT[], $2::VARCHAR[], $3::TIMESTAMP[])",
+            &user_ids,
+            &class_ids,
+            &taken_ats,
+        )
+        .execute(&mut *transaction)
+        .await
+        .map_err(map_err)?;
+
+        transaction.commit().await.map_err(map_err)?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_all(&self) -> Result<Vec<ClassRolloverEntry>, ClassRolloverRepositoryError> {
+        let records = query!(
+            "SELECT user_id, class_id, taken_at FROM class_rollover ORDER BY class_id, user_id",
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(|err| {
+            warn!(error = ?err, "Failed to find class rollover entries");
+            ClassRolloverRepositoryError::ServiceUnavailable
+        })?;
+
+        Ok(records
+            .into_iter()
+            .filter_map(|record| {
+                Some(ClassRolloverEntry {
+                    user_id: UserId(record.user_id as u64),
+                    class_id: db_to_domain_class_id(&record.class_id)?,
+                    taken_at: record.taken_at.and_utc(),
+                })
+            })
+            .collect())
+    }
+}
@@ -2,33 +2,83 @@ mod channel_id;
 mod create_attachment;
 mod create_button;
 mod create_message;
+mod role_cache;
 mod role_id;
 mod user_id;
 
 use crate::discord::channel_id::domain_to_serenity_channel_id;
 use crate::discord::create_message::domain_to_serenity_create_message;
+pub use crate::discord::role_cache::GuildRoleCache;
 use crate::discord::role_id::{domain_to_serenity_role_id, serenity_to_domain_role_id};
 use crate::discord::user_id::{domain_to_serenity_user_id, serenity_to_domain_user_id};
-use domain::ports::discord::{ChannelId, CreateMessage, DiscordPort, Role, RoleDiff};
+use domain::ports::discord::{
+    ChannelId, CreateMessage, DiscordPort, NicknameUpdate, Role, RoleDiff,
+};
 use domain::ports::discord::{DiscordError, Result};
 use domain_shared::discord::{RoleId, UserId};
-use poise::futures_util::stream::FuturesUnordered;
 use poise::serenity_prelude as serenity;
 use poise::serenity_prelude::GuildId;
 use serenity::all::{Builder, Http};
 use serenity::futures::StreamExt;
-use std::ops::Not;
+use std::collections::HashSet;
 use tracing::{error, instrument, warn};
 
 pub struct DiscordAdapter<'a> {
     client: &'a Http,
     guild_id: GuildId,
+    role_cache: &'a GuildRoleCache,
 }
 
 impl<'a> DiscordAdapter<'a> {
     #[instrument(level = "trace", skip_all)]
-    pub fn new(client: &'a Http, guild_id: GuildId) -> Self {
-        Self { client, guild_id }
+    pub fn new(client: &'a Http, guild_id: GuildId, role_cache: &'a GuildRoleCache) -> Self {
+        Self {
+            client,
+            guild_id,
+            role_cache,
+        }
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_member(
+        &self,
+        user_id: serenity::UserId,
+    ) -> Result<Option<serenity::Member>, DiscordError> {
+        match self.client.get_member(self.guild_id, user_id).await {
+            Ok(member) => Ok(Some(member)),
+            Err(err) => {
+                if let serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(err)) =
+                    &err
+                    && err.status_code == 404
+                {
+                    return Ok(None);
+                }
+                Err(map_serenity_err(err))
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn can_rename(&self, member: &serenity::Member) -> Result<bool, DiscordError> {
+        let guild = self
+            .role_cache
+            .get(self.client, self.guild_id)
+            .await
+            .map_err(map_serenity_err)?;
+        if guild.owner_id == member.user.id {
+            return Ok(false);
+        }
+
+        let bot = self
+            .role_cache
+            .get_bot(self.client, self.guild_id)
+            .await
+            .map_err(map_serenity_err)?;
+
+        // Discord only allows renaming members whose highest role is below the highest role of the bot,
+        // which also rules out the bot itself
+        Ok(member.user.id != bot.user_id
+            && guild.highest_position(&member.roles) < bot.highest_position)
     }
 }
 
@@ -63,17 +113,17 @@ impl<'a> DiscordPort for DiscordAdapter<'a> {
         role_name: &str,
         reason: &str,
     ) -> Result<Role, DiscordError> {
-        let roles = self
-            .client
-            .get_guild_roles(self.guild_id)
+        let guild = self
+            .role_cache
+            .get(self.client, self.guild_id)
             .await
             .map_err(map_serenity_err)?;
 
-        for role in roles {
+        for role in guild.roles.values() {
             if role.name.eq_ignore_ascii_case(role_name) {
                 return Ok(Role {
                     role_id: serenity_to_domain_role_id(role.id),
-                    name: role.name,
+                    name: role.name.clone(),
                 });
             }
         }
@@ -90,6 +140,7 @@ impl<'a> DiscordPort for DiscordAdapter<'a> {
             )
             .await
             .map_err(map_serenity_err)?;
+        self.role_cache.invalidate();
 
         Ok(Role {
             role_id: serenity_to_domain_role_id(role.id),
@@ -97,6 +148,29 @@ impl<'a> DiscordPort for DiscordAdapter<'a> {
         })
     }
 
+    #[instrument(level = "debug", err, skip_all)]
+    async fn rename_role(
+        &self,
+        role_id: RoleId,
+        role_name: &str,
+        reason: &str,
+    ) -> Result<(), DiscordError> {
+        let role_id = domain_to_serenity_role_id(role_id);
+
+        self.client
+            .edit_role(
+                self.guild_id,
+                role_id,
+                &serenity::EditRole::new().name(role_name),
+                Some(reason),
+            )
+            .await
+            .map_err(map_serenity_err)?;
+        self.role_cache.invalidate();
+
+        Ok(())
+    }
+
     #[instrument(level = "debug", err, skip_all)]
     async fn apply_role_diff(
         &self,
@@ -104,107 +178,160 @@ impl<'a> DiscordPort for DiscordAdapter<'a> {
         role_diff: &RoleDiff,
         reason: &str,
     ) -> Result<(), DiscordError> {
+        if role_diff.to_assign().is_empty() && role_diff.to_remove().is_empty() {
+            return Ok(());
+        }
+
         let user_id = domain_to_serenity_user_id(user_id);
-        let mut assign_set = FuturesUnordered::new();
-        let mut remove_set = FuturesUnordered::new();
+        let Some(member) = self.get_member(user_id).await? else {
+            warn!(
+                "User {:?} left the guild before the role diff was applied",
+                user_id
+            );
+            return Ok(());
+        };
 
+        // The whole role set is replaced in one request instead of a request per changed role
+        let to_remove = role_diff
+            .to_remove()
+            .iter()
+            .map(|role_id| domain_to_serenity_role_id(*role_id))
+            .collect::<HashSet<_>>();
+        let mut roles = member
+            .roles
+            .into_iter()
+            .filter(|role_id| !to_remove.contains(role_id))
+            .collect::<Vec<_>>();
         for role_id in role_diff.to_assign() {
             let role_id = domain_to_serenity_role_id(*role_id);
-
-            assign_set.push(async move {
-                self.client
-                    .add_member_role(self.guild_id, user_id, role_id, Some(reason))
-                    .await
-            });
+            if !roles.contains(&role_id) {
+                roles.push(role_id);
+            }
         }
 
-        for role_id in role_diff.to_remove() {
-            let role_id = domain_to_serenity_role_id(*role_id);
+        self.client
+            .edit_member(
+                self.guild_id,
+                user_id,
+                &serenity::EditMember::new().roles(roles),
+                Some(reason),
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    "An error occurred during role diff of user {:?}: {:?}",
+                    user_id, err,
+                );
+                map_serenity_err(err)
+            })?;
 
-            remove_set.push(async move {
-                self.client
-                    .remove_member_role(self.guild_id, user_id, role_id, Some(reason))
-                    .await
-            });
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip_all)]
+    async fn set_nickname(
+        &self,
+        user_id: UserId,
+        nickname: &str,
+        reason: &str,
+    ) -> Result<NicknameUpdate, DiscordError> {
+        let user_id = domain_to_serenity_user_id(user_id);
+
+        let Some(member) = self.get_member(user_id).await? else {
+            return Ok(NicknameUpdate::MemberNotFound);
+        };
+        if member.nick.as_deref() == Some(nickname) {
+            return Ok(NicknameUpdate::Unchanged);
         }
 
-        let mut failed = false;
-        loop {
-            tokio::select! {
-                Some(result) = assign_set.next() => {
-                    if let Err(err) = result {
-                        error!(
-                    "An error occurred during role diff of user {:?}: {:?}",
-                    user_id,
-                    map_serenity_err(err)
-                );
-                        failed = true;
-                    }
-                }
-                Some(result) = remove_set.next() => {
-                    if let Err(err) = result {
-                        error!(
-                    "An error occurred during role diff of user {:?}: {:?}",
-                    user_id,
-                    map_serenity_err(err)
-                );
-                        failed = true;
-                    }
-                }
-                else => {
-                    break;
-                }
-            }
+        if !self.can_rename(&member).await? {
+            return Ok(NicknameUpdate::Skipped);
         }
 
-        if failed.not() {
-            Ok(())
-        } else {
-            Err(DiscordError::DiscordUnavailable)
+        self.client
+            .edit_member(
+                self.guild_id,
+                user_id,
+                &serenity::EditMember::new().nickname(nickname),
+                Some(reason),
+            )
+            .await
+            .map_err(map_serenity_err)?;
+
+        Ok(NicknameUpdate::Updated)
+    }
+
+    #[instrument(level = "debug", err, skip_all)]
+    async fn reset_nickname(
+        &self,
+        user_id: UserId,
+        nickname: &str,
+        reason: &str,
+    ) -> Result<NicknameUpdate, DiscordError> {
+        let user_id = domain_to_serenity_user_id(user_id);
+
+        let Some(member) = self.get_member(user_id).await? else {
+            return Ok(NicknameUpdate::MemberNotFound);
+        };
+        // The member has changed the nickname since, it is theirs now
+        if member.nick.as_deref() != Some(nickname) {
+            return Ok(NicknameUpdate::Unchanged);
         }
+        if !self.can_rename(&member).await? {
+            return Ok(NicknameUpdate::Skipped);
+        }
+
+        // An empty nickname removes it
+        self.client
+            .edit_member(
+                self.guild_id,
+                user_id,
+                &serenity::EditMember::new().nickname(""),
+                Some(reason),
+            )
+            .await
+            .map_err(map_serenity_err)?;
+
+        Ok(NicknameUpdate::Updated)
     }
 
     #[instrument(level = "debug", err, skip_all)]
     async fn find_user_roles(&self, user_id: UserId) -> Result<Option<Vec<Role>>, DiscordError> {
         let user_id = domain_to_serenity_user_id(user_id);
-        let mut set = FuturesUnordered::new();
-
-        let member = match self.client.get_member(self.guild_id, user_id).await {
-            Ok(member) => member,
-            Err(err) => {
-                if let serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(err)) =
-                    &err
-                    && err.status_code == 404
-                {
-                    return Ok(None);
-                }
-                return Err(map_serenity_err(err));
-            }
+        let Some(member) = self.get_member(user_id).await? else {
+            return Ok(None);
         };
 
-        for role_id in member.roles.iter() {
-            let role_id = *role_id;
-
-            set.push(async move {
-                match self.client.get_guild_role(self.guild_id, role_id).await {
-                    Ok(role) => Ok(Role {
-                        role_id: serenity_to_domain_role_id(role.id),
-                        name: role.name,
-                    }),
-                    Err(error) => {
-                        warn!(
-                            "Failed to fetch role {} from guild {}: {:?}",
-                            role_id, self.guild_id, error,
-                        );
-                        Err(DiscordError::DiscordUnavailable)
-                    }
-                }
-            });
+        let mut guild = self
+            .role_cache
+            .get(self.client, self.guild_id)
+            .await
+            .map_err(map_serenity_err)?;
+        if let Some(role_id) = member
+            .roles
+            .iter()
+            .find(|role_id| !guild.roles.contains_key(role_id))
+        {
+            guild = self
+                .role_cache
+                .get_with_role(self.client, self.guild_id, *role_id)
+                .await
+                .map_err(map_serenity_err)?;
         }
 
         let mut roles = vec![];
-        while let Some(role) = set.next().await {
-            roles.push(role?);
+        for role_id in &member.roles {
+            let Some(role) = guild.roles.get(role_id) else {
+                warn!(
+                    "Role {} of user {} not found in guild {}",
+                    role_id, user_id, self.guild_id,
+                );
+                return Err(DiscordError::DiscordUnavailable);
+            };
+            roles.push(Role {
+                role_id: serenity_to_domain_role_id(role.id),
+                name: role.name.clone(),
+            });
         }
 
         Ok(Some(roles))
@@ -214,25 +341,26 @@ impl<'a> DiscordPort for DiscordAdapter<'a> {
     async fn find_role_name(&self, role_id: RoleId) -> Result<Option<String>, DiscordError> {
         let role_id = domain_to_serenity_role_id(role_id);
 
-        let role = self
-            .client
-            .get_guild_role(self.guild_id, role_id)
+        let guild = self
+            .role_cache
+            .get_with_role(self.client, self.guild_id, role_id)
             .await
             .map_err(map_serenity_err)?;
 
-        Ok(Some(role.name))
+        Ok(guild.roles.get(&role_id).map(|role| role.name.clone()))
     }
 
     #[instrument(level = "debug", err, skip_all)]
     async fn find_class_role(&self, class_id: &str) -> Result<Option<RoleId>, DiscordError> {
-        let roles = self
-            .client
-            .get_guild_roles(self.guild_id)
+        let guild = self
+            .role_cache
+            .get(self.client, self.guild_id)
             .await
             .map_err(map_serenity_err)?;
 
-        let class_role = roles
-            .iter()
+        let class_role = guild
+            .roles
+            .values()
             .find(|role| role.name.eq_ignore_ascii_case(class_id));
         let class_role_id = class_role.map(|role| serenity_to_domain_role_id(role.id));
 
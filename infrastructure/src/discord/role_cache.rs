@@ -0,0 +1,130 @@
+use poise::serenity_prelude as serenity;
+use serenity::all::{GuildId, Http};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument};
+
+/// Roles changed outside of the bot are picked up at the latest after this long
+const TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Guild roles shared by all the Discord adapters, so that reading the roles of a member
+/// does not cost a request per role
+#[derive(Default)]
+pub struct GuildRoleCache {
+    guild: RwLock<Option<CachedGuild>>,
+    bot: RwLock<Option<CachedBot>>,
+}
+
+#[derive(Clone)]
+pub(crate) struct CachedGuild {
+    pub owner_id: serenity::UserId,
+    pub roles: Arc<HashMap<serenity::RoleId, serenity::Role>>,
+    fetched_at: Instant,
+}
+
+/// The bot member, it can only rename members whose highest role is below its own
+#[derive(Clone, Copy)]
+pub(crate) struct CachedBot {
+    pub user_id: serenity::UserId,
+    pub highest_position: u16,
+    fetched_at: Instant,
+}
+
+impl GuildRoleCache {
+    /// Drops the cached roles and the bot member, they are fetched again on the next access
+    #[instrument(level = "debug", skip(self))]
+    pub fn invalidate(&self) {
+        *self.guild.write().unwrap_or_else(|e| e.into_inner()) = None;
+        *self.bot.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    #[instrument(level = "trace", skip(self, client))]
+    pub(crate) async fn get(
+        &self,
+        client: &Http,
+        guild_id: GuildId,
+    ) -> Result<CachedGuild, serenity::Error> {
+        if let Some(guild) = self
+            .guild
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .filter(|guild| guild.fetched_at.elapsed() < TTL)
+        {
+            return Ok(guild.clone());
+        }
+
+        debug!("Fetching guild roles");
+        let guild = client.get_guild(guild_id).await?;
+        let guild = CachedGuild {
+            owner_id: guild.owner_id,
+            roles: Arc::new(guild.roles),
+            fetched_at: Instant::now(),
+        };
+        *self.guild.write().unwrap_or_else(|e| e.into_inner()) = Some(guild.clone());
+
+        Ok(guild)
+    }
+
+    /// Like `get`, but fetches the roles again when the role is not cached, e.g. when it was
+    /// created outside of the bot
+    #[instrument(level = "trace", skip(self, client))]
+    pub(crate) async fn get_with_role(
+        &self,
+        client: &Http,
+        guild_id: GuildId,
+        role_id: serenity::RoleId,
+    ) -> Result<CachedGuild, serenity::Error> {
+        let guild = self.get(client, guild_id).await?;
+        if guild.roles.contains_key(&role_id) {
+            return Ok(guild);
+        }
+
+        self.invalidate();
+        self.get(client, guild_id).await
+    }
+
+    #[instrument(level = "trace", skip(self, client))]
+    pub(crate) async fn get_bot(
+        &self,
+        client: &Http,
+        guild_id: GuildId,
+    ) -> Result<CachedBot, serenity::Error> {
+        if let Some(bot) = self
+            .bot
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .filter(|bot| bot.fetched_at.elapsed() < TTL)
+        {
+            return Ok(*bot);
+        }
+
+        debug!("Fetching bot member");
+        let guild = self.get(client, guild_id).await?;
+        let bot_user = client.get_current_user().await?;
+        let bot_member = client.get_member(guild_id, bot_user.id).await?;
+        let bot = CachedBot {
+            user_id: bot_user.id,
+            highest_position: guild.highest_position(&bot_member.roles),
+            fetched_at: Instant::now(),
+        };
+        *self.bot.write().unwrap_or_else(|e| e.into_inner()) = Some(bot);
+
+        Ok(bot)
+    }
+}
+
+impl CachedGuild {
+    /// Returns the position of the highest of the roles, members without roles are at the bottom
+    #[instrument(level = "trace", skip_all)]
+    pub fn highest_position(&self, role_ids: &[serenity::RoleId]) -> u16 {
+        role_ids
+            .iter()
+            .filter_map(|role_id| self.roles.get(role_id))
+            .map(|role| role.position)
+            .max()
+            .unwrap_or(0)
+    }
+}
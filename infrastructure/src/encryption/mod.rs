@@ -0,0 +1,139 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::collections::HashMap;
+use std::fmt;
+use thiserror::Error;
+use tracing::instrument;
+
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum TokenCipherError {
+    #[error("Encryption key {0} is not configured")]
+    UnknownKey(String),
+    #[error("Encryption key {0} is not a base64 encoded 256-bit key")]
+    InvalidKey(String),
+    #[error("Failed to encrypt the token")]
+    EncryptionFailed,
+    #[error("Failed to decrypt the token")]
+    DecryptionFailed,
+    #[error("The token is stored in plain text")]
+    NotEncrypted,
+}
+
+/// AES-256-GCM cipher for OAuth tokens stored in the database.
+///
+/// Ciphertexts are stored as base64 of the nonce followed by the sealed token, together with the
+/// identifier of the key used, so that old keys can stay configured for decryption while new rows
+/// are written with the current key.
+#[derive(Clone)]
+pub struct TokenCipher {
+    current_key_id: String,
+    ciphers: HashMap<String, Aes256Gcm>,
+}
+
+impl fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCipher")
+            .field("current_key_id", &self.current_key_id)
+            .field("key_ids", &self.ciphers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl TokenCipher {
+    /// Creates the cipher from base64 encoded keys indexed by their identifiers
+    #[instrument(level = "trace", skip(keys))]
+    pub fn new(
+        current_key_id: String,
+        keys: HashMap<String, String>,
+    ) -> Result<Self, TokenCipherError> {
+        let ciphers = keys
+            .into_iter()
+            .map(|(key_id, key)| {
+                let key = BASE64
+                    .decode(key)
+                    .ok()
+                    .filter(|key| key.len() == KEY_LENGTH)
+                    .ok_or_else(|| TokenCipherError::InvalidKey(key_id.clone()))?;
+                let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+                Ok((key_id, cipher))
+            })
+            .collect::<Result<HashMap<_, _>, TokenCipherError>>()?;
+
+        if !ciphers.contains_key(&current_key_id) {
+            return Err(TokenCipherError::UnknownKey(current_key_id));
+        }
+
+        Ok(Self {
+            current_key_id,
+            ciphers,
+        })
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub fn current_key_id(&self) -> &str {
+        &self.current_key_id
+    }
+
+    /// Encrypts the token with the current key, `associated_data` is bound to the ciphertext,
+    /// so that the values cannot be swapped between the rows and columns it identifies
+    #[instrument(level = "trace", skip(self, token))]
+    pub fn encrypt(&self, token: &str, associated_data: &str) -> Result<String, TokenCipherError> {
+        let cipher = &self.ciphers[&self.current_key_id];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: token.as_bytes(),
+                    aad: associated_data.as_bytes(),
+                },
+            )
+            .map_err(|_| TokenCipherError::EncryptionFailed)?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(BASE64.encode(sealed))
+    }
+
+    /// Decrypts the token with the key it was encrypted with, rows without a key identifier
+    /// predate the encryption and are refused, they are re-encrypted before the bot starts
+    #[instrument(level = "trace", skip(self, sealed))]
+    pub fn decrypt(
+        &self,
+        key_id: Option<&str>,
+        sealed: &str,
+        associated_data: &str,
+    ) -> Result<String, TokenCipherError> {
+        let Some(key_id) = key_id else {
+            return Err(TokenCipherError::NotEncrypted);
+        };
+        let cipher = self
+            .ciphers
+            .get(key_id)
+            .ok_or_else(|| TokenCipherError::UnknownKey(key_id.to_string()))?;
+
+        let sealed = BASE64
+            .decode(sealed)
+            .map_err(|_| TokenCipherError::DecryptionFailed)?;
+        if sealed.len() < NONCE_LENGTH {
+            return Err(TokenCipherError::DecryptionFailed);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+        let token = cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: associated_data.as_bytes(),
+                },
+            )
+            .map_err(|_| TokenCipherError::DecryptionFailed)?;
+
+        String::from_utf8(token).map_err(|_| TokenCipherError::DecryptionFailed)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::jobs::sync_trigger::{db_to_domain_sync_trigger, domain_to_db_sync_trigger};
 use async_trait::async_trait;
 use domain::jobs::role_sync_job::{
     RoleSyncRequested, RoleSyncRequestedRepository, RoleSyncRequestedRepositoryError,
@@ -31,15 +32,17 @@ impl<'a> RoleSyncRequestedRepository for PostgresRoleSyncRequestedRepository<'a>
     ) -> Result<(), RoleSyncRequestedRepositoryError> {
         if request.low_priority {
             query!(
-                "INSERT INTO role_sync_requested (user_id, queued_at, low_priority) VALUES ($1, $2, true) ON CONFLICT (user_id) DO NOTHING",
+                "INSERT INTO role_sync_requested (user_id, queued_at, low_priority, triggered_by) VALUES ($1, $2, true, $3) ON CONFLICT (user_id) DO NOTHING",
                 request.user_id.0 as i64,
                 request.queued_at.naive_utc(),
+                domain_to_db_sync_trigger(request.trigger),
             )
         } else {
             query!(
-                "INSERT INTO role_sync_requested (user_id, queued_at, low_priority) VALUES ($1, $2, false) ON CONFLICT (user_id) DO UPDATE SET queued_at = $2, low_priority = false",
+                "INSERT INTO role_sync_requested (user_id, queued_at, low_priority, triggered_by) VALUES ($1, $2, false, $3) ON CONFLICT (user_id) DO UPDATE SET queued_at = $2, low_priority = false, triggered_by = $3",
                 request.user_id.0 as i64,
                 request.queued_at.naive_utc(),
+                domain_to_db_sync_trigger(request.trigger),
             )
         }.execute(self.pool).await.map_err(|err| {
             warn!(error = ?err, "Failed to save role sync request");
@@ -59,7 +62,7 @@ impl<'a> RoleSyncRequestedRepository for PostgresRoleSyncRequestedRepository<'a>
     ) -> Result<Option<RoleSyncRequested>, RoleSyncRequestedRepositoryError> {
         let row =
             query!(
-                "SELECT user_id, queued_at, low_priority FROM role_sync_requested WHERE low_priority = $1 ORDER BY queued_at LIMIT 1",
+                "SELECT user_id, queued_at, low_priority, triggered_by FROM role_sync_requested WHERE low_priority = $1 ORDER BY queued_at LIMIT 1",
                 low_priority,
             )
                 .fetch_optional(self.pool)
@@ -87,6 +90,7 @@ impl<'a> RoleSyncRequestedRepository for PostgresRoleSyncRequestedRepository<'a>
             user_id: UserId(row.user_id as u64),
             queued_at: row.queued_at.and_utc(),
             low_priority,
+            trigger: db_to_domain_sync_trigger(&row.triggered_by),
         }))
     }
 }
@@ -0,0 +1,43 @@
+use domain::jobs::sync_trigger::SyncTrigger;
+use tracing::{instrument, warn};
+
+#[instrument(level = "trace")]
+pub fn domain_to_db_sync_trigger(trigger: SyncTrigger) -> &'static str {
+    match trigger {
+        SyncTrigger::Verification => "verification",
+        SyncTrigger::ManualVerification => "manual_verification",
+        SyncTrigger::Unverification => "unverification",
+        SyncTrigger::EmailReused => "email_reused",
+        SyncTrigger::MemberJoin => "member_join",
+        SyncTrigger::AdminRefresh => "admin_refresh",
+        SyncTrigger::ClassRollover => "class_rollover",
+        SyncTrigger::ExemptionChange => "exemption_change",
+        SyncTrigger::NicknameSettingsChange => "nickname_settings_change",
+        SyncTrigger::PersonalDataErasure => "personal_data_erasure",
+        SyncTrigger::Periodic => "periodic",
+    }
+}
+
+#[instrument(level = "trace")]
+pub fn db_to_domain_sync_trigger(trigger: &str) -> SyncTrigger {
+    match trigger {
+        "verification" => SyncTrigger::Verification,
+        "manual_verification" => SyncTrigger::ManualVerification,
+        "unverification" => SyncTrigger::Unverification,
+        "email_reused" => SyncTrigger::EmailReused,
+        "member_join" => SyncTrigger::MemberJoin,
+        "admin_refresh" => SyncTrigger::AdminRefresh,
+        "class_rollover" => SyncTrigger::ClassRollover,
+        "exemption_change" => SyncTrigger::ExemptionChange,
+        "nickname_settings_change" => SyncTrigger::NicknameSettingsChange,
+        "personal_data_erasure" => SyncTrigger::PersonalDataErasure,
+        "periodic" => SyncTrigger::Periodic,
+        _ => {
+            warn!(
+                trigger,
+                "Unknown sync trigger stored in the database, using periodic"
+            );
+            SyncTrigger::Periodic
+        }
+    }
+}
@@ -1,5 +1,12 @@
 pub mod authentication;
+pub mod class;
 pub mod database;
 pub mod discord;
+pub mod encryption;
 pub mod jobs;
+pub mod nickname;
 pub mod oauth;
+pub mod oauth_provider;
+pub mod oidc;
+pub mod role_change_log;
+pub mod role_sync_exemption;
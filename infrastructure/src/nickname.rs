@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use domain::nickname::{
+    NicknameSettings, NicknameSettingsRepository, NicknameSettingsRepositoryError,
+};
+use domain_shared::discord::UserId;
+use sqlx::{PgPool, query};
+use tracing::{instrument, warn};
+
+pub struct PostgresNicknameSettingsRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PostgresNicknameSettingsRepository<'a> {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<'a> NicknameSettingsRepository for PostgresNicknameSettingsRepository<'a> {
+    #[instrument(level = "debug", err, skip(self))]
+    async fn save(
+        &self,
+        settings: &NicknameSettings,
+    ) -> Result<(), NicknameSettingsRepositoryError> {
+        query!(
+            "INSERT INTO nickname_settings (user_id, opt_out, admin_override, applied_nickname) VALUES ($1, $2, $3, $4) ON CONFLICT (user_id) DO UPDATE SET opt_out = $2, admin_override = $3, applied_nickname = $4",
+            settings.user_id.0 as i64,
+            settings.opt_out,
+            settings.admin_override,
+            settings.applied_nickname,
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<Option<NicknameSettings>, NicknameSettingsRepositoryError> {
+        let record = query!(
+            "SELECT user_id, opt_out, admin_override, applied_nickname FROM nickname_settings WHERE user_id = $1",
+            user_id.0 as i64,
+        )
+        .fetch_optional(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(record.map(|record| NicknameSettings {
+            user_id: UserId(record.user_id as u64),
+            opt_out: record.opt_out,
+            admin_override: record.admin_override,
+            applied_nickname: record.applied_nickname,
+        }))
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<bool, NicknameSettingsRepositoryError> {
+        let result = query!(
+            "DELETE FROM nickname_settings WHERE user_id = $1",
+            user_id.0 as i64,
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_err(err: sqlx::Error) -> NicknameSettingsRepositoryError {
+    warn!(error = ?err, "Failed to access the nickname settings");
+    NicknameSettingsRepositoryError::ServiceUnavailable
+}
@@ -1,12 +1,12 @@
-mod authentication_link;
-mod csrf_token;
+pub(crate) mod authentication_link;
+pub(crate) mod csrf_token;
 
 use crate::oauth::authentication_link::oauth_to_domain_authentication_link;
 use crate::oauth::csrf_token::oauth_to_domain_csrf_token;
 use chrono::Utc;
-use domain::ports::oauth::{OAuthError, OAuthPort, OAuthToken, UserInfoDto};
+use domain::ports::oauth::{AuthenticationLinkDto, OAuthError, OAuthPort, OAuthToken, UserInfoDto};
 use domain_shared::authentication::{
-    AccessToken, AuthenticationLink, ClientCallbackToken, CsrfToken, RefreshToken, UserGroup,
+    AccessToken, ClientCallbackToken, Nonce, PkceVerifier, RefreshToken, UserGroup,
 };
 use oauth2::basic::{
     BasicClient, BasicErrorResponse, BasicErrorResponseType, BasicRevocationErrorResponse,
@@ -14,13 +14,15 @@ use oauth2::basic::{
 };
 use oauth2::url::Url;
 use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, EndpointNotSet, EndpointSet, RedirectUrl,
-    RequestTokenError, Scope, StandardRevocableToken, TokenResponse, TokenUrl,
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, ConfigurationError, EndpointMaybeSet,
+    EndpointNotSet, EndpointSet, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl,
+    RequestTokenError, RevocationErrorResponseType, RevocationUrl, Scope, StandardRevocableToken,
+    TokenResponse, TokenUrl,
 };
 use reqwest::Client as HttpClient;
 use serde::Deserialize;
 use std::time::Duration;
-use tracing::{error, instrument, warn};
+use tracing::{Span, error, field, info, instrument, warn};
 
 #[derive(Clone, Debug)]
 pub struct OAuthAdapterConfig {
@@ -28,6 +30,8 @@ pub struct OAuthAdapterConfig {
     pub client_secret: ClientSecret,
     pub tenant_id: TenantId,
     pub authentication_callback_url: Url,
+    /// Token revocation endpoint (RFC 7009), Azure AD does not provide one
+    pub revocation_url: Option<RevocationUrl>,
 }
 
 pub struct OAuthAdapter {
@@ -47,7 +51,7 @@ pub type OAuthClient = oauth2::Client<
     EndpointSet,
     EndpointNotSet,
     EndpointNotSet,
-    EndpointNotSet,
+    EndpointMaybeSet,
     EndpointSet,
 >;
 
@@ -59,9 +63,16 @@ struct UserInfoResponse {
     pub email: String,
 }
 
+/// Only groups are requested, directory roles and administrative units are skipped
+const USER_GROUPS_URL: &str = "https://graph.microsoft.com/v1.0/me/memberOf/microsoft.graph.group?$select=id,displayName,mail&$top=999";
+/// Guards against a continuation link that never ends
+const MAX_USER_GROUP_PAGES: usize = 20;
+
 #[derive(Deserialize, Debug)]
 struct UserGroupResponse {
     pub value: Vec<UserGroup>,
+    #[serde(rename = "@odata.nextLink")]
+    pub next_link: Option<String>,
 }
 
 impl OAuthAdapter {
@@ -75,53 +86,121 @@ impl OAuthAdapter {
             http_client,
         }
     }
+
+    /// Reads all pages of the group memberships, following the `@odata.nextLink` continuation
+    #[instrument(level = "debug", err, skip(self, access_token))]
+    async fn get_user_groups(
+        &self,
+        access_token: &AccessToken,
+    ) -> domain::ports::oauth::Result<Vec<UserGroup>, OAuthError> {
+        let mut groups = Vec::new();
+        let mut next_link = Some(USER_GROUPS_URL.to_string());
+
+        for page in 0..MAX_USER_GROUP_PAGES {
+            let Some(url) = next_link.take() else {
+                break;
+            };
+            let user_groups = self
+                .http_client
+                .get(url)
+                .bearer_auth(access_token.0.clone())
+                .send()
+                .await
+                .map_err(|err| {
+                    warn!("Failed to get user groups: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                })?
+                .text()
+                .await
+                .map_err(|err| {
+                    warn!("Failed to get user groups: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                })?;
+            let UserGroupResponse {
+                value,
+                next_link: link,
+            } = serde_json::from_str(&user_groups).map_err(|err| {
+                warn!("Failed to parse user groups on page {}: {:?}", page, err);
+                OAuthError::OAuthUnavailable
+            })?;
+
+            groups.extend(value);
+            next_link = link;
+        }
+
+        if next_link.is_some() {
+            warn!(
+                "User groups have more than {} pages, the remaining ones are ignored",
+                MAX_USER_GROUP_PAGES
+            );
+        }
+
+        Ok(groups)
+    }
 }
 
 impl OAuthPort for OAuthAdapter {
     #[instrument(level = "debug", skip(self))]
-    async fn create_authentication_link(&self) -> (AuthenticationLink, CsrfToken) {
+    async fn create_authentication_link(&self) -> AuthenticationLinkDto {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let nonce = oauth2::CsrfToken::new_random();
         let (link, csrf_token) = self
             .oauth_client
             .authorize_url(oauth2::CsrfToken::new_random)
             .add_scope(Scope::new("User.read".to_string()))
             .add_scope(Scope::new("GroupMember.Read.All".to_string()))
             .add_scope(Scope::new("offline_access".to_string()))
+            .add_extra_param("nonce", nonce.secret())
+            .set_pkce_challenge(pkce_challenge)
             .url();
 
-        (
-            oauth_to_domain_authentication_link(link),
-            oauth_to_domain_csrf_token(csrf_token),
-        )
+        AuthenticationLinkDto {
+            link: oauth_to_domain_authentication_link(link),
+            csrf_token: oauth_to_domain_csrf_token(csrf_token),
+            pkce_verifier: PkceVerifier(pkce_verifier.into_secret()),
+            nonce: Nonce(nonce.into_secret()),
+        }
     }
 
-    #[instrument(level = "debug", skip(self, client_callback_token))]
+    #[instrument(
+        level = "debug",
+        skip(self, client_callback_token, pkce_verifier, _nonce)
+    )]
     async fn exchange_code_after_callback(
         &self,
         client_callback_token: ClientCallbackToken,
+        pkce_verifier: Option<PkceVerifier>,
+        // Azure AD returns no ID token for the Microsoft Graph scopes, there is nothing to verify
+        _nonce: Option<Nonce>,
     ) -> domain::ports::oauth::Result<OAuthToken, OAuthError> {
-        let token_result = self
+        let mut request = self
             .oauth_client
-            .exchange_code(AuthorizationCode::new(client_callback_token.0))
-            .request_async(&self.http_client)
-            .await
-            .map_err(|err| match err {
-                RequestTokenError::ServerResponse(_) => {
-                    error!("OAuth exchange code after callback failed: {:?}", err,);
-                    OAuthError::OAuthUnavailable
-                }
-                RequestTokenError::Request(err) => {
-                    warn!("OAuth request failed with error: {:?}", err);
-                    OAuthError::OAuthUnavailable
-                }
-                RequestTokenError::Parse(err, _) => {
-                    warn!("OAuth request failed to parse response: {:?}", err);
-                    OAuthError::OAuthUnavailable
-                }
-                RequestTokenError::Other(err) => {
-                    warn!("Request failed with error: {:?}", err);
-                    OAuthError::OAuthUnavailable
-                }
-            })?;
+            .exchange_code(AuthorizationCode::new(client_callback_token.0));
+        if let Some(pkce_verifier) = pkce_verifier {
+            request = request.set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier.0));
+        }
+        let token_result =
+            request
+                .request_async(&self.http_client)
+                .await
+                .map_err(|err| match err {
+                    RequestTokenError::ServerResponse(_) => {
+                        error!("OAuth exchange code after callback failed: {:?}", err,);
+                        OAuthError::OAuthUnavailable
+                    }
+                    RequestTokenError::Request(err) => {
+                        warn!("OAuth request failed with error: {:?}", err);
+                        OAuthError::OAuthUnavailable
+                    }
+                    RequestTokenError::Parse(err, _) => {
+                        warn!("OAuth request failed to parse response: {:?}", err);
+                        OAuthError::OAuthUnavailable
+                    }
+                    RequestTokenError::Other(err) => {
+                        warn!("Request failed with error: {:?}", err);
+                        OAuthError::OAuthUnavailable
+                    }
+                })?;
 
         let expires_at = Utc::now()
             + token_result
@@ -207,7 +286,12 @@ impl OAuthPort for OAuthAdapter {
         })
     }
 
-    #[instrument(level = "debug", err, skip(self, access_token))]
+    #[instrument(
+        level = "debug",
+        err,
+        skip(self, access_token),
+        fields(group_count = field::Empty)
+    )]
     async fn get_user_info(
         &self,
         access_token: &AccessToken,
@@ -233,28 +317,8 @@ impl OAuthPort for OAuthAdapter {
             OAuthError::OAuthUnavailable
         })?;
 
-        let user_groups = self
-            .http_client
-            .get("https://graph.microsoft.com/v1.0/me/memberOf")
-            .bearer_auth(access_token.0.clone())
-            .send()
-            .await
-            .map_err(|err| {
-                warn!("Failed to get user groups: {:?}", err);
-                OAuthError::OAuthUnavailable
-            })?
-            .text()
-            .await
-            .map_err(|err| {
-                warn!("Failed to get user groups: {:?}", err);
-                OAuthError::OAuthUnavailable
-            })?;
-        let groups = serde_json::from_str::<UserGroupResponse>(&user_groups)
-            .map_err(|err| {
-                warn!("Failed to parse user groups: {:?}", err);
-                OAuthError::OAuthUnavailable
-            })?
-            .value;
+        let groups = self.get_user_groups(access_token).await?;
+        Span::current().record("group_count", groups.len());
 
         Ok(UserInfoDto {
             name,
@@ -262,6 +326,57 @@ impl OAuthPort for OAuthAdapter {
             groups,
         })
     }
+
+    #[instrument(level = "debug", skip(self, oauth_token))]
+    async fn revoke_token(
+        &self,
+        oauth_token: &OAuthToken,
+    ) -> domain::ports::oauth::Result<(), OAuthError> {
+        let refresh_token = oauth2::RefreshToken::new(oauth_token.refresh_token.0.clone());
+        let request = match self
+            .oauth_client
+            .revoke_token(StandardRevocableToken::RefreshToken(refresh_token))
+        {
+            Ok(request) => request,
+            Err(ConfigurationError::MissingUrl(_)) => {
+                info!("OAuth revocation URL is not configured, the token is not revoked");
+                return Ok(());
+            }
+            Err(err) => {
+                error!("OAuth revocation request is misconfigured: {:?}", err);
+                return Err(OAuthError::OAuthUnavailable);
+            }
+        };
+
+        request
+            .request_async(&self.http_client)
+            .await
+            .map_err(|err| match err {
+                RequestTokenError::ServerResponse(err) => match err.error() {
+                    RevocationErrorResponseType::Basic(BasicErrorResponseType::InvalidGrant) => {
+                        // OAuth refresh token already expired or revoked
+                        OAuthError::TokenExpired
+                    }
+                    RevocationErrorResponseType::Basic(_)
+                    | RevocationErrorResponseType::UnsupportedTokenType => {
+                        warn!("OAuth revocation failed with error: {:?}", err);
+                        OAuthError::OAuthUnavailable
+                    }
+                },
+                RequestTokenError::Request(err) => {
+                    warn!("OAuth request failed with error: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                }
+                RequestTokenError::Parse(err, _) => {
+                    warn!("OAuth request failed to parse response: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                }
+                RequestTokenError::Other(err) => {
+                    warn!("Request failed with error: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                }
+            })
+    }
 }
 
 #[instrument(level = "trace", skip(config))]
@@ -283,4 +398,5 @@ fn create_oauth_client(config: &OAuthAdapterConfig) -> OAuthClient {
         .set_redirect_uri(RedirectUrl::from_url(
             config.authentication_callback_url.clone(),
         ))
+        .set_revocation_url_option(config.revocation_url.clone())
 }
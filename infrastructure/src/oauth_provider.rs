@@ -0,0 +1,93 @@
+use crate::oauth::{OAuthAdapter, OAuthAdapterConfig};
+use crate::oidc::{OidcAdapter, OidcProvider};
+use domain::ports::oauth::{AuthenticationLinkDto, OAuthError, OAuthPort, OAuthToken, UserInfoDto};
+use domain_shared::authentication::{AccessToken, ClientCallbackToken, Nonce, PkceVerifier};
+use tracing::instrument;
+
+/// Identity provider the users authenticate against.
+#[derive(Clone, Debug)]
+pub enum OAuthProviderConfig {
+    /// Azure AD with the user info read from Microsoft Graph
+    Azure(OAuthAdapterConfig),
+    /// Any OpenID Connect provider
+    Oidc(OidcProvider),
+}
+
+pub enum OAuthProviderAdapter {
+    Azure(Box<OAuthAdapter>),
+    Oidc(Box<OidcAdapter>),
+}
+
+impl OAuthProviderAdapter {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(config: &OAuthProviderConfig) -> Self {
+        match config {
+            OAuthProviderConfig::Azure(config) => Self::Azure(Box::new(OAuthAdapter::new(config))),
+            OAuthProviderConfig::Oidc(provider) => Self::Oidc(Box::new(OidcAdapter::new(provider))),
+        }
+    }
+}
+
+impl OAuthPort for OAuthProviderAdapter {
+    #[instrument(level = "trace", skip(self))]
+    async fn create_authentication_link(&self) -> AuthenticationLinkDto {
+        match self {
+            Self::Azure(adapter) => adapter.create_authentication_link().await,
+            Self::Oidc(adapter) => adapter.create_authentication_link().await,
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn exchange_code_after_callback(
+        &self,
+        client_callback_token: ClientCallbackToken,
+        pkce_verifier: Option<PkceVerifier>,
+        nonce: Option<Nonce>,
+    ) -> domain::ports::oauth::Result<OAuthToken, OAuthError> {
+        match self {
+            Self::Azure(adapter) => {
+                adapter
+                    .exchange_code_after_callback(client_callback_token, pkce_verifier, nonce)
+                    .await
+            }
+            Self::Oidc(adapter) => {
+                adapter
+                    .exchange_code_after_callback(client_callback_token, pkce_verifier, nonce)
+                    .await
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn refresh_token(
+        &self,
+        oauth_token: &OAuthToken,
+    ) -> domain::ports::oauth::Result<OAuthToken, OAuthError> {
+        match self {
+            Self::Azure(adapter) => adapter.refresh_token(oauth_token).await,
+            Self::Oidc(adapter) => adapter.refresh_token(oauth_token).await,
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn get_user_info(
+        &self,
+        access_token: &AccessToken,
+    ) -> domain::ports::oauth::Result<UserInfoDto, OAuthError> {
+        match self {
+            Self::Azure(adapter) => adapter.get_user_info(access_token).await,
+            Self::Oidc(adapter) => adapter.get_user_info(access_token).await,
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn revoke_token(
+        &self,
+        oauth_token: &OAuthToken,
+    ) -> domain::ports::oauth::Result<(), OAuthError> {
+        match self {
+            Self::Azure(adapter) => adapter.revoke_token(oauth_token).await,
+            Self::Oidc(adapter) => adapter.revoke_token(oauth_token).await,
+        }
+    }
+}
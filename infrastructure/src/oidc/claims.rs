@@ -0,0 +1,40 @@
+use domain_shared::authentication::UserGroup;
+use serde_json::Value;
+use tracing::instrument;
+
+/// Names of the claims the user info is read from, as they differ between identity providers.
+#[derive(Clone, Debug)]
+pub struct OidcClaimsConfig {
+    pub name: String,
+    pub email: String,
+    pub groups: String,
+}
+
+#[instrument(level = "trace", skip(claims))]
+pub fn find_string_claim(claims: &Value, claim: &str) -> Option<String> {
+    claims
+        .get(claim)
+        .and_then(Value::as_str)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Reads the groups claim, which is either a list of group names or a single group name.
+/// Groups looking like a mail address are treated as such, so the class groups are recognized.
+#[instrument(level = "trace", skip(claims))]
+pub fn find_user_groups(claims: &Value, claim: &str) -> Vec<UserGroup> {
+    let groups = match claims.get(claim) {
+        Some(Value::Array(groups)) => groups.iter().filter_map(Value::as_str).collect(),
+        Some(Value::String(group)) => vec![group.as_str()],
+        _ => Vec::new(),
+    };
+
+    groups
+        .into_iter()
+        .map(|group| UserGroup {
+            id: group.to_string(),
+            name: group.to_string(),
+            mail: group.contains('@').then(|| group.to_string()),
+        })
+        .collect()
+}
@@ -0,0 +1,413 @@
+mod claims;
+
+use crate::oauth::authentication_link::oauth_to_domain_authentication_link;
+use crate::oauth::csrf_token::oauth_to_domain_csrf_token;
+use crate::oidc::claims::{find_string_claim, find_user_groups};
+use chrono::Utc;
+use domain::ports::oauth::{AuthenticationLinkDto, OAuthError, OAuthPort, OAuthToken, UserInfoDto};
+use domain_shared::authentication::{
+    AccessToken, ClientCallbackToken, Nonce, PkceVerifier, RefreshToken,
+};
+use openidconnect::core::{
+    CoreAuthenticationFlow, CoreClient, CoreErrorResponseType, CoreJsonWebKeySet,
+    CoreJwsSigningAlgorithm, CoreProviderMetadata, CoreResponseType, CoreRevocableToken,
+    CoreSubjectIdentifierType,
+};
+use openidconnect::url::Url;
+use openidconnect::{
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, ConfigurationError,
+    EmptyAdditionalProviderMetadata, EndpointMaybeSet, EndpointNotSet, EndpointSet, IssuerUrl,
+    JsonWebKeySetUrl, OAuth2TokenResponse, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl,
+    RequestTokenError, ResponseTypes, RevocationErrorResponseType, RevocationUrl, Scope,
+    StandardErrorResponse, TokenResponse, TokenUrl, UserInfoUrl,
+};
+use reqwest::Client as HttpClient;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{error, info, instrument, warn};
+
+pub use crate::oidc::claims::OidcClaimsConfig;
+
+#[derive(Clone, Debug)]
+pub struct OidcAdapterConfig {
+    pub client_id: ClientId,
+    pub client_secret: ClientSecret,
+    pub issuer_url: IssuerUrl,
+    pub authentication_callback_url: Url,
+    /// Endpoints used instead of the discovery document, when the provider does not publish one
+    pub endpoints: Option<OidcEndpoints>,
+    /// Scopes requested in addition to `openid`
+    pub scopes: Vec<String>,
+    pub claims: OidcClaimsConfig,
+    pub revocation_url: Option<RevocationUrl>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OidcEndpoints {
+    pub auth_url: AuthUrl,
+    pub token_url: TokenUrl,
+    pub user_info_url: UserInfoUrl,
+    pub jwks_url: JsonWebKeySetUrl,
+}
+
+/// OpenID Connect provider with the client built from the metadata resolved at startup.
+#[derive(Clone, Debug)]
+pub struct OidcProvider {
+    oidc_client: Arc<OidcClient>,
+    scopes: Vec<String>,
+    claims: OidcClaimsConfig,
+    revocation_url: Option<RevocationUrl>,
+}
+
+#[derive(Debug, Error)]
+pub enum OidcProviderError {
+    #[error("Failed to discover the OpenID Connect provider: {0}")]
+    DiscoveryFailed(String),
+}
+
+impl OidcProvider {
+    /// Resolves the provider metadata either from the discovery document of the issuer
+    /// or from the explicitly configured endpoints.
+    #[instrument(level = "info", skip(config))]
+    pub async fn discover(config: OidcAdapterConfig) -> Result<Self, OidcProviderError> {
+        let http_client = create_http_client();
+
+        let provider_metadata = match &config.endpoints {
+            None => CoreProviderMetadata::discover_async(config.issuer_url.clone(), &http_client)
+                .await
+                .map_err(|err| OidcProviderError::DiscoveryFailed(format!("{err:?}")))?,
+            Some(endpoints) => {
+                let jwks = CoreJsonWebKeySet::fetch_async(&endpoints.jwks_url, &http_client)
+                    .await
+                    .map_err(|err| OidcProviderError::DiscoveryFailed(format!("{err:?}")))?;
+
+                CoreProviderMetadata::new(
+                    config.issuer_url.clone(),
+                    endpoints.auth_url.clone(),
+                    endpoints.jwks_url.clone(),
+                    vec![ResponseTypes::new(vec![CoreResponseType::Code])],
+                    vec![CoreSubjectIdentifierType::Public],
+                    vec![CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256],
+                    EmptyAdditionalProviderMetadata {},
+                )
+                .set_token_endpoint(Some(endpoints.token_url.clone()))
+                .set_userinfo_endpoint(Some(endpoints.user_info_url.clone()))
+                .set_jwks(jwks)
+            }
+        };
+
+        info!(
+            issuer = provider_metadata.issuer().as_str(),
+            "OpenID Connect provider resolved",
+        );
+
+        let oidc_client = CoreClient::from_provider_metadata(
+            provider_metadata,
+            config.client_id,
+            Some(config.client_secret),
+        )
+        .set_redirect_uri(RedirectUrl::from_url(config.authentication_callback_url));
+
+        Ok(Self {
+            oidc_client: Arc::new(oidc_client),
+            scopes: config.scopes,
+            claims: config.claims,
+            revocation_url: config.revocation_url,
+        })
+    }
+}
+
+type OidcClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+pub struct OidcAdapter {
+    provider: OidcProvider,
+    http_client: HttpClient,
+}
+
+impl OidcAdapter {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(provider: &OidcProvider) -> Self {
+        Self {
+            provider: provider.clone(),
+            http_client: create_http_client(),
+        }
+    }
+}
+
+impl OAuthPort for OidcAdapter {
+    #[instrument(level = "debug", skip(self))]
+    async fn create_authentication_link(&self) -> AuthenticationLinkDto {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let nonce = openidconnect::Nonce::new_random();
+        let request_nonce = nonce.clone();
+        let mut request = self.provider.oidc_client.authorize_url(
+            CoreAuthenticationFlow::AuthorizationCode,
+            openidconnect::CsrfToken::new_random,
+            move || request_nonce,
+        );
+        for scope in &self.provider.scopes {
+            request = request.add_scope(Scope::new(scope.clone()));
+        }
+        let (link, csrf_token, _) = request.set_pkce_challenge(pkce_challenge).url();
+
+        AuthenticationLinkDto {
+            link: oauth_to_domain_authentication_link(link),
+            csrf_token: oauth_to_domain_csrf_token(csrf_token),
+            pkce_verifier: PkceVerifier(pkce_verifier.into_secret()),
+            nonce: Nonce(nonce.secret().clone()),
+        }
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self, client_callback_token, pkce_verifier, nonce)
+    )]
+    async fn exchange_code_after_callback(
+        &self,
+        client_callback_token: ClientCallbackToken,
+        pkce_verifier: Option<PkceVerifier>,
+        nonce: Option<Nonce>,
+    ) -> domain::ports::oauth::Result<OAuthToken, OAuthError> {
+        let mut request = self
+            .provider
+            .oidc_client
+            .exchange_code(AuthorizationCode::new(client_callback_token.0))
+            .map_err(map_configuration_err)?;
+        if let Some(pkce_verifier) = pkce_verifier {
+            request = request.set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier.0));
+        }
+        let token_result = request
+            .request_async(&self.http_client)
+            .await
+            .map_err(map_request_token_err)?;
+
+        let id_token = token_result.id_token().ok_or_else(|| {
+            error!("OpenID Connect provider did not return an ID token");
+            OAuthError::OAuthUnavailable
+        })?;
+        let id_token_verifier = self.provider.oidc_client.id_token_verifier();
+        let claims = match nonce {
+            Some(nonce) => id_token.claims(&id_token_verifier, &openidconnect::Nonce::new(nonce.0)),
+            // Requests created before the nonce was introduced
+            None => id_token.claims(
+                &id_token_verifier,
+                |_: Option<&openidconnect::Nonce>| Ok(()),
+            ),
+        };
+        if let Err(err) = claims {
+            error!("OpenID Connect ID token validation failed: {:?}", err);
+            return Err(OAuthError::OAuthUnavailable);
+        }
+
+        let refresh_token = token_result.refresh_token().ok_or_else(|| {
+            error!("OpenID Connect provider did not return a refresh token, is offline_access requested?");
+            OAuthError::OAuthUnavailable
+        })?;
+
+        Ok(OAuthToken {
+            access_token: AccessToken(token_result.access_token().secret().clone()),
+            expires_at: find_expires_at(token_result.expires_in()),
+            refresh_token: RefreshToken(refresh_token.secret().clone()),
+        })
+    }
+
+    #[instrument(level = "debug", skip(self, oauth_token))]
+    async fn refresh_token(
+        &self,
+        oauth_token: &OAuthToken,
+    ) -> domain::ports::oauth::Result<OAuthToken, OAuthError> {
+        let refresh_token = openidconnect::RefreshToken::new(oauth_token.refresh_token.0.clone());
+        let token_result = self
+            .provider
+            .oidc_client
+            .exchange_refresh_token(&refresh_token)
+            .map_err(map_configuration_err)?
+            .request_async(&self.http_client)
+            .await
+            .map_err(map_request_token_err)?;
+
+        // Not every provider rotates refresh tokens
+        let refresh_token = token_result
+            .refresh_token()
+            .map(|token| RefreshToken(token.secret().clone()))
+            .unwrap_or_else(|| oauth_token.refresh_token.clone());
+
+        Ok(OAuthToken {
+            access_token: AccessToken(token_result.access_token().secret().clone()),
+            expires_at: find_expires_at(token_result.expires_in()),
+            refresh_token,
+        })
+    }
+
+    #[instrument(level = "debug", err, skip(self, access_token))]
+    async fn get_user_info(
+        &self,
+        access_token: &AccessToken,
+    ) -> domain::ports::oauth::Result<UserInfoDto, OAuthError> {
+        let user_info_url = self.provider.oidc_client.user_info_url().ok_or_else(|| {
+            error!("OpenID Connect provider has no user info endpoint");
+            OAuthError::OAuthUnavailable
+        })?;
+
+        let response = self
+            .http_client
+            .get(user_info_url.url().clone())
+            .bearer_auth(access_token.0.clone())
+            .send()
+            .await
+            .map_err(|err| {
+                warn!("Failed to get user info: {:?}", err);
+                OAuthError::OAuthUnavailable
+            })?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(OAuthError::TokenExpired);
+        }
+        let user_info = response.text().await.map_err(|err| {
+            warn!("Failed to get user info: {:?}", err);
+            OAuthError::OAuthUnavailable
+        })?;
+        let user_info: serde_json::Value = serde_json::from_str(&user_info).map_err(|err| {
+            warn!("Failed to parse user info: {:?}", err);
+            OAuthError::OAuthUnavailable
+        })?;
+
+        let name = find_string_claim(&user_info, &self.provider.claims.name).ok_or_else(|| {
+            warn!(
+                claim = self.provider.claims.name,
+                "User info is missing the name claim"
+            );
+            OAuthError::OAuthUnavailable
+        })?;
+        let email =
+            find_string_claim(&user_info, &self.provider.claims.email).ok_or_else(|| {
+                warn!(
+                    claim = self.provider.claims.email,
+                    "User info is missing the email claim"
+                );
+                OAuthError::OAuthUnavailable
+            })?;
+        let groups = find_user_groups(&user_info, &self.provider.claims.groups);
+
+        Ok(UserInfoDto {
+            name,
+            email,
+            groups,
+        })
+    }
+
+    #[instrument(level = "debug", skip(self, oauth_token))]
+    async fn revoke_token(
+        &self,
+        oauth_token: &OAuthToken,
+    ) -> domain::ports::oauth::Result<(), OAuthError> {
+        let revocation_url = match &self.provider.revocation_url {
+            Some(revocation_url) => revocation_url.clone(),
+            None => {
+                info!("OAuth revocation URL is not configured, the token is not revoked");
+                return Ok(());
+            }
+        };
+
+        let refresh_token = openidconnect::RefreshToken::new(oauth_token.refresh_token.0.clone());
+        self.provider
+            .oidc_client
+            .as_ref()
+            .clone()
+            .set_revocation_url(revocation_url)
+            .revoke_token(CoreRevocableToken::RefreshToken(refresh_token))
+            .map_err(map_configuration_err)?
+            .request_async(&self.http_client)
+            .await
+            .map_err(|err| match err {
+                RequestTokenError::ServerResponse(err) => match err.error() {
+                    RevocationErrorResponseType::Basic(CoreErrorResponseType::InvalidGrant) => {
+                        // OAuth refresh token already expired or revoked
+                        OAuthError::TokenExpired
+                    }
+                    RevocationErrorResponseType::Basic(_)
+                    | RevocationErrorResponseType::UnsupportedTokenType => {
+                        warn!("OAuth revocation failed with error: {:?}", err);
+                        OAuthError::OAuthUnavailable
+                    }
+                },
+                RequestTokenError::Request(err) => {
+                    warn!("OAuth request failed with error: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                }
+                RequestTokenError::Parse(err, _) => {
+                    warn!("OAuth request failed to parse response: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                }
+                RequestTokenError::Other(err) => {
+                    warn!("Request failed with error: {:?}", err);
+                    OAuthError::OAuthUnavailable
+                }
+            })
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn create_http_client() -> HttpClient {
+    HttpClient::builder()
+        // Following redirects opens the client to SSRF vulnerabilities
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("HTTP client should be created")
+}
+
+#[instrument(level = "trace")]
+fn find_expires_at(expires_in: Option<Duration>) -> chrono::DateTime<Utc> {
+    Utc::now()
+        + expires_in
+            .map(|d| d.saturating_sub(Duration::from_secs(30)))
+            .unwrap_or(Duration::from_secs(300))
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_configuration_err(err: ConfigurationError) -> OAuthError {
+    error!("OpenID Connect client is misconfigured: {:?}", err);
+    OAuthError::OAuthUnavailable
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_request_token_err<RE: std::error::Error + 'static>(
+    err: RequestTokenError<RE, StandardErrorResponse<CoreErrorResponseType>>,
+) -> OAuthError {
+    match err {
+        RequestTokenError::ServerResponse(err) => match err.error() {
+            CoreErrorResponseType::InvalidGrant => {
+                // OAuth code or refresh token expired or revoked
+                OAuthError::TokenExpired
+            }
+            CoreErrorResponseType::InvalidClient
+            | CoreErrorResponseType::InvalidRequest
+            | CoreErrorResponseType::InvalidScope
+            | CoreErrorResponseType::UnauthorizedClient
+            | CoreErrorResponseType::UnsupportedGrantType
+            | CoreErrorResponseType::Extension(_) => {
+                warn!("OAuth request failed with error: {:?}", err);
+                OAuthError::OAuthUnavailable
+            }
+        },
+        RequestTokenError::Request(err) => {
+            warn!("OAuth request failed with error: {:?}", err);
+            OAuthError::OAuthUnavailable
+        }
+        RequestTokenError::Parse(err, _) => {
+            warn!("OAuth request failed to parse response: {:?}", err);
+            OAuthError::OAuthUnavailable
+        }
+        RequestTokenError::Other(err) => {
+            warn!("Request failed with error: {:?}", err);
+            OAuthError::OAuthUnavailable
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use domain::role_change_log::{
+    RoleChangeLogEntry, RoleChangeLogRepository, RoleChangeLogRepositoryError,
+};
+use domain_shared::discord::{RoleId, UserId};
+use sqlx::{PgPool, query};
+use tracing::{instrument, warn};
+
+pub struct PostgresRoleChangeLogRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PostgresRoleChangeLogRepository<'a> {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<'a> RoleChangeLogRepository for PostgresRoleChangeLogRepository<'a> {
+    #[instrument(level = "debug", err, skip(self, entry))]
+    async fn save(&self, entry: &RoleChangeLogEntry) -> Result<(), RoleChangeLogRepositoryError> {
+        let assigned = entry
+            .assigned
+            .iter()
+            .map(|role_id| role_id.0 as i64)
+            .collect::<Vec<_>>();
+        let removed = entry
+            .removed
+            .iter()
+            .map(|role_id| role_id.0 as i64)
+            .collect::<Vec<_>>();
+
+        query!(
+            "INSERT INTO role_change_log (user_id, assigned, removed, reason, low_priority, changed_at) VALUES ($1, $2, $3, $4, $5, $6)",
+            entry.user_id.0 as i64,
+            &assigned,
+            &removed,
+            entry.reason,
+            entry.low_priority,
+            entry.changed_at.naive_utc(),
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_by_user_id(
+        &self,
+        user_id: UserId,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<RoleChangeLogEntry>, RoleChangeLogRepositoryError> {
+        let records = query!(
+            "SELECT user_id, assigned, removed, reason, low_priority, changed_at FROM role_change_log WHERE user_id = $1 ORDER BY changed_at DESC, id DESC OFFSET $2 LIMIT $3",
+            user_id.0 as i64,
+            offset as i64,
+            limit as i64,
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| RoleChangeLogEntry {
+                user_id: UserId(record.user_id as u64),
+                assigned: record
+                    .assigned
+                    .into_iter()
+                    .map(|role_id| RoleId(role_id as u64))
+                    .collect(),
+                removed: record
+                    .removed
+                    .into_iter()
+                    .map(|role_id| RoleId(role_id as u64))
+                    .collect(),
+                reason: record.reason,
+                low_priority: record.low_priority,
+                changed_at: record.changed_at.and_utc(),
+            })
+            .collect())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn count_by_user_id(&self, user_id: UserId) -> Result<u64, RoleChangeLogRepositoryError> {
+        let count = query!(
+            "SELECT COUNT(*) AS \"count!\" FROM role_change_log WHERE user_id = $1",
+            user_id.0 as i64,
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(map_err)?
+        .count;
+
+        Ok(count as u64)
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> Result<u64, RoleChangeLogRepositoryError> {
+        let result = query!(
+            "DELETE FROM role_change_log WHERE user_id = $1",
+            user_id.0 as i64,
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_err(err: sqlx::Error) -> RoleChangeLogRepositoryError {
+    warn!(error = ?err, "Failed to access the role change log");
+    RoleChangeLogRepositoryError::ServiceUnavailable
+}
@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use domain::role_sync_exemption::{
+    ExemptionTarget, RoleSyncExemption, RoleSyncExemptionRepository,
+    RoleSyncExemptionRepositoryError,
+};
+use domain_shared::discord::{RoleId, UserId};
+use sqlx::{PgPool, query};
+use tracing::{instrument, warn};
+
+pub struct PostgresRoleSyncExemptionRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PostgresRoleSyncExemptionRepository<'a> {
+    #[instrument(level = "trace", skip_all)]
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<'a> RoleSyncExemptionRepository for PostgresRoleSyncExemptionRepository<'a> {
+    #[instrument(level = "debug", err, skip(self))]
+    async fn save(
+        &self,
+        exemption: &RoleSyncExemption,
+    ) -> Result<(), RoleSyncExemptionRepositoryError> {
+        let (kind, target_id) = domain_to_db_exemption_target(exemption.target);
+
+        query!(
+            "INSERT INTO role_sync_exemptions (kind, target_id, reason, created_by, created_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (kind, target_id) DO UPDATE SET reason = $3, created_by = $4, created_at = $5",
+            kind,
+            target_id,
+            exemption.reason,
+            exemption.created_by.0 as i64,
+            exemption.created_at.naive_utc(),
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn remove(
+        &self,
+        target: ExemptionTarget,
+    ) -> Result<bool, RoleSyncExemptionRepositoryError> {
+        let (kind, target_id) = domain_to_db_exemption_target(target);
+
+        let result = query!(
+            "DELETE FROM role_sync_exemptions WHERE kind = $1 AND target_id = $2",
+            kind,
+            target_id,
+        )
+        .execute(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_all(&self) -> Result<Vec<RoleSyncExemption>, RoleSyncExemptionRepositoryError> {
+        let records = query!(
+            "SELECT kind, target_id, reason, created_by, created_at FROM role_sync_exemptions ORDER BY created_at",
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(records
+            .into_iter()
+            .filter_map(|record| {
+                Some(RoleSyncExemption {
+                    target: db_to_domain_exemption_target(&record.kind, record.target_id)?,
+                    reason: record.reason,
+                    created_by: UserId(record.created_by as u64),
+                    created_at: record.created_at.and_utc(),
+                })
+            })
+            .collect())
+    }
+
+    #[instrument(level = "debug", err, skip(self))]
+    async fn find_by_target(
+        &self,
+        target: ExemptionTarget,
+    ) -> Result<Option<RoleSyncExemption>, RoleSyncExemptionRepositoryError> {
+        let (kind, target_id) = domain_to_db_exemption_target(target);
+
+        let record = query!(
+            "SELECT reason, created_by, created_at FROM role_sync_exemptions WHERE kind = $1 AND target_id = $2",
+            kind,
+            target_id,
+        )
+        .fetch_optional(self.pool)
+        .await
+        .map_err(map_err)?;
+
+        Ok(record.map(|record| RoleSyncExemption {
+            target,
+            reason: record.reason,
+            created_by: UserId(record.created_by as u64),
+            created_at: record.created_at.and_utc(),
+        }))
+    }
+}
+
+#[instrument(level = "trace")]
+fn domain_to_db_exemption_target(target: ExemptionTarget) -> (&'static str, i64) {
+    match target {
+        ExemptionTarget::User(user_id) => ("user", user_id.0 as i64),
+        ExemptionTarget::Role(role_id) => ("role", role_id.0 as i64),
+    }
+}
+
+#[instrument(level = "trace")]
+fn db_to_domain_exemption_target(kind: &str, target_id: i64) -> Option<ExemptionTarget> {
+    match kind {
+        "user" => Some(ExemptionTarget::User(UserId(target_id as u64))),
+        "role" => Some(ExemptionTarget::Role(RoleId(target_id as u64))),
+        _ => {
+            warn!(
+                kind,
+                "Unknown exemption kind stored in the database, skipping"
+            );
+            None
+        }
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn map_err(err: sqlx::Error) -> RoleSyncExemptionRepositoryError {
+    warn!(error = ?err, "Failed to access the role sync exemptions");
+    RoleSyncExemptionRepositoryError::ServiceUnavailable
+}
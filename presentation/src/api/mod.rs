@@ -6,6 +6,7 @@ use tower_http::trace::TraceLayer;
 use tracing::{info_span, instrument};
 
 pub mod oauth;
+mod page;
 
 #[instrument(level = "trace", skip())]
 pub fn create_router<L: Locator + Send + Sync + Clone + 'static>() -> Router<L> {
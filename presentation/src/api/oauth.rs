@@ -1,12 +1,16 @@
+use crate::api::page::{CallbackPage, Language};
 use crate::application_ports::Locator;
 use crate::application_ports::LocatorScope;
-use application_ports::authentication::AuthenticationError;
 use application_ports::authentication::AuthenticationPort;
+use application_ports::authentication::{
+    AuthenticationError, ConfirmedAuthenticationDto, ReplacedUserDto,
+};
 use axum::extract::Query;
 use axum::extract::State;
-use axum::http::StatusCode;
+use axum::http::HeaderMap;
 use axum::response::{IntoResponse, Redirect, Response};
 use domain_shared::authentication::{ClientCallbackToken, CsrfToken};
+use domain_shared::discord::{ChannelId, InviteLink};
 use poise::serenity_prelude as serenity;
 use poise::serenity_prelude::{CreateMessage, Mentionable};
 use serde::Deserialize;
@@ -18,24 +22,42 @@ pub struct AuthRequest {
     state: String,
 }
 
-#[instrument(level = "info", skip(service_locator, query))]
+#[instrument(level = "info", skip(service_locator, headers, query))]
 pub async fn callback_handler<L: Locator>(
     State(service_locator): State<L>,
+    headers: HeaderMap,
     Query(query): Query<AuthRequest>,
 ) -> Response {
+    let language = Language::from_headers(&headers);
+    let invite_link = service_locator.get_invite_link();
     let mut locator_scope = service_locator.create_scope().await;
     let mut authentication_port = locator_scope.create_authentication_port();
     let discord_client = service_locator.get_discord_client();
 
-    let user_id = match authentication_port
+    let ConfirmedAuthenticationDto {
+        user_id,
+        replaced_user,
+    } = match authentication_port
         .confirm_authentication(CsrfToken(query.state), ClientCallbackToken(query.code))
         .await
     {
         Ok(result) => result,
-        Err(err) => return response_auth_error(err),
+        Err(err) => return auth_error_page(err).render(language, invite_link),
     };
     let user_id = serenity::UserId::new(user_id.0);
-    let msg = response_successfully_verified(user_id, &service_locator.get_invite_link().0);
+
+    if let Some(replaced_user) = &replaced_user {
+        notify_replaced_user(
+            discord_client,
+            service_locator.get_moderation_log_channel_id(),
+            user_id,
+            replaced_user,
+            invite_link,
+        )
+        .await;
+    }
+
+    let msg = response_successfully_verified(user_id, &invite_link.0, replaced_user.is_some());
     let message = match user_id.direct_message(discord_client, msg).await {
         Ok(msg) => msg,
         Err(err) => {
@@ -44,52 +66,121 @@ pub async fn callback_handler<L: Locator>(
                 user_id = user_id.get(),
                 "Failed to send direct message confirming the verification",
             );
-            return response_successful_but_failed_to_send_message();
+            return CallbackPage::Verified.render(language, invite_link);
         }
     };
 
     Redirect::to(&message.link()).into_response()
 }
 
+/// Tells the previously verified account and the moderators that the school account was
+/// linked to another Discord account, failures are only logged
+#[instrument(level = "debug", skip(discord_client, invite_link))]
+async fn notify_replaced_user(
+    discord_client: &serenity::http::Http,
+    moderation_log_channel_id: Option<ChannelId>,
+    user_id: serenity::UserId,
+    replaced_user: &ReplacedUserDto,
+    invite_link: &InviteLink,
+) {
+    let replaced_user_id = serenity::UserId::new(replaced_user.user_id.0);
+
+    let msg = response_account_replaced(&invite_link.0);
+    if let Err(err) = replaced_user_id.direct_message(discord_client, msg).await {
+        warn!(
+            error = ?err,
+            user_id = replaced_user_id.get(),
+            "Failed to send direct message about the replaced account",
+        );
+    }
+
+    let Some(ChannelId(channel_id)) = moderation_log_channel_id else {
+        return;
+    };
+    let msg = response_account_replaced_notice(user_id, replaced_user);
+    if let Err(err) = serenity::ChannelId::new(channel_id)
+        .send_message(discord_client, msg)
+        .await
+    {
+        error!(
+            error = ?err,
+            channel_id,
+            "Failed to send the replaced account notice to the moderation log channel",
+        );
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
-fn response_auth_error(error: AuthenticationError) -> Response {
+fn auth_error_page(error: AuthenticationError) -> CallbackPage {
     match error {
         AuthenticationError::AuthenticationRequestNotFound => {
             warn!("Authentication request not found");
-            (
-                StatusCode::NOT_FOUND,
-                "Authentication request not found, the request may have been fulfilled already",
-            )
-                .into_response()
+            CallbackPage::NotFound
+        }
+        AuthenticationError::AuthenticatedUserNotFound
+        | AuthenticationError::UserAlreadyVerified
+        | AuthenticationError::EmailAlreadyUsed
+        | AuthenticationError::InvalidClassId => {
+            error!(
+                error = ?error,
+                "Unreachable: Got unrelated authentication error when confirming an authentication request"
+            );
+            CallbackPage::Unavailable
         }
         AuthenticationError::TemporaryUnavailable => {
             warn!("Authentication is temporarily unavailable");
-            (StatusCode::SERVICE_UNAVAILABLE, "Verification is currently unavailable, please contact the admin team and try later").into_response()
+            CallbackPage::Unavailable
         }
         AuthenticationError::AuthenticationRequestAlreadyConfirmed => {
             warn!("Authentication request already confirmed");
-            (
-                StatusCode::NO_CONTENT,
-                "Authentication request already confirmed, the request may have been fulfilled already",
-            )
-                .into_response()
+            CallbackPage::AlreadyConfirmed
+        }
+        AuthenticationError::AuthenticationRequestExpired => {
+            warn!("Authentication request expired");
+            CallbackPage::Expired
         }
     }
 }
 
 #[instrument(level = "trace", skip_all)]
-fn response_successful_but_failed_to_send_message() -> Response {
-    (
-        StatusCode::OK,
-        "Byl jsi úspětně ověřen, ale bot ti nemohl poslat potvrzující zprávu. Nyní se můžeš vrátit na SSPŠ Discord server.",
-        ).into_response()
+fn response_successfully_verified(
+    user_id: serenity::UserId,
+    invite_link: &str,
+    replaced_user: bool,
+) -> CreateMessage {
+    let mut content = format!(
+        "Ahoj, {}! Byl jsi úspěšně ověřen. Nyní se můžeš vrátit na [SSPŠ Discord server]({})!",
+        user_id.mention(),
+        invite_link,
+    );
+    if replaced_user {
+        content.push_str("\n\nTvůj školní účet byl dříve propojen s jiným Discord účtem. Toto propojení bylo zrušeno a nahrazeno tímto účtem.");
+    }
+    CreateMessage::default().content(content)
 }
 
 #[instrument(level = "trace", skip_all)]
-fn response_successfully_verified(user_id: serenity::UserId, invite_link: &str) -> CreateMessage {
+fn response_account_replaced(invite_link: &str) -> CreateMessage {
     CreateMessage::default().content(format!(
-        "Ahoj, {}! Byl jsi úspěšně ověřen. Nyní se můžeš vrátit na [SSPŠ Discord server]({})!",
-        user_id.mention(),
+        "Ahoj! Tvůj školní účet byl právě použit k ověření jiného Discord účtu, proto ti bylo ověření na [SSPŠ Discord serveru]({}) zrušeno. Pokud jsi to nebyl ty, kontaktuj prosím admin tým.",
         invite_link,
     ))
 }
+
+#[instrument(level = "trace", skip_all)]
+fn response_account_replaced_notice(
+    user_id: serenity::UserId,
+    replaced_user: &ReplacedUserDto,
+) -> CreateMessage {
+    let replaced_user_id = serenity::UserId::new(replaced_user.user_id.0);
+    CreateMessage::default()
+        .content(format!(
+            "School account re-verified from a different Discord account.\nNew account: {} (`{}`)\nReplaced account: {} (`{}`), archived at {}",
+            user_id.mention(),
+            user_id.get(),
+            replaced_user_id.mention(),
+            replaced_user_id.get(),
+            replaced_user.archived_at.to_rfc3339(),
+        ))
+        .allowed_mentions(serenity::CreateAllowedMentions::new())
+}
@@ -0,0 +1,180 @@
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{Html, IntoResponse, Response};
+use domain_shared::discord::InviteLink;
+use tracing::instrument;
+
+const TEMPLATE: &str = include_str!("template.html");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Czech,
+    English,
+}
+
+impl Language {
+    /// Picks the preferred supported language from the `Accept-Language` header, Czech by default
+    #[instrument(level = "trace", skip(headers))]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let Some(accept_language) = headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Language::Czech;
+        };
+
+        accept_language
+            .split(',')
+            .enumerate()
+            .filter_map(|(position, range)| {
+                let mut parts = range.trim().split(';');
+                let tag = parts.next()?.trim().to_lowercase();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |quality| quality.parse::<f32>().ok())?;
+                let language = match tag.split('-').next()? {
+                    "cs" | "sk" => Language::Czech,
+                    "en" => Language::English,
+                    _ => return None,
+                };
+                (quality > 0.0).then_some((quality, position, language))
+            })
+            .max_by(|(a_quality, a_position, _), (b_quality, b_position, _)| {
+                a_quality
+                    .total_cmp(b_quality)
+                    .then_with(|| b_position.cmp(a_position))
+            })
+            .map_or(Language::Czech, |(_, _, language)| language)
+    }
+
+    #[instrument(level = "trace")]
+    fn code(self) -> &'static str {
+        match self {
+            Language::Czech => "cs",
+            Language::English => "en",
+        }
+    }
+}
+
+/// Outcome of the OAuth callback shown to the user in the browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackPage {
+    Verified,
+    AlreadyConfirmed,
+    Expired,
+    NotFound,
+    Unavailable,
+}
+
+struct PageText {
+    icon: &'static str,
+    title: &'static str,
+    message: &'static str,
+    button: &'static str,
+}
+
+impl CallbackPage {
+    #[instrument(level = "trace")]
+    fn status(self) -> StatusCode {
+        match self {
+            CallbackPage::Verified | CallbackPage::AlreadyConfirmed => StatusCode::OK,
+            CallbackPage::Expired => StatusCode::GONE,
+            CallbackPage::NotFound => StatusCode::NOT_FOUND,
+            CallbackPage::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    #[instrument(level = "trace")]
+    fn text(self, language: Language) -> PageText {
+        match (self, language) {
+            (CallbackPage::Verified, Language::Czech) => PageText {
+                icon: "✅",
+                title: "Ověření proběhlo úspěšně",
+                message: "Byl jsi úspěšně ověřen. Bot ti nemohl poslat potvrzující zprávu, ale role ti budou přiděleny během chvíle.",
+                button: "Zpět na SSPŠ Discord server",
+            },
+            (CallbackPage::Verified, Language::English) => PageText {
+                icon: "✅",
+                title: "Verification successful",
+                message: "You have been verified. The bot could not send you a confirmation message, but your roles will be assigned shortly.",
+                button: "Back to the SSPŠ Discord server",
+            },
+            (CallbackPage::AlreadyConfirmed, Language::Czech) => PageText {
+                icon: "ℹ️",
+                title: "Odkaz už byl použit",
+                message: "Tento odkaz k ověření už byl použit. Pokud se ti role nepřidělily, vyžádej si na serveru nový odkaz.",
+                button: "Zpět na SSPŠ Discord server",
+            },
+            (CallbackPage::AlreadyConfirmed, Language::English) => PageText {
+                icon: "ℹ️",
+                title: "Link already used",
+                message: "This verification link has already been used. If you did not get your roles, request a new link on the server.",
+                button: "Back to the SSPŠ Discord server",
+            },
+            (CallbackPage::Expired, Language::Czech) => PageText {
+                icon: "⌛",
+                title: "Platnost odkazu vypršela",
+                message: "Platnost odkazu k ověření vypršela. Vrať se na SSPŠ Discord server a vyžádej si nový odkaz kliknutím na tlačítko „Ověřit se“.",
+                button: "Zpět na SSPŠ Discord server",
+            },
+            (CallbackPage::Expired, Language::English) => PageText {
+                icon: "⌛",
+                title: "Link expired",
+                message: "This verification link has expired. Return to the SSPŠ Discord server and request a new one with the “Ověřit se” button.",
+                button: "Back to the SSPŠ Discord server",
+            },
+            (CallbackPage::NotFound, Language::Czech) => PageText {
+                icon: "❓",
+                title: "Odkaz nebyl nalezen",
+                message: "Tento odkaz k ověření neexistuje. Vrať se na SSPŠ Discord server a vyžádej si nový odkaz.",
+                button: "Zpět na SSPŠ Discord server",
+            },
+            (CallbackPage::NotFound, Language::English) => PageText {
+                icon: "❓",
+                title: "Link not found",
+                message: "This verification link does not exist. Return to the SSPŠ Discord server and request a new one.",
+                button: "Back to the SSPŠ Discord server",
+            },
+            (CallbackPage::Unavailable, Language::Czech) => PageText {
+                icon: "⚠️",
+                title: "Ověření je nedostupné",
+                message: "Ověření je momentálně nedostupné. Zkus to prosím později, případně kontaktuj admin tým.",
+                button: "Zpět na SSPŠ Discord server",
+            },
+            (CallbackPage::Unavailable, Language::English) => PageText {
+                icon: "⚠️",
+                title: "Verification unavailable",
+                message: "Verification is currently unavailable. Please try again later or contact the admin team.",
+                button: "Back to the SSPŠ Discord server",
+            },
+        }
+    }
+
+    /// Renders the page with a button linking back to the guild
+    #[instrument(level = "trace", skip(invite_link))]
+    pub fn render(self, language: Language, invite_link: &InviteLink) -> Response {
+        let PageText {
+            icon,
+            title,
+            message,
+            button,
+        } = self.text(language);
+        let html = TEMPLATE
+            .replace("{{lang}}", language.code())
+            .replace("{{icon}}", icon)
+            .replace("{{title}}", &escape_html(title))
+            .replace("{{message}}", &escape_html(message))
+            .replace("{{button}}", &escape_html(button))
+            .replace("{{invite_link}}", &escape_html(&invite_link.0));
+
+        (self.status(), Html(html)).into_response()
+    }
+}
+
+#[instrument(level = "trace", skip_all)]
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -1,15 +1,24 @@
 use application_ports::authentication::AuthenticationPort;
+use application_ports::class::ClassPort;
+use application_ports::class_rollover::ClassRolloverPort;
 use application_ports::information_channel::InformationChannelPort;
+use application_ports::nickname::NicknamePort;
 use application_ports::periodic_scheduling_handler::PeriodicSchedulingHandlerPort;
+use application_ports::personal_data::PersonalDataPort;
+use application_ports::retention_handler::RetentionHandlerPort;
+use application_ports::role_log::RoleLogPort;
+use application_ports::role_reconciliation::RoleReconciliationPort;
+use application_ports::role_sync_exemption::RoleSyncExemptionPort;
 use application_ports::role_sync_job_handler::RoleSyncJobHandlerPort;
 use application_ports::user::UserPort;
 use application_ports::user_info_sync_job_handler::UserInfoSyncJobHandlerPort;
-use domain_shared::discord::InviteLink;
+use domain_shared::discord::{ChannelId, InviteLink};
 use poise::serenity_prelude as serenity;
 use std::future::Future;
 
 pub trait Locator {
     fn create_authentication_port(&self) -> impl AuthenticationPort + Send + Sync;
+    fn create_retention_handler_port(&self) -> impl RetentionHandlerPort + Send + Sync;
     fn create_periodic_scheduling_handler_port(
         &self,
     ) -> impl PeriodicSchedulingHandlerPort + Send + Sync;
@@ -19,10 +28,21 @@ pub trait Locator {
     ) -> impl UserInfoSyncJobHandlerPort + Send + Sync;
     fn create_information_channel_port(&self) -> impl InformationChannelPort + Send + Sync;
     fn create_user_port(&self) -> impl UserPort + Send + Sync;
+    fn create_class_port(&self) -> impl ClassPort + Send + Sync;
+    fn create_class_rollover_port(&self) -> impl ClassRolloverPort + Send + Sync;
+    fn create_personal_data_port(&self) -> impl PersonalDataPort + Send + Sync;
+    fn create_role_reconciliation_port(&self) -> impl RoleReconciliationPort + Send + Sync;
+    fn create_role_log_port(&self) -> impl RoleLogPort + Send + Sync;
+    fn create_nickname_port(&self) -> impl NicknamePort + Send + Sync;
+    fn create_role_sync_exemption_port(&self) -> impl RoleSyncExemptionPort + Send + Sync;
     fn create_scope(&self) -> impl Future<Output = impl LocatorScope + Send + Sync> + Send;
 
     fn get_invite_link(&self) -> &InviteLink;
+    fn get_moderation_log_channel_id(&self) -> Option<ChannelId>;
     fn get_discord_client(&self) -> &serenity::http::Http;
+    /// Called when the guild roles or the roles of the bot change, so that the role sync
+    /// does not use stale roles
+    fn invalidate_guild_role_cache(&self);
 }
 
 pub trait LocatorScope {
@@ -40,7 +40,12 @@ pub async fn handle_button_click<L: Locator>(
             response::unavailable::temporary_unavailable()
         }
         Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed)
-        | Err(AuthenticationError::AuthenticationRequestNotFound) => {
+        | Err(AuthenticationError::AuthenticationRequestExpired)
+        | Err(AuthenticationError::AuthenticationRequestNotFound)
+        | Err(AuthenticationError::AuthenticatedUserNotFound)
+        | Err(AuthenticationError::UserAlreadyVerified)
+        | Err(AuthenticationError::EmailAlreadyUsed)
+        | Err(AuthenticationError::InvalidClassId) => {
             error!(
                 "Unreachable: Got authentication request not found error when creating an authentication request",
             );
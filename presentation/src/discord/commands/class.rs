@@ -0,0 +1,212 @@
+use crate::application_ports::Locator;
+use crate::discord::response::embed::join_field;
+use crate::discord::{Context, Error, response};
+use application_ports::class::{AddClassDto, ClassDto, ClassError, ClassPort};
+use domain_shared::discord::RoleId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::CreateEmbed;
+use std::collections::BTreeMap;
+use tracing::{info, instrument, warn};
+
+#[poise::command(
+    slash_command,
+    rename = "class",
+    subcommands("list", "add", "retire", "rename"),
+    subcommand_required,
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(_ctx))]
+pub async fn command<D: Sync + Locator>(_ctx: Context<'_, D>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Lists the class catalog
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn list<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    let mut class_port = ctx.data().create_class_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Listing class catalog",
+    );
+
+    let classes = match class_port.list_classes().await {
+        Ok(classes) => classes,
+        Err(error) => {
+            warn!(error = ?error, "Failed to list classes");
+            ctx.send(response::unavailable::temporary_unavailable())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let mut active_classes = BTreeMap::<u8, Vec<String>>::new();
+    let mut retired_classes = Vec::new();
+    for ClassDto {
+        class_id,
+        display_name,
+        role_id: _,
+        year,
+        field: _,
+        active,
+    } in classes
+    {
+        let class = format!("`{}` {}", class_id, display_name);
+        if active {
+            active_classes.entry(year).or_default().push(class);
+        } else {
+            retired_classes.push(class);
+        }
+    }
+
+    let mut fields = active_classes
+        .into_iter()
+        .map(|(year, classes)| {
+            (
+                format!("{}. ročník", year),
+                join_field(&classes, ", "),
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+    if !retired_classes.is_empty() {
+        fields.push((
+            "Vyřazené třídy".to_string(),
+            join_field(&retired_classes, ", "),
+            false,
+        ));
+    }
+
+    let mut embed = CreateEmbed::default().title("Katalog tříd");
+    if fields.is_empty() {
+        embed = embed.description("Katalog neobsahuje žádné třídy.");
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .embed(embed.fields(fields)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Adds a class to the catalog or reactivates a retired one
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn add<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Class ID as in the class group mail, e.g. c2b"] class_id: String,
+    #[description = "Display name and role name, defaults to the uppercase class ID"]
+    display_name: Option<String>,
+    #[description = "Existing role of the class, created by the role sync when not set"]
+    role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let mut class_port = ctx.data().create_class_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Adding class {}",
+        class_id,
+    );
+
+    let request = AddClassDto {
+        class_id,
+        display_name,
+        role_id: role.map(|role| RoleId(role.id.get())),
+    };
+
+    let reply = match class_port.add_class(request).await {
+        Ok(class) => message(&format!(
+            "Class `{}` ({}) is active. The students will get its role in a few minutes.",
+            class.class_id, class.display_name,
+        )),
+        Err(error) => map_class_error(error),
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Retires a class, its role is no longer assigned to anybody
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn retire<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Class ID, e.g. c2b"] class_id: String,
+) -> Result<(), Error> {
+    let mut class_port = ctx.data().create_class_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Retiring class {}",
+        class_id,
+    );
+
+    let reply = match class_port.retire_class(&class_id).await {
+        Ok(()) => message(
+            "Class retired. Its role will be removed from the students on their next role sync.",
+        ),
+        Err(error) => map_class_error(error),
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Renames a class together with its role
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn rename<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Class ID, e.g. c2b"] class_id: String,
+    #[description = "New display name and role name"] display_name: String,
+) -> Result<(), Error> {
+    let mut class_port = ctx.data().create_class_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Renaming class {}",
+        class_id,
+    );
+
+    let reply = match class_port.rename_class(&class_id, &display_name).await {
+        Ok(()) => message("Class renamed."),
+        Err(error) => map_class_error(error),
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip_all)]
+fn map_class_error(error: ClassError) -> CreateReply {
+    match error {
+        ClassError::InvalidClassId => message(
+            "The class ID must be an optional field of study, the year 1-4 and the group, e.g. c2b.",
+        ),
+        ClassError::InvalidClassDetails => message("The display name must not be empty."),
+        ClassError::ClassAlreadyExists => message("The class is already active."),
+        ClassError::ClassNotFound => message("The class is not in the class catalog."),
+        ClassError::TemporaryUnavailable => {
+            warn!("Class catalog is temporarily unavailable");
+            response::unavailable::temporary_unavailable()
+        }
+    }
+}
+
+#[instrument(level = "debug", skip_all)]
+fn message(content: &str) -> CreateReply {
+    CreateReply::default()
+        .reply(true)
+        .ephemeral(true)
+        .content(content)
+}
@@ -0,0 +1,262 @@
+use crate::application_ports::Locator;
+use crate::discord::response::embed::join_lines;
+use crate::discord::{Context, Error, response};
+use application_ports::class_rollover::{
+    ClassChangeCountDto, ClassRolloverError, ClassRolloverPort, ClassRolloverReportDto,
+    ClassRolloverStartedDto, FlaggedClassChangeDto,
+};
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{
+    ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
+    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+    EditInteractionResponse, Mentionable,
+};
+use std::time::Duration;
+use tracing::{info, instrument, warn};
+
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[poise::command(
+    slash_command,
+    rename = "class-rollover",
+    subcommands("start", "report"),
+    subcommand_required,
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(_ctx))]
+pub async fn command<D: Sync + Locator>(_ctx: Context<'_, D>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Moves all students to the next school year by re-reading their classes
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn start<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Move the class roles one year up, so the channel permissions follow the students"]
+    shift_roles: bool,
+    #[description = "Start even though a rollover was started recently"] force: Option<bool>,
+) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Requesting school-year rollover",
+    );
+
+    let confirm_button_id = format!("{}-rollover-confirm", ctx.id());
+    let cancel_button_id = format!("{}-rollover-cancel", ctx.id());
+
+    let content = if shift_roles {
+        "Start the school-year rollover? The class info of every verified user will be synced \
+        and the class roles will be moved one year up, the final-year roles are reused by the first-year classes."
+    } else {
+        "Start the school-year rollover? The class info of every verified user will be synced."
+    };
+    let components = vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(&confirm_button_id)
+            .style(ButtonStyle::Danger)
+            .label("Start"),
+        CreateButton::new(&cancel_button_id)
+            .style(ButtonStyle::Secondary)
+            .label("Cancel"),
+    ])];
+    let reply_handle = ctx
+        .send(
+            CreateReply::default()
+                .reply(true)
+                .ephemeral(true)
+                .content(content)
+                .components(components),
+        )
+        .await?;
+
+    let press = {
+        let ctx_id = ctx.id().to_string();
+        ComponentInteractionCollector::new(ctx.serenity_context())
+            .author_id(ctx.author().id)
+            .channel_id(ctx.channel_id())
+            .timeout(CONFIRMATION_TIMEOUT)
+            .filter(move |press| press.data.custom_id.starts_with(&ctx_id))
+            .await
+    };
+
+    let Some(press) = press else {
+        // The confirmation timed out, remove the buttons to prevent stale clicks
+        let reply = CreateReply::default()
+            .content("The rollover was not started.")
+            .components(vec![]);
+        reply_handle.edit(ctx, reply).await?;
+        return Ok(());
+    };
+
+    if press.data.custom_id != confirm_button_id {
+        press
+            .create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content("The rollover was not started.")
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    // Reading all the users and moving the roles takes longer than Discord waits for a response
+    press.defer(ctx.serenity_context()).await?;
+
+    let mut class_rollover_port = ctx.data().create_class_rollover_port();
+    let content = match class_rollover_port
+        .start_rollover(shift_roles, force.unwrap_or(false))
+        .await
+    {
+        Ok(ClassRolloverStartedDto {
+            students,
+            users_enqueued,
+            roles_moved,
+        }) => format!(
+            "Rollover started. Classes of {} students were recorded, {} users will be synced and {} class roles were moved. \
+            Check `/class-rollover report` once the syncs finish.",
+            students, users_enqueued, roles_moved,
+        ),
+        Err(ClassRolloverError::RecentlyStarted(started_at)) => format!(
+            "A rollover was already started on {}, starting another one would move the class roles again. \
+            Use the `force` option if that is intended.",
+            started_at.to_rfc2822(),
+        ),
+        Err(error) => {
+            warn!(error = ?error, "Failed to start the school-year rollover");
+            response::unavailable::TEMPORARY_UNAVAILABLE_MESSAGE.to_string()
+        }
+    };
+
+    press
+        .edit_response(
+            ctx.serenity_context(),
+            EditInteractionResponse::new()
+                .content(content)
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Shows how the classes changed since the start of the last rollover
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn report<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    let mut class_rollover_port = ctx.data().create_class_rollover_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Accessing school-year rollover report",
+    );
+
+    ctx.defer_ephemeral().await?;
+
+    let report = match class_rollover_port.get_rollover_report().await {
+        Ok(Some(report)) => report,
+        Ok(None) => {
+            let reply = CreateReply::default()
+                .reply(true)
+                .ephemeral(true)
+                .content("No rollover has been started yet.");
+            ctx.send(reply).await?;
+            return Ok(());
+        }
+        Err(error) => {
+            warn!(error = ?error, "Failed to create the school-year rollover report");
+            ctx.send(response::unavailable::temporary_unavailable())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .embed(report_embed(report)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip_all)]
+fn report_embed(report: ClassRolloverReportDto) -> CreateEmbed {
+    let ClassRolloverReportDto {
+        taken_at,
+        students,
+        advanced,
+        graduated,
+        class_changes,
+        flagged,
+    } = report;
+
+    let class_changes = class_changes
+        .into_iter()
+        .map(
+            |ClassChangeCountDto {
+                 previous_class_id,
+                 current_class_id,
+                 count,
+             }| {
+                format!(
+                    "`{}` → `{}`: {}",
+                    previous_class_id,
+                    current_class_id.unwrap_or_else(|| "N/A".to_string()),
+                    count,
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let flagged_count = flagged.len();
+    let flagged = flagged
+        .into_iter()
+        .map(
+            |FlaggedClassChangeDto {
+                 user_id,
+                 name,
+                 previous_class_id,
+                 current_class_id,
+                 expected_class_id,
+                 not_advanced,
+             }| {
+                format!(
+                    "{} {}: `{}` → `{}`, očekáváno `{}`{}",
+                    serenity::UserId::new(user_id.0).mention(),
+                    name.unwrap_or_else(|| "neověřen".to_string()),
+                    previous_class_id,
+                    current_class_id.unwrap_or_else(|| "N/A".to_string()),
+                    expected_class_id.unwrap_or_else(|| "absolvent".to_string()),
+                    if not_advanced { " (nepostoupil)" } else { "" },
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let mut embed = CreateEmbed::default()
+        .title("Přechod do nového školního roku")
+        .description(format!(
+            "Studentů: {}\nPostoupilo: {}\nAbsolvovalo: {}\nK prověření: {}",
+            students, advanced, graduated, flagged_count,
+        ))
+        .footer(CreateEmbedFooter::new(format!(
+            "Třídy zaznamenány {}",
+            taken_at.to_rfc2822(),
+        )));
+    if !class_changes.is_empty() {
+        embed = embed.field("Změny tříd", join_lines(&class_changes), false);
+    }
+    if !flagged.is_empty() {
+        embed = embed.field("K prověření", join_lines(&flagged), false);
+    }
+
+    embed
+}
@@ -0,0 +1,123 @@
+use crate::application_ports::Locator;
+use crate::discord::response::pagination::{self, Pages};
+use crate::discord::{Context, Error};
+use application_ports::user::{AuthenticatedUserInfoDto, UserError, UserPort, UserSearchPageDto};
+use domain_shared::authentication::UserKind;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{CreateEmbed, CreateEmbedFooter, Mentionable};
+use tracing::{info, instrument};
+
+const MIN_QUERY_LENGTH: usize = 2;
+
+#[poise::command(
+    slash_command,
+    rename = "find-user",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Part of the name, email or class of the student"] query: String,
+) -> Result<(), Error> {
+    let user_port = ctx.data().create_user_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Searching users",
+    );
+
+    let query = query.trim().to_string();
+    if query.chars().count() < MIN_QUERY_LENGTH {
+        let reply = CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content(format!(
+                "The query must be at least {} characters long.",
+                MIN_QUERY_LENGTH
+            ));
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+
+    pagination::paginate(ctx, "find-user", UserSearchPages { user_port, query }).await
+}
+
+struct UserSearchPages<P> {
+    user_port: P,
+    query: String,
+}
+
+impl<P: UserPort + Send> Pages for UserSearchPages<P> {
+    type Page = UserSearchPageDto;
+    type Error = UserError;
+
+    async fn fetch_page(&mut self, offset: u64, limit: u64) -> Result<Self::Page, Self::Error> {
+        self.user_port
+            .search_users(&self.query, offset, limit)
+            .await
+    }
+
+    fn total(page: &Self::Page) -> u64 {
+        page.total
+    }
+
+    fn page_embed(&self, page: Self::Page, page_index: u64, page_count: u64) -> CreateEmbed {
+        search_embed(&self.query, page, page_index, page_count)
+    }
+}
+
+#[instrument(level = "debug", skip(search_page))]
+fn search_embed(
+    query: &str,
+    search_page: UserSearchPageDto,
+    page: u64,
+    page_count: u64,
+) -> CreateEmbed {
+    let UserSearchPageDto { users, total } = search_page;
+
+    let embed = CreateEmbed::default().title(format!("Výsledky hledání „{}“", query));
+    if users.is_empty() {
+        return embed.description("Nebyl nalezen žádný ověřený uživatel.");
+    }
+
+    let fields = users.into_iter().map(|user| {
+        let AuthenticatedUserInfoDto {
+            user_id,
+            name,
+            email,
+            kind,
+            class_id,
+            graduation_year,
+            manual_verification: _,
+            authenticated_at: _,
+        } = user;
+        let kind = match (kind, graduation_year) {
+            (UserKind::Staff, _) => "Zaměstnanec".to_string(),
+            (UserKind::Student, Some(graduation_year)) => {
+                format!("Absolvent {}", graduation_year)
+            }
+            (UserKind::Student, None) => "Student".to_string(),
+        };
+        (
+            name,
+            format!(
+                "{} (`{}`)\nEmail: {}\nTyp: {}\nTřída: {}",
+                serenity::UserId::new(user_id.0).mention(),
+                user_id.0,
+                email,
+                kind,
+                class_id.unwrap_or_else(|| "N/A".to_string()),
+            ),
+            false,
+        )
+    });
+
+    embed.fields(fields).footer(CreateEmbedFooter::new(format!(
+        "Strana {} z {}, celkem {} uživatelů",
+        page + 1,
+        page_count,
+        total,
+    )))
+}
@@ -0,0 +1,84 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::authentication::{AuthenticationError, AuthenticationPort, ForceVerifyDto};
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use tracing::{error, info, instrument, warn};
+
+#[poise::command(
+    slash_command,
+    rename = "force-verify",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: serenity::User,
+    #[description = "Full name of the student"] name: String,
+    #[description = "School email of the student"] email: String,
+    #[description = "Class of the student, e.g. 2B"] class: String,
+    #[description = "Why the student cannot verify themselves"] reason: String,
+) -> Result<(), Error> {
+    let mut authentication_port = ctx.data().create_authentication_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Manually verifying user {}",
+        target.id.get(),
+    );
+
+    let request = ForceVerifyDto {
+        user_id: UserId(target.id.get()),
+        name,
+        email,
+        class_id: class,
+        verified_by: UserId(ctx.author().id.get()),
+        reason,
+    };
+
+    let reply = match authentication_port.force_verify(request).await {
+        Ok(()) => {
+            message("User successfully verified. In a few minutes, the roles will be assigned.")
+        }
+        Err(AuthenticationError::UserAlreadyVerified) => {
+            message("User is already verified. Unverify the user first to verify them manually.")
+        }
+        Err(AuthenticationError::EmailAlreadyUsed) => {
+            message("The email is already used by another verified user.")
+        }
+        Err(AuthenticationError::InvalidClassId) => {
+            message("The class is not an active class of the class catalog.")
+        }
+        Err(AuthenticationError::TemporaryUnavailable) => {
+            warn!(
+                "Failed to manually verify user {}: Service is temporarily unavailable",
+                target.id.get(),
+            );
+            response::unavailable::temporary_unavailable()
+        }
+        Err(AuthenticationError::AuthenticationRequestNotFound)
+        | Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed)
+        | Err(AuthenticationError::AuthenticationRequestExpired)
+        | Err(AuthenticationError::AuthenticatedUserNotFound) => {
+            error!(
+                user_id = target.id.get(),
+                "Unreachable: Got unrelated authentication error when manually verifying a user",
+            );
+            response::unavailable::temporary_unavailable()
+        }
+    };
+
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip_all)]
+fn message(content: &str) -> CreateReply {
+    CreateReply::default()
+        .reply(true)
+        .ephemeral(true)
+        .content(content)
+}
@@ -3,16 +3,44 @@ use crate::discord::Error;
 use poise::Command;
 use tracing::instrument;
 
+pub mod class;
+pub mod class_rollover;
+pub mod find_user;
+pub mod force_verify;
+pub mod my_data;
+pub mod nickname_override;
+pub mod nickname_sync;
 pub mod refresh_user_roles;
+pub mod role_log;
+pub mod role_reconciliation;
+pub mod role_sync_exemption;
+pub mod unverify;
+pub mod unverify_user;
 pub mod update_information;
+pub mod user_data;
+pub mod user_history;
 pub mod user_info;
 pub mod verify;
 
 #[instrument(level = "trace", skip())]
 pub fn enabled_commands<L: Locator + Send + Sync + 'static>() -> Vec<Command<L, Error>> {
     vec![
+        class::command(),
+        class_rollover::command(),
+        find_user::command(),
+        force_verify::command(),
+        my_data::command(),
+        nickname_override::command(),
+        nickname_sync::command(),
         refresh_user_roles::command(),
+        role_log::command(),
+        role_reconciliation::command(),
+        role_sync_exemption::command(),
+        unverify::command(),
+        unverify_user::command(),
         update_information::command(),
+        user_data::command(),
+        user_history::command(),
         user_info::command(),
         verify::command(),
     ]
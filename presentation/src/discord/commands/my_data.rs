@@ -0,0 +1,141 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::personal_data::PersonalDataPort;
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude::{
+    ComponentInteractionCollector, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use std::time::Duration;
+use tracing::{info, instrument, warn};
+
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[poise::command(
+    slash_command,
+    rename = "my-data",
+    subcommands("export", "erase"),
+    subcommand_required
+)]
+#[instrument(level = "info", skip(_ctx))]
+pub async fn command<D: Sync + Locator>(_ctx: Context<'_, D>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Sends you all data the bot stores about you
+#[poise::command(slash_command)]
+#[instrument(level = "info", skip(ctx))]
+async fn export<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Exporting personal data",
+    );
+
+    ctx.defer_ephemeral().await?;
+
+    let mut personal_data_port = ctx.data().create_personal_data_port();
+    let export = match personal_data_port
+        .export_personal_data(UserId(ctx.author().id.get()))
+        .await
+    {
+        Ok(export) => export,
+        Err(error) => {
+            warn!(error = ?error, "Failed to export personal data");
+            ctx.send(response::unavailable::temporary_unavailable())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let attachment = response::personal_data::export_attachment(&export);
+    let message = response::personal_data::export_message(attachment);
+    let content = match ctx
+        .author()
+        .direct_message(ctx.serenity_context(), message)
+        .await
+    {
+        Ok(_) => response::personal_data::export_sent(),
+        Err(error) => {
+            warn!(error = ?error, "Failed to send the personal data export");
+            response::personal_data::export_not_delivered()
+        }
+    };
+
+    let reply = CreateReply::default()
+        .reply(true)
+        .ephemeral(true)
+        .content(content);
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Deletes all data the bot stores about you and removes your student roles
+#[poise::command(slash_command)]
+#[instrument(level = "info", skip(ctx))]
+async fn erase<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Requesting personal data erasure",
+    );
+
+    let confirm_button_id = format!("{}-erase-confirm", ctx.id());
+    let cancel_button_id = format!("{}-erase-cancel", ctx.id());
+
+    let reply = response::personal_data::erase_confirmation(&confirm_button_id, &cancel_button_id);
+    let reply_handle = ctx.send(reply).await?;
+
+    let press = {
+        let ctx_id = ctx.id().to_string();
+        ComponentInteractionCollector::new(ctx.serenity_context())
+            .author_id(ctx.author().id)
+            .channel_id(ctx.channel_id())
+            .timeout(CONFIRMATION_TIMEOUT)
+            .filter(move |press| press.data.custom_id.starts_with(&ctx_id))
+            .await
+    };
+
+    let press = match press {
+        Some(press) => press,
+        None => {
+            // The confirmation timed out, remove the buttons to prevent stale clicks
+            let reply = CreateReply::default()
+                .content(response::personal_data::erase_cancelled())
+                .components(vec![]);
+            reply_handle.edit(ctx, reply).await?;
+            return Ok(());
+        }
+    };
+
+    let content = if press.data.custom_id != confirm_button_id {
+        info!(user_id = ctx.author().id.get(), "Erasure cancelled");
+        response::personal_data::erase_cancelled()
+    } else {
+        let mut personal_data_port = ctx.data().create_personal_data_port();
+        match personal_data_port
+            .erase_personal_data(UserId(ctx.author().id.get()))
+            .await
+        {
+            Ok(_) => response::personal_data::erased(),
+            Err(error) => {
+                warn!(error = ?error, "Failed to erase personal data");
+                response::unavailable::TEMPORARY_UNAVAILABLE_MESSAGE
+            }
+        }
+    };
+
+    press
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::nickname::NicknamePort;
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use tracing::{info, instrument, warn};
+
+#[derive(Debug, poise::ChoiceParameter)]
+pub enum NicknameOverride {
+    #[name = "Follow the preference of the user"]
+    Default,
+    #[name = "Always set the nickname"]
+    Enforce,
+    #[name = "Never change the nickname"]
+    Disable,
+}
+
+/// Overrides whether the nickname of the user is set from their school account
+#[poise::command(
+    slash_command,
+    rename = "nickname-override",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: serenity::User,
+    #[description = "Whether the nickname is set"] mode: NicknameOverride,
+) -> Result<(), Error> {
+    let mut nickname_port = ctx.data().create_nickname_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Overriding nickname sync of user {} with {:?}",
+        target.id.get(),
+        mode,
+    );
+
+    let admin_override = match mode {
+        NicknameOverride::Default => None,
+        NicknameOverride::Enforce => Some(true),
+        NicknameOverride::Disable => Some(false),
+    };
+
+    let reply = match nickname_port
+        .set_nickname_override(UserId(target.id.get()), admin_override)
+        .await
+    {
+        Ok(()) => CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content("Nickname override saved. It applies on the next role sync of the user, which was requested."),
+        Err(error) => {
+            warn!(error = ?error, "Failed to override nickname sync");
+            response::unavailable::temporary_unavailable()
+        }
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::nickname::NicknamePort;
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use tracing::{info, instrument, warn};
+
+/// Sets whether the bot keeps your nickname in line with your school account
+#[poise::command(slash_command, rename = "nickname-sync")]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Whether the nickname is set from your school account"] enabled: bool,
+) -> Result<(), Error> {
+    let mut nickname_port = ctx.data().create_nickname_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Setting nickname sync to {}",
+        enabled,
+    );
+
+    let reply = match nickname_port
+        .set_nickname_opt_out(UserId(ctx.author().id.get()), !enabled)
+        .await
+    {
+        Ok(()) => CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content(if enabled {
+                "Přezdívka ti bude během chvíle nastavena podle školního účtu."
+            } else {
+                "Přezdívka ti už nebude nastavována podle školního účtu. Současnou přezdívku si můžeš změnit sám."
+            }),
+        Err(error) => {
+            warn!(error = ?error, "Failed to set nickname sync");
+            response::unavailable::temporary_unavailable()
+        }
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
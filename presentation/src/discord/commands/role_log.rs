@@ -0,0 +1,124 @@
+use crate::application_ports::Locator;
+use crate::discord::response::pagination::{self, Pages};
+use crate::discord::{Context, Error};
+use application_ports::role_log::{RoleChangeDto, RoleLogError, RoleLogPageDto, RoleLogPort};
+use domain_shared::discord::{RoleId, UserId};
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{CreateEmbed, CreateEmbedFooter, Mentionable};
+use tracing::{info, instrument};
+
+/// Shows the role changes applied to the user by the role sync
+#[poise::command(
+    slash_command,
+    rename = "role-log",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: serenity::User,
+) -> Result<(), Error> {
+    let role_log_port = ctx.data().create_role_log_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Accessing role log of user {}",
+        target.id.get(),
+    );
+
+    let user_id = UserId(target.id.get());
+    pagination::paginate(
+        ctx,
+        "role-log",
+        RoleLogPages {
+            role_log_port,
+            user_id,
+        },
+    )
+    .await
+}
+
+struct RoleLogPages<P> {
+    role_log_port: P,
+    user_id: UserId,
+}
+
+impl<P: RoleLogPort + Send> Pages for RoleLogPages<P> {
+    type Page = RoleLogPageDto;
+    type Error = RoleLogError;
+
+    async fn fetch_page(&mut self, offset: u64, limit: u64) -> Result<Self::Page, Self::Error> {
+        self.role_log_port
+            .get_role_log(self.user_id, offset, limit)
+            .await
+    }
+
+    fn total(page: &Self::Page) -> u64 {
+        page.total
+    }
+
+    fn page_embed(&self, page: Self::Page, page_index: u64, page_count: u64) -> CreateEmbed {
+        role_log_embed(self.user_id, page, page_index, page_count)
+    }
+}
+
+#[instrument(level = "debug", skip(role_log_page))]
+fn role_log_embed(
+    user_id: UserId,
+    role_log_page: RoleLogPageDto,
+    page: u64,
+    page_count: u64,
+) -> CreateEmbed {
+    let RoleLogPageDto { entries, total } = role_log_page;
+
+    let embed = CreateEmbed::default().title(format!("Změny rolí uživatele {}", user_id.0));
+    if entries.is_empty() {
+        return embed.description(format!(
+            "{}\nUživateli nebyly změněny žádné role.",
+            serenity::UserId::new(user_id.0).mention(),
+        ));
+    }
+    let embed = embed.description(serenity::UserId::new(user_id.0).mention().to_string());
+
+    let fields = entries.into_iter().map(|entry| {
+        let RoleChangeDto {
+            assigned,
+            removed,
+            reason,
+            low_priority,
+            changed_at,
+        } = entry;
+        (
+            changed_at.to_rfc2822(),
+            format!(
+                "Přidané: {}\nOdebrané: {}\nDůvod: {}\nPriorita: {}",
+                format_roles(&assigned),
+                format_roles(&removed),
+                reason,
+                if low_priority { "nízká" } else { "vysoká" },
+            ),
+            false,
+        )
+    });
+
+    embed.fields(fields).footer(CreateEmbedFooter::new(format!(
+        "Strana {} z {}, celkem {} změn",
+        page + 1,
+        page_count,
+        total,
+    )))
+}
+
+#[instrument(level = "trace")]
+fn format_roles(role_ids: &[RoleId]) -> String {
+    if role_ids.is_empty() {
+        return "žádné".to_string();
+    }
+
+    role_ids
+        .iter()
+        .map(|role_id| serenity::RoleId::new(role_id.0).mention().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
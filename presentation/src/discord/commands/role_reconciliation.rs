@@ -0,0 +1,106 @@
+use crate::application_ports::Locator;
+use crate::discord::response::embed::join_lines;
+use crate::discord::{Context, Error, response};
+use crate::report::role_reconciliation_csv;
+use application_ports::role_reconciliation::{
+    RoleChangeCountDto, RoleReconciliationPort, RoleReconciliationReportDto,
+};
+use poise::CreateReply;
+use poise::serenity_prelude::{CreateAttachment, CreateEmbed};
+use tracing::{info, instrument, warn};
+
+/// Shows what the role sync would change for every guild member, without changing anything
+#[poise::command(
+    slash_command,
+    rename = "role-reconciliation",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Running role reconciliation dry run",
+    );
+
+    // Walking the whole guild takes a while
+    ctx.defer_ephemeral().await?;
+
+    let mut role_reconciliation_port = ctx.data().create_role_reconciliation_port();
+    let report = match role_reconciliation_port.reconcile_roles_dry_run().await {
+        Ok(report) => report,
+        Err(error) => {
+            warn!(error = ?error, "Failed to run the role reconciliation dry run");
+            ctx.send(response::unavailable::temporary_unavailable())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let attachment =
+        CreateAttachment::bytes(role_reconciliation_csv(&report), "role-reconciliation.csv");
+    ctx.send(
+        CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .embed(report_embed(&report))
+            .attachment(attachment),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip_all)]
+fn report_embed(report: &RoleReconciliationReportDto) -> CreateEmbed {
+    let RoleReconciliationReportDto {
+        members,
+        classes_without_role,
+        role_changes,
+        member_diffs,
+    } = report;
+
+    let role_changes = role_changes
+        .iter()
+        .map(
+            |RoleChangeCountDto {
+                 role_id,
+                 role_name,
+                 assigned,
+                 removed,
+             }| {
+                format!(
+                    "{}: +{} / −{}",
+                    role_name.clone().unwrap_or_else(|| role_id.0.to_string()),
+                    assigned,
+                    removed,
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let mut embed = CreateEmbed::default()
+        .title("Role reconciliation dry run")
+        .description(format!(
+            "Members: {}\nMembers with role changes: {}\nNothing was changed, the changes of the individual members are attached.",
+            members,
+            member_diffs.len(),
+        ));
+    if !role_changes.is_empty() {
+        embed = embed.field("Assigned / removed roles", join_lines(&role_changes), false);
+    }
+    if !classes_without_role.is_empty() {
+        embed = embed.field(
+            "Classes without a role",
+            join_lines(
+                &classes_without_role
+                    .iter()
+                    .map(|class_id| format!("`{}`", class_id))
+                    .collect::<Vec<_>>(),
+            ),
+            false,
+        );
+    }
+
+    embed
+}
@@ -0,0 +1,207 @@
+use crate::application_ports::Locator;
+use crate::discord::response::embed::join_lines;
+use crate::discord::{Context, Error, response};
+use application_ports::role_sync_exemption::{
+    ExemptionTargetDto, RoleSyncExemptionDto, RoleSyncExemptionError, RoleSyncExemptionPort,
+};
+use domain_shared::discord::{RoleId, UserId};
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{CreateEmbed, Mentionable};
+use tracing::{info, instrument, warn};
+
+#[poise::command(
+    slash_command,
+    rename = "role-sync-exemption",
+    subcommands("list", "add", "remove"),
+    subcommand_required,
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(_ctx))]
+pub async fn command<D: Sync + Locator>(_ctx: Context<'_, D>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Lists the members and roles exempt from the role sync
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn list<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    let mut role_sync_exemption_port = ctx.data().create_role_sync_exemption_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Listing role sync exemptions",
+    );
+
+    let exemptions = match role_sync_exemption_port.list_exemptions().await {
+        Ok(exemptions) => exemptions,
+        Err(error) => {
+            ctx.send(map_exemption_error(error)).await?;
+            return Ok(());
+        }
+    };
+
+    let (user_exemptions, role_exemptions): (Vec<_>, Vec<_>) = exemptions
+        .iter()
+        .partition(|exemption| matches!(exemption.target, ExemptionTargetDto::User(_)));
+
+    let mut embed = CreateEmbed::default().title("Výjimky ze synchronizace rolí");
+    if exemptions.is_empty() {
+        embed = embed.description("Žádný uživatel ani role nemá výjimku.");
+    }
+    if !user_exemptions.is_empty() {
+        embed = embed.field(
+            "Uživatelé",
+            join_lines(
+                &user_exemptions
+                    .into_iter()
+                    .map(format_exemption)
+                    .collect::<Vec<_>>(),
+            ),
+            false,
+        );
+    }
+    if !role_exemptions.is_empty() {
+        embed = embed.field(
+            "Role",
+            join_lines(
+                &role_exemptions
+                    .into_iter()
+                    .map(format_exemption)
+                    .collect::<Vec<_>>(),
+            ),
+            false,
+        );
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .embed(embed),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Exempts a member or everybody with a role from the role sync
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn add<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Why the roles must not be changed"] reason: String,
+    #[description = "Exempted member"] target: Option<serenity::User>,
+    #[description = "Role whose members are exempted"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let mut role_sync_exemption_port = ctx.data().create_role_sync_exemption_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Adding role sync exemption",
+    );
+
+    let Some(target) = exemption_target(target, role) else {
+        ctx.send(message("Select either a target or a role."))
+            .await?;
+        return Ok(());
+    };
+
+    let reply = match role_sync_exemption_port
+        .add_exemption(target, reason, UserId(ctx.author().id.get()))
+        .await
+    {
+        Ok(()) => message("Exemption added. The roles will no longer be changed by the role sync."),
+        Err(error) => map_exemption_error(error),
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Removes the role sync exemption of a member or a role
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn remove<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Exempted member"] target: Option<serenity::User>,
+    #[description = "Role whose members are exempted"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let mut role_sync_exemption_port = ctx.data().create_role_sync_exemption_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Removing role sync exemption",
+    );
+
+    let Some(target) = exemption_target(target, role) else {
+        ctx.send(message("Select either a target or a role."))
+            .await?;
+        return Ok(());
+    };
+
+    let reply = match role_sync_exemption_port.remove_exemption(target).await {
+        Ok(()) => message("Exemption removed. The roles will be synced again in a few minutes."),
+        Err(error) => map_exemption_error(error),
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip_all)]
+fn exemption_target(
+    target: Option<serenity::User>,
+    role: Option<serenity::Role>,
+) -> Option<ExemptionTargetDto> {
+    match (target, role) {
+        (Some(target), None) => Some(ExemptionTargetDto::User(UserId(target.id.get()))),
+        (None, Some(role)) => Some(ExemptionTargetDto::Role(RoleId(role.id.get()))),
+        _ => None,
+    }
+}
+
+/// Formats the exemption as a line, also used by `/user-info`
+#[instrument(level = "debug", skip_all)]
+pub(crate) fn format_exemption(exemption: &RoleSyncExemptionDto) -> String {
+    let RoleSyncExemptionDto {
+        target,
+        reason,
+        created_by,
+        created_at,
+    } = exemption;
+    let target = match target {
+        ExemptionTargetDto::User(user_id) => serenity::UserId::new(user_id.0).mention().to_string(),
+        ExemptionTargetDto::Role(role_id) => serenity::RoleId::new(role_id.0).mention().to_string(),
+    };
+
+    format!(
+        "{}: {} ({}, {})",
+        target,
+        reason,
+        serenity::UserId::new(created_by.0).mention(),
+        created_at.to_rfc2822(),
+    )
+}
+
+#[instrument(level = "debug", skip_all)]
+fn map_exemption_error(error: RoleSyncExemptionError) -> CreateReply {
+    match error {
+        RoleSyncExemptionError::ExemptionNotFound => message("There is no such exemption."),
+        RoleSyncExemptionError::TemporaryUnavailable => {
+            warn!("Role sync exemptions are temporarily unavailable");
+            response::unavailable::temporary_unavailable()
+        }
+    }
+}
+
+#[instrument(level = "debug", skip_all)]
+fn message(content: &str) -> CreateReply {
+    CreateReply::default()
+        .reply(true)
+        .ephemeral(true)
+        .content(content)
+}
@@ -0,0 +1,107 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::authentication::AuthenticationError;
+use application_ports::authentication::AuthenticationPort;
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{
+    ComponentInteractionCollector, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use std::time::Duration;
+use tracing::{error, info, instrument};
+
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[poise::command(slash_command, rename = "unverify")]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Requesting unverification",
+    );
+
+    let confirm_button_id = format!("{}-unverify-confirm", ctx.id());
+    let cancel_button_id = format!("{}-unverify-cancel", ctx.id());
+
+    let reply = response::unverify::confirmation(&confirm_button_id, &cancel_button_id);
+    let reply_handle = ctx.send(reply).await?;
+
+    let press = {
+        let ctx_id = ctx.id().to_string();
+        ComponentInteractionCollector::new(ctx.serenity_context())
+            .author_id(ctx.author().id)
+            .channel_id(ctx.channel_id())
+            .timeout(CONFIRMATION_TIMEOUT)
+            .filter(move |press| press.data.custom_id.starts_with(&ctx_id))
+            .await
+    };
+
+    let press = match press {
+        Some(press) => press,
+        None => {
+            // The confirmation timed out, remove the buttons to prevent stale clicks
+            let reply = CreateReply::default()
+                .content(response::unverify::cancelled())
+                .components(vec![]);
+            reply_handle.edit(ctx, reply).await?;
+            return Ok(());
+        }
+    };
+
+    if press.data.custom_id != confirm_button_id {
+        info!(user_id = ctx.author().id.get(), "Unverification cancelled");
+        respond_to_press(ctx, &press, response::unverify::cancelled()).await?;
+        return Ok(());
+    }
+
+    let mut authentication_port = ctx.data().create_authentication_port();
+
+    let content = match authentication_port
+        .unverify(UserId(ctx.author().id.get()))
+        .await
+    {
+        Ok(()) => response::unverify::unverified(),
+        Err(AuthenticationError::AuthenticatedUserNotFound) => response::unverify::not_verified(),
+        Err(AuthenticationError::TemporaryUnavailable) => {
+            response::unavailable::TEMPORARY_UNAVAILABLE_MESSAGE
+        }
+        Err(AuthenticationError::AuthenticationRequestNotFound)
+        | Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed)
+        | Err(AuthenticationError::AuthenticationRequestExpired)
+        | Err(AuthenticationError::UserAlreadyVerified)
+        | Err(AuthenticationError::EmailAlreadyUsed)
+        | Err(AuthenticationError::InvalidClassId) => {
+            error!(
+                user_id = ctx.author().id.get(),
+                "Unreachable: Got unrelated authentication error when unverifying a user",
+            );
+            response::unavailable::TEMPORARY_UNAVAILABLE_MESSAGE
+        }
+    };
+
+    respond_to_press(ctx, &press, content).await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip(ctx, press))]
+async fn respond_to_press<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    press: &serenity::ComponentInteraction,
+    content: &str,
+) -> Result<(), Error> {
+    press
+        .create_response(
+            ctx.serenity_context(),
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::authentication::AuthenticationError;
+use application_ports::authentication::AuthenticationPort;
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use tracing::{error, info, instrument, warn};
+
+#[poise::command(
+    slash_command,
+    rename = "unverify-user",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: serenity::User,
+) -> Result<(), Error> {
+    let mut authentication_port = ctx.data().create_authentication_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Unverifying user {}",
+        target.id.get(),
+    );
+
+    let user_id = UserId(target.id.get());
+
+    let reply = match authentication_port.unverify(user_id).await {
+        Ok(()) => CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content("User successfully unverified. In a few minutes, the roles will be removed."),
+        Err(AuthenticationError::AuthenticatedUserNotFound) => CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content("User is not verified."),
+        Err(AuthenticationError::TemporaryUnavailable) => {
+            warn!(
+                "Failed to unverify user {}: Service is temporarily unavailable",
+                target.id.get(),
+            );
+            response::unavailable::temporary_unavailable()
+        }
+        Err(AuthenticationError::AuthenticationRequestNotFound)
+        | Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed)
+        | Err(AuthenticationError::AuthenticationRequestExpired)
+        | Err(AuthenticationError::UserAlreadyVerified)
+        | Err(AuthenticationError::EmailAlreadyUsed)
+        | Err(AuthenticationError::InvalidClassId) => {
+            error!(
+                user_id = target.id.get(),
+                "Unreachable: Got unrelated authentication error when unverifying a user",
+            );
+            response::unavailable::temporary_unavailable()
+        }
+    };
+
+    ctx.send(reply).await?;
+
+    Ok(())
+}
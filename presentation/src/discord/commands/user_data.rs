@@ -0,0 +1,103 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::personal_data::{PersonalDataErasureDto, PersonalDataPort};
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use tracing::{info, instrument, warn};
+
+#[poise::command(
+    slash_command,
+    rename = "user-data",
+    subcommands("export", "erase"),
+    subcommand_required,
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(_ctx))]
+pub async fn command<D: Sync + Locator>(_ctx: Context<'_, D>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Exports all data stored about the user, e.g. for a request received by email
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn export<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: serenity::User,
+) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Exporting personal data of user {}",
+        target.id.get(),
+    );
+
+    ctx.defer_ephemeral().await?;
+
+    let mut personal_data_port = ctx.data().create_personal_data_port();
+    let reply = match personal_data_port
+        .export_personal_data(UserId(target.id.get()))
+        .await
+    {
+        Ok(export) => CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content("Personal data export of the user is attached.")
+            .attachment(response::personal_data::export_attachment(&export)),
+        Err(error) => {
+            warn!(error = ?error, "Failed to export personal data");
+            response::unavailable::temporary_unavailable()
+        }
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Erases all data stored about the user, e.g. for a request received by email
+#[poise::command(slash_command, required_permissions = "ADMINISTRATOR")]
+#[instrument(level = "info", skip(ctx))]
+async fn erase<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: serenity::User,
+) -> Result<(), Error> {
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Erasing personal data of user {}",
+        target.id.get(),
+    );
+
+    let mut personal_data_port = ctx.data().create_personal_data_port();
+    let reply = match personal_data_port
+        .erase_personal_data(UserId(target.id.get()))
+        .await
+    {
+        Ok(PersonalDataErasureDto {
+            authenticated_user_removed,
+            archived_users_removed,
+            authentication_requests_removed,
+            role_changes_removed,
+            nickname_settings_removed,
+            role_sync_exemption_removed,
+        }) => CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .content(format!(
+                "Personal data erased. Verified user removed: {}, archived identities removed: {}, authentication requests removed: {}, role log entries removed: {}, nickname settings removed: {}, role sync exemption removed: {}. The roles will be removed in a few minutes.",
+                if authenticated_user_removed { "yes" } else { "no" },
+                archived_users_removed,
+                authentication_requests_removed,
+                role_changes_removed,
+                if nickname_settings_removed { "yes" } else { "no" },
+                if role_sync_exemption_removed { "yes" } else { "no" },
+            )),
+        Err(error) => {
+            warn!(error = ?error, "Failed to erase personal data");
+            response::unavailable::temporary_unavailable()
+        }
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use crate::application_ports::Locator;
+use crate::discord::{Context, Error, response};
+use application_ports::user::{ArchivedUserInfoDto, UserHistoryQuery, UserPort};
+use domain_shared::discord::UserId;
+use poise::CreateReply;
+use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{CreateEmbed, CreateEmbedFooter, Mentionable};
+use tracing::{info, instrument, warn};
+
+/// Discord allows at most 25 fields in an embed
+const MAX_HISTORY_FIELDS: usize = 25;
+
+#[poise::command(
+    slash_command,
+    rename = "user-history",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[instrument(level = "info", skip(ctx))]
+pub async fn command<D: Sync + Locator>(
+    ctx: Context<'_, D>,
+    #[description = "Selected target"] target: Option<serenity::User>,
+    #[description = "School email of the student"] email: Option<String>,
+) -> Result<(), Error> {
+    let mut user_port = ctx.data().create_user_port();
+
+    info!(
+        guild_id = ctx.guild_id().map(|id| id.get()),
+        user_id = ctx.author().id.get(),
+        "Accessing user history",
+    );
+
+    let query = match (target, email) {
+        (Some(target), None) => UserHistoryQuery::UserId(UserId(target.id.get())),
+        (None, Some(email)) => UserHistoryQuery::Email(email.trim().to_string()),
+        _ => {
+            ctx.send(message("Select either a target or an email."))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let archived_users = match user_port.get_user_history(query.clone()).await {
+        Ok(archived_users) => archived_users,
+        Err(error) => {
+            warn!(error = ?error, "Failed to fetch user history");
+            ctx.send(response::unavailable::temporary_unavailable())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let title = match &query {
+        UserHistoryQuery::UserId(user_id) => format!("Historie uživatele {}", user_id.0),
+        UserHistoryQuery::Email(email) => format!("Historie emailu {}", email),
+    };
+
+    if archived_users.is_empty() {
+        let embed = CreateEmbed::default()
+            .title(title)
+            .description("Žádné archivované identity.");
+        ctx.send(
+            CreateReply::default()
+                .reply(true)
+                .ephemeral(true)
+                .embed(embed),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let total = archived_users.len();
+    let fields = archived_users
+        .into_iter()
+        .take(MAX_HISTORY_FIELDS)
+        .map(|archived_user| {
+            let ArchivedUserInfoDto {
+                user_id,
+                name,
+                email,
+                class_id,
+                authenticated_at,
+                archived_at,
+            } = archived_user;
+            (
+                format!("Archivováno {}", archived_at.to_rfc2822()),
+                format!(
+                    "{} (`{}`)\nJméno: {}\nEmail: {}\nTřída: {}\nOvěřen: {}",
+                    serenity::UserId::new(user_id.0).mention(),
+                    user_id.0,
+                    name,
+                    email,
+                    class_id.unwrap_or_else(|| "N/A".to_string()),
+                    authenticated_at.to_rfc2822(),
+                ),
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let embed = CreateEmbed::default()
+        .title(title)
+        .fields(fields)
+        .footer(CreateEmbedFooter::new(format!(
+            "Zobrazeno {} z {} archivovaných identit",
+            total.min(MAX_HISTORY_FIELDS),
+            total,
+        )));
+
+    ctx.send(
+        CreateReply::default()
+            .reply(true)
+            .ephemeral(true)
+            .embed(embed),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[instrument(level = "debug", skip_all)]
+fn message(content: &str) -> CreateReply {
+    CreateReply::default()
+        .reply(true)
+        .ephemeral(true)
+        .content(content)
+}
@@ -1,7 +1,10 @@
 use crate::application_ports::Locator;
+use crate::discord::commands::role_sync_exemption::format_exemption;
 use crate::discord::{Context, Error};
-use application_ports::user::AuthenticatedUserInfoDto;
+use application_ports::role_sync_exemption::RoleSyncExemptionPort;
 use application_ports::user::UserPort;
+use application_ports::user::{AuthenticatedUserInfoDto, ManualVerificationDto};
+use domain_shared::authentication::UserKind;
 use domain_shared::discord::UserId;
 use poise::CreateReply;
 use poise::serenity_prelude as serenity;
@@ -51,12 +54,19 @@ pub async fn command<D: Sync + Locator>(
         }
     };
 
+    let manual_verification = user_info
+        .as_ref()
+        .and_then(|user_info| user_info.manual_verification.clone());
+
     let embed = match user_info {
         Some(AuthenticatedUserInfoDto {
             user_id,
             name,
             email,
+            kind: UserKind::Student,
             class_id,
+            graduation_year: None,
+            manual_verification: _,
             authenticated_at,
         }) => CreateEmbed::default()
             .title("Ověřený student".to_string())
@@ -66,6 +76,7 @@ pub async fn command<D: Sync + Locator>(
                 ("User ID", user_id.0.to_string(), false),
                 ("Jméno", name, false),
                 ("Email", email.to_string(), false),
+                ("Typ", "Student".to_string(), false),
                 (
                     "Třída",
                     class_id.unwrap_or_else(|| "N/A".to_string()),
@@ -73,6 +84,52 @@ pub async fn command<D: Sync + Locator>(
                 ),
                 ("Ověřen", authenticated_at.to_rfc2822(), false),
             ]),
+        Some(AuthenticatedUserInfoDto {
+            user_id,
+            name,
+            email,
+            kind: UserKind::Student,
+            class_id,
+            graduation_year: Some(graduation_year),
+            manual_verification: _,
+            authenticated_at,
+        }) => CreateEmbed::default()
+            .title("Absolvent".to_string())
+            .thumbnail(target.face())
+            .fields(vec![
+                ("", target.mention().to_string(), false),
+                ("User ID", user_id.0.to_string(), false),
+                ("Jméno", name, false),
+                ("Email", email.to_string(), false),
+                ("Typ", "Absolvent".to_string(), false),
+                (
+                    "Poslední třída",
+                    class_id.unwrap_or_else(|| "N/A".to_string()),
+                    false,
+                ),
+                ("Rok absolvování", graduation_year.to_string(), false),
+                ("Ověřen", authenticated_at.to_rfc2822(), false),
+            ]),
+        Some(AuthenticatedUserInfoDto {
+            user_id,
+            name,
+            email,
+            kind: UserKind::Staff,
+            class_id: _,
+            graduation_year: _,
+            manual_verification: _,
+            authenticated_at,
+        }) => CreateEmbed::default()
+            .title("Ověřený zaměstnanec".to_string())
+            .thumbnail(target.face())
+            .fields(vec![
+                ("", target.mention().to_string(), false),
+                ("User ID", user_id.0.to_string(), false),
+                ("Jméno", name, false),
+                ("Email", email.to_string(), false),
+                ("Typ", "Zaměstnanec".to_string(), false),
+                ("Ověřen", authenticated_at.to_rfc2822(), false),
+            ]),
         None => CreateEmbed::default()
             .title("Neověřený uživatel".to_string())
             .thumbnail(target.face())
@@ -82,6 +139,39 @@ pub async fn command<D: Sync + Locator>(
             ]),
     };
 
+    let embed = match manual_verification {
+        Some(ManualVerificationDto {
+            verified_by,
+            reason,
+        }) => embed.fields(vec![
+            (
+                "Ověřen ručně",
+                serenity::UserId::new(verified_by.0).mention().to_string(),
+                false,
+            ),
+            ("Důvod", reason, false),
+        ]),
+        None => embed,
+    };
+
+    let mut role_sync_exemption_port = ctx.data().create_role_sync_exemption_port();
+    let embed = match role_sync_exemption_port.find_user_exemptions(user_id).await {
+        Ok(exemptions) if exemptions.is_empty() => embed,
+        Ok(exemptions) => embed.field(
+            "Výjimky ze synchronizace rolí",
+            exemptions
+                .iter()
+                .map(format_exemption)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            false,
+        ),
+        Err(error) => {
+            warn!(error = ?error, "Failed to fetch role sync exemptions");
+            embed.field("Výjimky ze synchronizace rolí", "N/A", false)
+        }
+    };
+
     let reply = CreateReply::default()
         .reply(true)
         .ephemeral(true)
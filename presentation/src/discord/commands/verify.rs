@@ -35,13 +35,18 @@ pub async fn command<D: Sync + Locator>(ctx: Context<'_, D>) -> Result<(), Error
         Err(AuthenticationError::TemporaryUnavailable) => {
             response::unavailable::temporary_unavailable()
         }
-        Err(AuthenticationError::AuthenticationRequestNotFound) => {
+        Err(AuthenticationError::AuthenticationRequestNotFound)
+        | Err(AuthenticationError::AuthenticatedUserNotFound) => {
             error!(
                 "Unreachable: Got authentication request not found error when creating an authentication request",
             );
             response::unavailable::temporary_unavailable()
         }
-        Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed) => {
+        Err(AuthenticationError::AuthenticationRequestAlreadyConfirmed)
+        | Err(AuthenticationError::AuthenticationRequestExpired)
+        | Err(AuthenticationError::UserAlreadyVerified)
+        | Err(AuthenticationError::EmailAlreadyUsed)
+        | Err(AuthenticationError::InvalidClassId) => {
             error!(
                 user_id = user.id.get(),
                 "Unreachable: Got authentication request already confirmed error when creating an authentication request",
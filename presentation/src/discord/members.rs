@@ -0,0 +1,34 @@
+use crate::application_ports::Locator;
+use crate::discord::Error;
+use application_ports::user::UserPort;
+use domain_shared::discord::UserId;
+use poise::serenity_prelude as serenity;
+use tracing::{info, instrument, warn};
+
+/// Syncs the roles of the joined member right away, so that members verified before
+/// (e.g. after leaving and rejoining the guild) do not wait for the periodic role sync
+#[instrument(level = "info", skip(member, locator))]
+pub async fn handle_member_addition<L: Locator>(
+    member: &serenity::Member,
+    locator: &L,
+) -> Result<(), Error> {
+    if member.user.bot {
+        return Ok(());
+    }
+
+    info!(
+        guild_id = member.guild_id.get(),
+        user_id = member.user.id.get(),
+        "Member joined the guild, requesting role sync",
+    );
+
+    let mut user_port = locator.create_user_port();
+    if let Err(error) = user_port
+        .sync_joined_member_roles(UserId(member.user.id.get()))
+        .await
+    {
+        warn!(error = ?error, "Failed to request role sync of the joined member");
+    }
+
+    Ok(())
+}
@@ -5,6 +5,7 @@ use tracing::instrument;
 
 mod buttons;
 pub mod commands;
+mod members;
 mod response;
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
@@ -57,5 +58,23 @@ async fn event_handler<L: Locator>(
         buttons::handle_button_click(ctx, component_interaction, framework, locator).await?
     }
 
+    if let serenity::FullEvent::GuildMemberAddition { new_member } = event {
+        members::handle_member_addition(new_member, locator).await?
+    }
+
+    if let serenity::FullEvent::GuildRoleCreate { .. }
+    | serenity::FullEvent::GuildRoleUpdate { .. }
+    | serenity::FullEvent::GuildRoleDelete { .. } = event
+    {
+        locator.invalidate_guild_role_cache();
+    }
+
+    // The cache also holds the highest role of the bot, which limits the members it can rename
+    if let serenity::FullEvent::GuildMemberUpdate { event, .. } = event
+        && event.user.id == ctx.cache.current_user().id
+    {
+        locator.invalidate_guild_role_cache();
+    }
+
     Ok(())
 }
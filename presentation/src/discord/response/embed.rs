@@ -0,0 +1,31 @@
+use tracing::instrument;
+
+/// Discord allows at most 1024 characters in an embed field
+pub const MAX_FIELD_LENGTH: usize = 1024;
+
+/// Joins the lines into an embed field value, the lines that do not fit are replaced by an ellipsis
+#[instrument(level = "debug", skip_all)]
+pub fn join_lines(lines: &[String]) -> String {
+    join_field(lines, "\n")
+}
+
+/// Joins the items into an embed field value, the items that do not fit are replaced by an ellipsis
+#[instrument(level = "debug", skip_all)]
+pub fn join_field(items: &[String], separator: &str) -> String {
+    // Reserve space for the separator and the ellipsis
+    let reserved_length = separator.chars().count() + 1;
+
+    let mut joined = String::new();
+    for item in items {
+        if joined.chars().count() + item.chars().count() + reserved_length > MAX_FIELD_LENGTH {
+            joined.push_str(separator);
+            joined.push('…');
+            break;
+        }
+        if !joined.is_empty() {
+            joined.push_str(separator);
+        }
+        joined.push_str(item);
+    }
+    joined
+}
@@ -1,2 +1,6 @@
 pub mod authentication_link;
+pub mod embed;
+pub mod pagination;
+pub mod personal_data;
 pub mod unavailable;
+pub mod unverify;
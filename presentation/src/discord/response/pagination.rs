@@ -0,0 +1,152 @@
+use crate::discord::response::unavailable::{TEMPORARY_UNAVAILABLE_MESSAGE, temporary_unavailable};
+use crate::discord::{Context, Error};
+use poise::CreateReply;
+use poise::serenity_prelude::{
+    ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use std::fmt::Debug;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{instrument, warn};
+
+const PAGE_SIZE: u64 = 10;
+const PAGINATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Items listed page by page
+pub trait Pages {
+    type Page;
+    type Error: Debug;
+
+    fn fetch_page(
+        &mut self,
+        offset: u64,
+        limit: u64,
+    ) -> impl Future<Output = Result<Self::Page, Self::Error>> + Send;
+    /// Count of the items on all the pages
+    fn total(page: &Self::Page) -> u64;
+    fn page_embed(&self, page: Self::Page, page_index: u64, page_count: u64) -> CreateEmbed;
+}
+
+/// Replies with the first page and lets the author of the command switch the pages with buttons
+#[instrument(level = "debug", skip(ctx, pages))]
+pub async fn paginate<D: Sync, P: Pages + Send>(
+    ctx: Context<'_, D>,
+    name: &str,
+    mut pages: P,
+) -> Result<(), Error> {
+    let previous_button_id = format!("{}-{}-previous", ctx.id(), name);
+    let next_button_id = format!("{}-{}-next", ctx.id(), name);
+
+    let mut page = 0;
+    let fetched = match pages.fetch_page(page * PAGE_SIZE, PAGE_SIZE).await {
+        Ok(fetched) => fetched,
+        Err(error) => {
+            warn!(error = ?error, "Failed to fetch the first page of {}", name);
+            ctx.send(temporary_unavailable()).await?;
+            return Ok(());
+        }
+    };
+    let mut page_count = P::total(&fetched).div_ceil(PAGE_SIZE).max(1);
+
+    let reply = CreateReply::default()
+        .reply(true)
+        .ephemeral(true)
+        .embed(pages.page_embed(fetched, page, page_count))
+        .components(pagination(
+            &previous_button_id,
+            &next_button_id,
+            page,
+            page_count,
+        ));
+    let reply_handle = ctx.send(reply).await?;
+
+    if page_count == 1 {
+        return Ok(());
+    }
+
+    loop {
+        let press = {
+            let ctx_id = ctx.id().to_string();
+            ComponentInteractionCollector::new(ctx.serenity_context())
+                .author_id(ctx.author().id)
+                .channel_id(ctx.channel_id())
+                .timeout(PAGINATION_TIMEOUT)
+                .filter(move |press| press.data.custom_id.starts_with(&ctx_id))
+                .await
+        };
+
+        let Some(press) = press else {
+            // The pagination timed out, remove the buttons to prevent stale clicks
+            reply_handle
+                .edit(ctx, CreateReply::default().components(vec![]))
+                .await?;
+            return Ok(());
+        };
+
+        if press.data.custom_id == previous_button_id {
+            page = page.saturating_sub(1);
+        } else if press.data.custom_id == next_button_id {
+            page = (page + 1).min(page_count - 1);
+        }
+
+        let fetched = match pages.fetch_page(page * PAGE_SIZE, PAGE_SIZE).await {
+            Ok(fetched) => fetched,
+            Err(error) => {
+                warn!(error = ?error, "Failed to fetch a page of {}", name);
+                press
+                    .create_response(
+                        ctx.serenity_context(),
+                        CreateInteractionResponse::UpdateMessage(
+                            CreateInteractionResponseMessage::new()
+                                .content(TEMPORARY_UNAVAILABLE_MESSAGE)
+                                .embeds(vec![])
+                                .components(vec![]),
+                        ),
+                    )
+                    .await?;
+                return Ok(());
+            }
+        };
+        page_count = P::total(&fetched).div_ceil(PAGE_SIZE).max(1);
+
+        press
+            .create_response(
+                ctx.serenity_context(),
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(pages.page_embed(fetched, page, page_count))
+                        .components(pagination(
+                            &previous_button_id,
+                            &next_button_id,
+                            page,
+                            page_count,
+                        )),
+                ),
+            )
+            .await?;
+    }
+}
+
+#[instrument(level = "debug")]
+fn pagination(
+    previous_button_id: &str,
+    next_button_id: &str,
+    page: u64,
+    page_count: u64,
+) -> Vec<CreateActionRow> {
+    if page_count <= 1 {
+        return vec![];
+    }
+
+    let previous_button = CreateButton::new(previous_button_id)
+        .style(ButtonStyle::Secondary)
+        .label("Předchozí")
+        .disabled(page == 0);
+    let next_button = CreateButton::new(next_button_id)
+        .style(ButtonStyle::Secondary)
+        .label("Další")
+        .disabled(page + 1 >= page_count);
+
+    vec![CreateActionRow::Buttons(vec![previous_button, next_button])]
+}
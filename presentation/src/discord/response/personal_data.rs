@@ -0,0 +1,212 @@
+use application_ports::nickname::NicknameSettingsDto;
+use application_ports::personal_data::{AuthenticationRequestInfoDto, PersonalDataExportDto};
+use application_ports::role_log::RoleChangeDto;
+use application_ports::role_sync_exemption::RoleSyncExemptionDto;
+use application_ports::user::{
+    ArchivedUserInfoDto, AuthenticatedUserInfoDto, ManualVerificationDto,
+};
+use domain_shared::authentication::UserKind;
+use poise::CreateReply;
+use poise::serenity_prelude::{
+    ButtonStyle, CreateActionRow, CreateAttachment, CreateButton, CreateMessage,
+};
+use serde_json::{Value, json};
+use tracing::instrument;
+
+/// Serializes the export into a JSON attachment named after the user
+#[instrument(level = "debug", skip_all)]
+pub fn export_attachment(export: &PersonalDataExportDto) -> CreateAttachment {
+    let PersonalDataExportDto {
+        user_id,
+        authenticated_user,
+        oauth_token_expires_at,
+        archived_users,
+        authentication_requests,
+        role_changes,
+        nickname_settings,
+        role_sync_exemption,
+    } = export;
+
+    let authenticated_user = authenticated_user.as_ref().map(|user| {
+        let AuthenticatedUserInfoDto {
+            user_id,
+            name,
+            email,
+            kind,
+            class_id,
+            graduation_year,
+            manual_verification,
+            authenticated_at,
+        } = user;
+        json!({
+            "discord_user_id": user_id.0.to_string(),
+            "name": name,
+            "email": email,
+            "kind": match kind {
+                UserKind::Student => "student",
+                UserKind::Staff => "staff",
+            },
+            "class_id": class_id,
+            "graduation_year": graduation_year,
+            "manual_verification": manual_verification.as_ref().map(|ManualVerificationDto { verified_by, reason }| json!({
+                "verified_by": verified_by.0.to_string(),
+                "reason": reason,
+            })),
+            "authenticated_at": authenticated_at.to_rfc3339(),
+            "oauth_token_stored": oauth_token_expires_at.is_some(),
+            "oauth_token_expires_at": oauth_token_expires_at.map(|t| t.to_rfc3339()),
+        })
+    });
+
+    let archived_users = archived_users
+        .iter()
+        .map(|archived_user| {
+            let ArchivedUserInfoDto {
+                user_id,
+                name,
+                email,
+                class_id,
+                authenticated_at,
+                archived_at,
+            } = archived_user;
+            json!({
+                "discord_user_id": user_id.0.to_string(),
+                "name": name,
+                "email": email,
+                "class_id": class_id,
+                "authenticated_at": authenticated_at.to_rfc3339(),
+                "archived_at": archived_at.to_rfc3339(),
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    let authentication_requests = authentication_requests
+        .iter()
+        .map(|request| {
+            let AuthenticationRequestInfoDto {
+                requested_at,
+                expires_at,
+                confirmed_at,
+            } = request;
+            json!({
+                "requested_at": requested_at.to_rfc3339(),
+                "expires_at": expires_at.to_rfc3339(),
+                "confirmed_at": confirmed_at.map(|t| t.to_rfc3339()),
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    let role_changes = role_changes
+        .iter()
+        .map(|role_change| {
+            let RoleChangeDto {
+                assigned,
+                removed,
+                reason,
+                low_priority,
+                changed_at,
+            } = role_change;
+            json!({
+                "assigned_role_ids": assigned.iter().map(|role_id| role_id.0.to_string()).collect::<Vec<_>>(),
+                "removed_role_ids": removed.iter().map(|role_id| role_id.0.to_string()).collect::<Vec<_>>(),
+                "reason": reason,
+                "low_priority": low_priority,
+                "changed_at": changed_at.to_rfc3339(),
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    let nickname_settings = nickname_settings.as_ref().map(|settings| {
+        let NicknameSettingsDto {
+            opt_out,
+            admin_override,
+            applied_nickname,
+        } = settings;
+        json!({
+            "opt_out": opt_out,
+            "admin_override": admin_override,
+            "applied_nickname": applied_nickname,
+        })
+    });
+
+    let role_sync_exemption = role_sync_exemption.as_ref().map(|exemption| {
+        let RoleSyncExemptionDto {
+            target: _,
+            reason,
+            created_by,
+            created_at,
+        } = exemption;
+        json!({
+            "reason": reason,
+            "created_by": created_by.0.to_string(),
+            "created_at": created_at.to_rfc3339(),
+        })
+    });
+
+    let export = json!({
+        "discord_user_id": user_id.0.to_string(),
+        "authenticated_user": authenticated_user,
+        "archived_authenticated_users": archived_users,
+        "authentication_requests": authentication_requests,
+        "role_changes": role_changes,
+        "nickname_settings": nickname_settings,
+        "role_sync_exemption": role_sync_exemption,
+    });
+    let export = serde_json::to_vec_pretty(&export).unwrap_or_default();
+
+    CreateAttachment::bytes(export, format!("personal-data-{}.json", user_id.0))
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn export_message(attachment: CreateAttachment) -> CreateMessage {
+    CreateMessage::default()
+        .content(
+            "Posíláme ti export všech údajů, které o tobě bot uchovává. \
+            Přístupové tokeny ke školnímu účtu export neobsahuje, uvádí pouze, zda jsou uloženy.",
+        )
+        .add_file(attachment)
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn export_sent() -> &'static str {
+    "Export tvých údajů ti byl zaslán do soukromých zpráv."
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn export_not_delivered() -> &'static str {
+    "Export se nepodařilo doručit. Povol si prosím soukromé zprávy od členů serveru a zkus to znovu."
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn erase_confirmation(confirm_button_id: &str, cancel_button_id: &str) -> CreateReply {
+    let response = "Opravdu chceš smazat všechny údaje, které o tobě bot uchovává? \
+        Propojení se školním účtem bude zrušeno, přijdeš o role studenta a smazána bude i historie tvých ověření.";
+
+    let confirm_button = CreateButton::new(confirm_button_id)
+        .style(ButtonStyle::Danger)
+        .label("Smazat údaje");
+    let cancel_button = CreateButton::new(cancel_button_id)
+        .style(ButtonStyle::Secondary)
+        .label("Ponechat");
+
+    let components = vec![CreateActionRow::Buttons(vec![
+        confirm_button,
+        cancel_button,
+    ])];
+
+    CreateReply::default()
+        .content(response)
+        .components(components)
+        .reply(true)
+        .ephemeral(true)
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn erased() -> &'static str {
+    "Tvé údaje byly smazány. Role studenta ti budou během chvíle odebrány."
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn erase_cancelled() -> &'static str {
+    "Tvé údaje zůstávají beze změny."
+}
@@ -1,12 +1,12 @@
 use poise::CreateReply;
 use tracing::instrument;
 
+pub const TEMPORARY_UNAVAILABLE_MESSAGE: &str = "Omlouváme se, služba je momentálně nedostupná. Zkus to prosím později, případně kontaktujte admina.";
+
 #[instrument(level = "debug", skip_all)]
 pub fn temporary_unavailable() -> CreateReply {
-    let response = "Omlouváme se, služba je momentálně nedostupná. Zkus to prosím později, případně kontaktujte admina.";
-
     CreateReply::default()
-        .content(response)
+        .content(TEMPORARY_UNAVAILABLE_MESSAGE)
         .ephemeral(true)
         .reply(true)
 }
@@ -0,0 +1,42 @@
+use poise::CreateReply;
+use poise::serenity_prelude::{ButtonStyle, CreateActionRow, CreateButton};
+use tracing::instrument;
+
+#[instrument(level = "debug", skip_all)]
+pub fn confirmation(confirm_button_id: &str, cancel_button_id: &str) -> CreateReply {
+    let response = "Opravdu chceš zrušit propojení se školním účtem? \
+        Přijdeš o role studenta a pro jejich opětovné získání se budeš muset znovu ověřit.";
+
+    let confirm_button = CreateButton::new(confirm_button_id)
+        .style(ButtonStyle::Danger)
+        .label("Zrušit ověření");
+    let cancel_button = CreateButton::new(cancel_button_id)
+        .style(ButtonStyle::Secondary)
+        .label("Ponechat");
+
+    let components = vec![CreateActionRow::Buttons(vec![
+        confirm_button,
+        cancel_button,
+    ])];
+
+    CreateReply::default()
+        .content(response)
+        .components(components)
+        .reply(true)
+        .ephemeral(true)
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn unverified() -> &'static str {
+    "Propojení se školním účtem bylo zrušeno. Role studenta ti budou během chvíle odebrány."
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn cancelled() -> &'static str {
+    "Ověření zůstává beze změny."
+}
+
+#[instrument(level = "debug", skip_all)]
+pub fn not_verified() -> &'static str {
+    "Nejsi ověřený, není co rušit."
+}
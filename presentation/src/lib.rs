@@ -1,4 +1,5 @@
 pub mod api;
 pub mod application_ports;
 pub mod discord;
+pub mod report;
 pub mod worker;
@@ -0,0 +1,60 @@
+use application_ports::role_reconciliation::{
+    MemberRoleDiffDto, RoleChangeCountDto, RoleReconciliationReportDto,
+};
+use domain_shared::discord::RoleId;
+use std::collections::HashMap;
+use tracing::instrument;
+
+/// Formats the per-member role diffs as CSV, the roles are named when their name is known
+#[instrument(level = "debug", skip_all)]
+pub fn role_reconciliation_csv(report: &RoleReconciliationReportDto) -> String {
+    let role_names = report
+        .role_changes
+        .iter()
+        .filter_map(
+            |RoleChangeCountDto {
+                 role_id, role_name, ..
+             }| role_name.as_deref().map(|role_name| (*role_id, role_name)),
+        )
+        .collect::<HashMap<_, _>>();
+    let format_roles = |role_ids: &[RoleId]| {
+        role_ids
+            .iter()
+            .map(|role_id| match role_names.get(role_id) {
+                Some(role_name) => role_name.to_string(),
+                None => role_id.0.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+
+    let mut csv = String::from("user_id,name,assigned_roles,removed_roles\n");
+    for MemberRoleDiffDto {
+        user_id,
+        name,
+        assigned,
+        removed,
+    } in &report.member_diffs
+    {
+        let row = [
+            user_id.0.to_string(),
+            name.clone().unwrap_or_default(),
+            format_roles(assigned),
+            format_roles(removed),
+        ];
+        let row = row.iter().map(|field| csv_field(field)).collect::<Vec<_>>();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+#[instrument(level = "trace")]
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
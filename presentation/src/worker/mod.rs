@@ -1,9 +1,11 @@
 mod periodic_scheduling_worker;
+mod retention_worker;
 mod role_sync_job;
 mod user_info_sync_job;
 
 use crate::application_ports::Locator;
 use crate::worker::periodic_scheduling_worker::run_periodic_scheduling_worker;
+use crate::worker::retention_worker::run_retention_worker;
 use crate::worker::role_sync_job::run_role_sync_job_handler;
 use crate::worker::user_info_sync_job::run_user_info_sync_job_handler;
 use tracing::instrument;
@@ -25,10 +27,12 @@ pub async fn run_worker<L: Locator + Clone + Send + Sync + 'static>(
         user_info_sync_job_wake_channel,
     ));
     let periodic_scheduling_handle = tokio::spawn(run_periodic_scheduling_worker(locator.clone()));
+    let retention_handle = tokio::spawn(run_retention_worker(locator.clone()));
 
     role_sync_handle.await?;
     user_info_sync_handle.await?;
     periodic_scheduling_handle.await?;
+    retention_handle.await?;
 
     Ok(())
 }
@@ -0,0 +1,19 @@
+use crate::application_ports::Locator;
+use application_ports::retention_handler::{RetentionHandlerError, RetentionHandlerPort};
+use std::time::Duration;
+use tracing::{instrument, warn};
+
+#[instrument(level = "debug", skip(locator))]
+pub async fn run_retention_worker<L: Locator + Send + Sync + 'static>(locator: L) {
+    let handler = locator.create_retention_handler_port();
+    loop {
+        tokio::time::sleep(Duration::from_secs(10 * 60)).await;
+
+        match handler.tick().await {
+            Ok(()) => {}
+            Err(RetentionHandlerError::TemporarilyUnavailable) => {
+                warn!("Retention worker is temporarily unavailable. Trying again in the next run");
+            }
+        }
+    }
+}